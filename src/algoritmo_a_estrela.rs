@@ -1,34 +1,31 @@
-use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use serde::{Serialize, Serializer};
 
-use crate::grafo_metro::{GrafoMetro, IdEstacao, CorLinha, TEMPO_BALDEACAO_MINUTOS};
+use crate::estrategia_busca::EstrategiaBusca;
+use crate::fila_prioridade::{EstatisticasFila, FilaPrioridadeIndexada, ItemFila};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+use crate::heuristica_alt::FonteHeuristica;
+use crate::observador_busca::{ObservadorBusca, ObservadorSilencioso};
+use crate::quadro_horarios::{ConsultaHorarios, HorarioTrecho};
 
-#[derive(Debug, Clone)]
+/// Estado da busca: a estação e a linha pela qual se chegou nela (None na partida sem linha)
+pub type EstadoBusca = (IdEstacao, Option<IdLinha>);
+
+// O caminho até o nó não fica no nó: é reconstruído pelos predecessores de cada estado
+#[derive(Debug, Clone, Serialize)]
 pub struct EstadoNoFronteira {
     pub id_estacao: IdEstacao,
-    pub linha_chegada: Option<CorLinha>,
-    pub custo_f: f32,
+    pub linha_chegada: Option<IdLinha>,
+    pub custo_f: f32, // Prioridade na fronteira, calculada pela estratégia de busca
     pub custo_g_viagem: f32, 
-    pub caminho: Vec<IdEstacao>,
+    pub custo_h: f32,
+    pub trechos: usize, // Conexões percorridas desde a partida
 }
 
 impl EstadoNoFronteira {
-    pub fn debug_print(&self) -> String {
-        format!("E{} (f={:.1}, g={:.1}, h={:.1})", 
-                self.id_estacao + 1, 
-                self.custo_f, 
-                self.custo_g_viagem, 
-                self.custo_f - self.custo_g_viagem)
-    }
-
-    pub fn debug_full(&self) -> String {
-        let caminho_str = self.caminho.iter()
-            .map(|&id| format!("E{}", id+1))
-            .collect::<Vec<_>>()
-            .join(" -> ");
-        
-        format!("Estado: {}, Caminho: {}", self.debug_print(), caminho_str)
+    pub fn estado(&self) -> EstadoBusca {
+        (self.id_estacao, self.linha_chegada)
     }
 }
 
@@ -41,25 +38,101 @@ impl PartialEq for EstadoNoFronteira {
     }
 }
 impl Eq for EstadoNoFronteira {}
-impl Ord for EstadoNoFronteira {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match other.custo_f.partial_cmp(&self.custo_f) {
-            Some(order) => order,
-            None => Ordering::Equal
-        }
+
+// Na fronteira cada estado (estação, linha de chegada) aparece uma vez só, ordenado por f
+// (g + h no A*; cada estratégia define o seu)
+impl ItemFila for EstadoNoFronteira {
+    type Chave = EstadoBusca;
+
+    fn chave(&self) -> Self::Chave {
+        self.estado()
     }
-}
-impl PartialOrd for EstadoNoFronteira {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+
+    fn prioridade(&self) -> f32 {
+        self.custo_f
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct InfoCaminho {
-    pub estacoes_do_caminho: Vec<(IdEstacao, Option<CorLinha>)>, 
-    pub tempo_total_minutos: f32,
+    pub estacoes_do_caminho: Vec<(IdEstacao, Option<IdLinha>)>, 
+    pub linha_partida: Option<IdLinha>, // Linha em que a busca começou na origem, se informada
+    pub tempos_trechos_minutos: Vec<f32>, // Tempo de viagem de cada trecho, sem as baldeações
+    pub tempo_total_minutos: f32, // Com as baldeações e, numa consulta com horário, as esperas
     pub baldeacoes: u32,
+    pub horario_partida: Option<f32>, // Minutos desde a meia-noite, só nas consultas com horário
+    pub horarios_trechos: Vec<HorarioTrecho>, // Um por trecho quando há horário de partida, senão vazio
+}
+
+impl InfoCaminho {
+    /// Itinerário a partir dos estados (estação, linha de chegada) escolhidos pela busca, da partida
+    /// ao objetivo; o primeiro estado traz a linha da partida, se houver
+    pub(crate) fn dos_estados(grafo: &GrafoMetro, mut estados: Vec<EstadoBusca>, tempo_total_minutos: f32) -> Self {
+        let mut tempos_trechos = Vec::with_capacity(estados.len().saturating_sub(1));
+        let mut baldeacoes = 0;
+        
+        // Cada trecho usa a linha do estado de chegada, a mesma que a busca escolheu
+        for par in estados.windows(2) {
+            let ((id_estacao_anterior, linha_anterior), (id_estacao_atual, linha_usada)) = (par[0], par[1]);
+            tempos_trechos.push(grafo.lista_adjacencia[id_estacao_anterior].iter()
+                .filter(|conexao| conexao.para_estacao == id_estacao_atual && Some(conexao.linha) == linha_usada)
+                .map(|conexao| conexao.tempo_minutos)
+                .fold(f32::INFINITY, f32::min));
+            
+            // Mesma regra da expansão: partindo sem linha definida, o primeiro embarque não é baldeação
+            if linha_anterior.is_some() && linha_anterior != linha_usada {
+                baldeacoes += 1;
+            }
+        }
+        
+        // Primeira estação não tem linha de chegada; a linha da partida vai à parte
+        let linha_partida = estados.first_mut().and_then(|primeira| primeira.1.take());
+        
+        InfoCaminho {
+            estacoes_do_caminho: estados,
+            linha_partida,
+            tempos_trechos_minutos: tempos_trechos,
+            tempo_total_minutos,
+            baldeacoes,
+            horario_partida: None,
+            horarios_trechos: Vec::new(),
+        }
+    }
+
+    /// Percorre o itinerário a partir do horário de partida da consulta e preenche os horários de
+    /// cada trecho. Ao embarcar numa linha (na origem ou depois de uma baldeação) espera-se o
+    /// próximo trem; seguir na mesma linha não tem espera. O tempo total passa a incluir as esperas.
+    pub(crate) fn com_horarios(mut self, grafo: &GrafoMetro, consulta: &ConsultaHorarios) -> Self {
+        let mut decorrido = 0.0;
+        let mut linha_atual = self.linha_partida;
+        self.horarios_trechos = self.estacoes_do_caminho.windows(2).zip(&self.tempos_trechos_minutos)
+            .map(|(par, &tempo_trecho)| {
+                let (id_estacao_anterior, linha_usada) = (par[0].0, par[1].1);
+                let mut espera_minutos = 0.0;
+                if linha_atual != linha_usada {
+                    if linha_atual.is_some() {
+                        decorrido += grafo.tempo_baldeacao_em(id_estacao_anterior);
+                    }
+                    if let Some(id_linha) = linha_usada {
+                        espera_minutos = consulta.espera_minutos(id_linha, id_estacao_anterior, decorrido);
+                    }
+                }
+                linha_atual = linha_usada;
+                decorrido += espera_minutos;
+                let partida = consulta.horario_partida + decorrido;
+                decorrido += tempo_trecho;
+                HorarioTrecho { espera_minutos, partida, chegada: consulta.horario_partida + decorrido }
+            })
+            .collect();
+        self.horario_partida = Some(consulta.horario_partida);
+        self.tempo_total_minutos = decorrido;
+        self
+    }
+
+    /// Minutos de espera pelos trens somados ao longo do itinerário
+    pub fn espera_total_minutos(&self) -> f32 {
+        self.horarios_trechos.iter().map(|horario| horario.espera_minutos).sum()
+    }
 }
 
 #[derive(Debug)]
@@ -70,7 +143,10 @@ pub enum ResultadoPassoAEstrela {
     Erro(String),
 }
 
-#[derive(Debug, Clone)]
+/// Micro-passo da busca. Serializado com o nome da variante no campo "tipo", ex.:
+/// `{"tipo": "VerificandoSeEhObjetivo", "id_estacao": 3, "eh_objetivo": false}`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "tipo")]
 pub enum EventoVisual {
     NoEscolhidoDaFronteira {
         id_estacao: IdEstacao,
@@ -83,9 +159,6 @@ pub enum EventoVisual {
         id_estacao: IdEstacao,
         eh_objetivo: bool,
     },
-    NoJaExplorado {
-        id_estacao: IdEstacao,
-    },
     InicioDaExpansaoDeNo {
         id_estacao: IdEstacao,
         numero_vizinhos: usize,
@@ -93,9 +166,10 @@ pub enum EventoVisual {
     AvaliandoVizinho {
         id_estacao_atual: IdEstacao,
         id_vizinho: IdEstacao,
-        linha_conexao: CorLinha,
+        linha_conexao: IdLinha,
         tempo_conexao: f32,
         custo_baldeacao: f32,
+        custo_espera: f32, // Espera pelo trem ao embarcar na linha, só nas consultas com horário
         custo_g_novo: f32,
         custo_h: f32,
         custo_f_novo: f32,
@@ -131,14 +205,15 @@ pub enum EventoVisual {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DetalhesAnalise {
     pub estacao_expandida: IdEstacao,
+    pub ids_vizinhos_analisados: Vec<IdEstacao>, // Mesma ordem de vizinhos_analisados
     pub vizinhos_analisados: Vec<String>,
     pub fronteira_atual: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum StatusEstacao {
     Disponivel,
     SelecionadaParaExpansao,
@@ -146,28 +221,51 @@ pub enum StatusEstacao {
     Explorada,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum EstadoAlgoritmo {
     Inicializado,
     SelecionandoNoDaFronteira,
     VerificandoObjetivo,
-    VerificandoSeJaExplorado,
+    MarcandoComoExplorado,
     IniciandoExpansao,
     AvaliandoVizinho { indice_vizinho: usize },
+    DecidindoVizinho { indice_vizinho: usize, custo_g_novo: f32, custo_h: f32, custo_f_novo: f32 },
     FinalizandoExpansao,
     CaminhoEncontrado,
     SemCaminho,
 }
 
-#[derive(Debug, Clone)]
+/// Serializa um conjunto em ordem crescente, para que a saída não dependa da ordem do hash
+fn serializar_conjunto_ordenado<T: Ord + Serialize, S: Serializer>(conjunto: &HashSet<T>, serializador: S) -> Result<S::Ok, S::Error> {
+    let mut itens: Vec<&T> = conjunto.iter().collect();
+    itens.sort();
+    serializador.collect_seq(itens)
+}
+
+/// Serializa um mapa em ordem crescente de chave. Chaves compostas (estação, linha) não podem
+/// ser chaves de objeto JSON, então o mapa vira uma lista de pares [chave, valor].
+fn serializar_pares_ordenados<K: Ord + Serialize, V: Serialize, S: Serializer>(mapa: &HashMap<K, V>, serializador: S) -> Result<S::Ok, S::Error> {
+    serializador.collect_seq(mapa.iter().collect::<BTreeMap<_, _>>())
+}
+
+fn serializar_mapa_ordenado<K: Ord + Serialize, V: Serialize, S: Serializer>(mapa: &HashMap<K, V>, serializador: S) -> Result<S::Ok, S::Error> {
+    serializador.collect_map(mapa.iter().collect::<BTreeMap<_, _>>())
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct SnapshotEstado {
-    pub fronteira: BinaryHeap<EstadoNoFronteira>,
-    pub explorados: HashSet<(IdEstacao, Option<CorLinha>)>,
-    pub custos_g_viagem_mapa: HashMap<(IdEstacao, Option<CorLinha>), f32>,
-    pub predecessores_info: HashMap<IdEstacao, (IdEstacao, Option<CorLinha>, CorLinha)>,
+    pub fronteira: FilaPrioridadeIndexada<EstadoNoFronteira>, // Serializada na ordem de retirada (menor f primeiro)
+    #[serde(serialize_with = "serializar_conjunto_ordenado")]
+    pub explorados: HashSet<EstadoBusca>,
+    #[serde(serialize_with = "serializar_pares_ordenados")]
+    pub custos_g_viagem_mapa: HashMap<EstadoBusca, f32>,
+    #[serde(serialize_with = "serializar_pares_ordenados")]
+    pub predecessores_info: HashMap<EstadoBusca, EstadoBusca>,
+    #[serde(serialize_with = "serializar_mapa_ordenado")]
     pub status_estacoes: HashMap<IdEstacao, StatusEstacao>,
     pub estacao_sendo_explorada_no_momento: Option<IdEstacao>,
     pub passo_atual: usize,
+    #[serde(serialize_with = "serializar_conjunto_ordenado")]
     pub vizinhos_sendo_analisados: HashSet<IdEstacao>,
     pub estado_atual: EstadoAlgoritmo,
     pub no_atual: Option<EstadoNoFronteira>,
@@ -175,19 +273,24 @@ pub struct SnapshotEstado {
     pub indice_vizinho_atual: usize,
     pub vizinhos_adicionados_neste_passo: usize,
     pub ultima_analise: Option<DetalhesAnalise>,
+    pub ultimo_evento: Option<EventoVisual>,
 }
 
 #[derive(Debug)]
 pub struct SolucionadorAEstrela {
     grafo: Arc<GrafoMetro>,
     id_inicio: IdEstacao,
-    linha_de_partida_busca: Option<CorLinha>,
+    linha_de_partida_busca: Option<IdLinha>,
     id_objetivo: IdEstacao,
-    pub fronteira: BinaryHeap<EstadoNoFronteira>,
-    pub explorados: HashSet<(IdEstacao, Option<CorLinha>)>,
-    custos_g_viagem_mapa: HashMap<(IdEstacao, Option<CorLinha>), f32>,
-    predecessores_info: HashMap<IdEstacao, (IdEstacao, Option<CorLinha>, CorLinha)>,
+    estrategia: EstrategiaBusca,
+    fonte_heuristica: FonteHeuristica,
+    horarios: Option<ConsultaHorarios>,
+    pub fronteira: FilaPrioridadeIndexada<EstadoNoFronteira>,
+    pub explorados: HashSet<EstadoBusca>,
+    custos_g_viagem_mapa: HashMap<EstadoBusca, f32>,
+    predecessores_info: HashMap<EstadoBusca, EstadoBusca>, // Estado -> estado de onde se chegou a ele
     pub ultima_analise: Option<DetalhesAnalise>,
+    pub ultimo_evento: Option<EventoVisual>, // Último micro-passo, para destacar no mapa
     pub status_estacoes: HashMap<IdEstacao, StatusEstacao>,
     pub estacao_sendo_explorada_no_momento: Option<IdEstacao>,
     pub passo_atual: usize,
@@ -201,6 +304,8 @@ pub struct SolucionadorAEstrela {
     
     historico_estados: Vec<SnapshotEstado>,
     max_historico: usize, // Limite máximo de estados no histórico
+
+    observador: Box<dyn ObservadorBusca>,
 }
 
 impl SolucionadorAEstrela {
@@ -210,51 +315,25 @@ impl SolucionadorAEstrela {
     pub fn novo(
         grafo_compartilhado: Arc<GrafoMetro>,
         id_inicio_param: IdEstacao,
-        linha_inicial_opcional: Option<CorLinha>,
+        linha_inicial_opcional: Option<IdLinha>,
         id_objetivo_param: IdEstacao,
     ) -> Self {
         // Cria fronteira: lista ordenada de rotas parciais a serem analisadas
         // A fronteira sempre mantém as rotas mais promissoras no topo
-        let mut fronteira_heap = BinaryHeap::new();
-        let mut custos_g_map = HashMap::new();
-
-        // Calcula estimativa inicial (heurística h): tempo estimado até destino
-        // É como calcular "distância em linha reta" convertida para tempo
-        let custo_h_inicial = grafo_compartilhado
-            .obter_tempo_heuristico_minutos(id_inicio_param, id_objetivo_param)
-            .unwrap_or(0.0);
-
-        // Custo real de viagem (g): zero no ponto de partida
-        let custo_g_viagem_inicial = 0.0;
-        // Custo total estimado (f): soma do real + estimativa (f = g + h)
-        let custo_f_inicial = custo_g_viagem_inicial + custo_h_inicial;
-
-        // Inicializa o caminho percorrido com apenas a estação de partida
-        let mut caminho_inicial = Vec::new();
-        caminho_inicial.push(id_inicio_param);
-
-        // Adiciona ponto de partida na fronteira como primeira rota a ser analisada
-        // Esta é a única opção inicial para começar a busca
-        fronteira_heap.push(EstadoNoFronteira {
-            id_estacao: id_inicio_param,
-            linha_chegada: linha_inicial_opcional,
-            custo_f: custo_f_inicial,
-            custo_g_viagem: custo_g_viagem_inicial,
-            caminho: caminho_inicial, // Caminho inicial contém só a origem
-        });
-        
-        custos_g_map.insert((id_inicio_param, linha_inicial_opcional), custo_g_viagem_inicial);
-
-        Self {
+        let mut solucionador = Self {
             grafo: grafo_compartilhado,
             id_inicio: id_inicio_param,
             linha_de_partida_busca: linha_inicial_opcional,
             id_objetivo: id_objetivo_param,
-            fronteira: fronteira_heap,
+            estrategia: EstrategiaBusca::default(),
+            fonte_heuristica: FonteHeuristica::default(),
+            horarios: None,
+            fronteira: FilaPrioridadeIndexada::nova(),
             explorados: HashSet::new(),
-            custos_g_viagem_mapa: custos_g_map,
+            custos_g_viagem_mapa: HashMap::new(),
             predecessores_info: HashMap::new(),
             ultima_analise: None, // Inicializar como None
+            ultimo_evento: None,
             status_estacoes: HashMap::new(), // Inicializar vazio
             estacao_sendo_explorada_no_momento: None, // Inicializar como None
             passo_atual: 0, // Inicializar contador de passos
@@ -270,18 +349,111 @@ impl SolucionadorAEstrela {
             // Inicializar campos de histórico
             historico_estados: Vec::new(),
             max_historico: 50, // Limitar a 50 passos para evitar uso excessivo de memória
-        }
+
+            observador: Box::new(ObservadorSilencioso),
+        };
+        solucionador.inserir_no_inicial();
+        solucionador
+    }
+
+    /// Coloca a partida na fronteira como única rota a ser analisada
+    fn inserir_no_inicial(&mut self) {
+        // Calcula estimativa inicial (heurística h): tempo estimado até destino
+        // É como calcular "distância em linha reta" convertida para tempo
+        let custo_h_inicial = self.heuristica(self.id_inicio);
+
+        // Custo real de viagem (g): zero no ponto de partida
+        let custo_g_viagem_inicial = 0.0;
+        // Custo total estimado (f): no A*, soma do real + estimativa (f = g + h)
+        let custo_f_inicial = self.estrategia.prioridade(custo_g_viagem_inicial, custo_h_inicial, 0);
+
+        self.fronteira.inserir_ou_diminuir(EstadoNoFronteira {
+            id_estacao: self.id_inicio,
+            linha_chegada: self.linha_de_partida_busca,
+            custo_f: custo_f_inicial,
+            custo_g_viagem: custo_g_viagem_inicial,
+            custo_h: custo_h_inicial,
+            trechos: 0,
+        });
+        self.custos_g_viagem_mapa.insert((self.id_inicio, self.linha_de_partida_busca), custo_g_viagem_inicial);
+    }
+
+    /// Troca a estratégia que ordena a fronteira (A* por padrão) e recomeça a busca
+    pub fn com_estrategia(mut self, estrategia: EstrategiaBusca) -> Self {
+        self.estrategia = estrategia;
+        self.reiniciar();
+        self
+    }
+
+    pub fn estrategia(&self) -> EstrategiaBusca {
+        self.estrategia
+    }
+
+    /// Troca a fonte da estimativa h (linha reta por padrão) e recomeça a busca
+    pub fn com_heuristica(mut self, fonte_heuristica: FonteHeuristica) -> Self {
+        self.fonte_heuristica = fonte_heuristica;
+        self.reiniciar();
+        self
+    }
+
+    pub fn fonte_heuristica(&self) -> &FonteHeuristica {
+        &self.fonte_heuristica
+    }
+
+    /// Parte no horário da consulta e passa a contar a espera pelo próximo trem a cada embarque
+    /// (o custo g vira o tempo decorrido desde a partida) e recomeça a busca
+    pub fn com_horarios(mut self, horarios: ConsultaHorarios) -> Self {
+        self.horarios = Some(horarios);
+        self.reiniciar();
+        self
+    }
+
+    pub fn horarios(&self) -> Option<&ConsultaHorarios> {
+        self.horarios.as_ref()
+    }
+
+    /// Estimativa de tempo até o objetivo; 0 quando a fonte não permite calculá-la
+    fn heuristica(&self, id_estacao: IdEstacao) -> f32 {
+        self.fonte_heuristica.estimar_minutos(&self.grafo, id_estacao, self.id_objetivo)
+    }
+
+    /// Troca o observador que recebe os eventos da busca (silencioso por padrão)
+    pub fn com_observador(mut self, observador: Box<dyn ObservadorBusca>) -> Self {
+        self.observador = observador;
+        self
+    }
+
+    /// Descarrega a saída do observador e devolve o primeiro erro de escrita, se houver
+    pub fn finalizar_observador(&mut self) -> std::io::Result<()> {
+        self.observador.finalizar()
+    }
+
+    fn notificar(&mut self, evento: &EventoVisual) {
+        self.observador.notificar(&self.grafo, evento);
     }
 
     // PARTE 2: BUSCA INTELIGENTE - Núcleo do algoritmo A* (versão orientada a eventos)
     // Esta função gera um evento visual de cada vez, permitindo controle fino da visualização
     // A GUI chama este método a cada clique do usuário para obter o próximo micro-passo
     pub fn proximo_evento(&mut self) -> EventoVisual {
-        match &self.estado_atual {
+        let evento = self.gerar_proximo_evento();
+        self.notificar(&evento);
+        self.ultimo_evento = Some(evento.clone());
+        evento
+    }
+
+    /// Um micro-passo que pode ser desfeito com `passo_anterior`, como os passos completos
+    pub fn proximo_micro_passo(&mut self) -> EventoVisual {
+        self.salvar_estado_no_historico();
+        self.proximo_evento()
+    }
+
+    // Único motor da busca: `proximo_evento` devolve cada micro-passo e `proximo_passo`
+    // avança até o fim da expansão de um nó, então os dois modos sempre concordam
+    fn gerar_proximo_evento(&mut self) -> EventoVisual {
+        match self.estado_atual.clone() {
             EstadoAlgoritmo::Inicializado => {
-                let custo_h_inicial = self.grafo
-                    .obter_tempo_heuristico_minutos(self.id_inicio, self.id_objetivo)
-                    .unwrap_or(0.0);
+                let custo_h_inicial = self.heuristica(self.id_inicio);
                 
                 self.estado_atual = EstadoAlgoritmo::SelecionandoNoDaFronteira;
                 
@@ -293,20 +465,22 @@ impl SolucionadorAEstrela {
             },
             
             EstadoAlgoritmo::SelecionandoNoDaFronteira => {
-                if let Some(no_da_fronteira_atual) = self.fronteira.pop() {
-                    let custo_h = no_da_fronteira_atual.custo_f - no_da_fronteira_atual.custo_g_viagem;
-                    
-                    // Armazenar o nó atual para próximos passos
-                    self.no_atual = Some(no_da_fronteira_atual.clone());
-                    self.estado_atual = EstadoAlgoritmo::VerificandoObjetivo;
+                if let Some(no_da_fronteira_atual) = self.fronteira.remover_minimo() {
+                    let custo_h = no_da_fronteira_atual.custo_h;
+                    self.passo_atual += 1;
                     
-                    EventoVisual::NoEscolhidoDaFronteira {
+                    let evento = EventoVisual::NoEscolhidoDaFronteira {
                         id_estacao: no_da_fronteira_atual.id_estacao,
                         custo_f: no_da_fronteira_atual.custo_f,
                         custo_g: no_da_fronteira_atual.custo_g_viagem,
                         custo_h,
-                        caminho_ate_aqui: no_da_fronteira_atual.caminho.clone(),
-                    }
+                        caminho_ate_aqui: self.caminho_ate(no_da_fronteira_atual.estado()),
+                    };
+                    
+                    // Armazenar o nó atual para próximos passos
+                    self.no_atual = Some(no_da_fronteira_atual);
+                    self.estado_atual = EstadoAlgoritmo::VerificandoObjetivo;
+                    evento
                 } else {
                     self.estado_atual = EstadoAlgoritmo::SemCaminho;
                     EventoVisual::NenhumCaminho
@@ -314,189 +488,184 @@ impl SolucionadorAEstrela {
             },
             
             EstadoAlgoritmo::VerificandoObjetivo => {
-                let no_atual = self.no_atual.as_ref().unwrap();
-                let eh_objetivo = no_atual.id_estacao == self.id_objetivo;
+                let id_estacao = self.no_atual.as_ref().unwrap().id_estacao;
                 
-                if eh_objetivo {
-                    self.estado_atual = EstadoAlgoritmo::CaminhoEncontrado;
+                // CONDIÇÃO DE PARADA: Verificar se chegamos ao objetivo
+                let eh_objetivo = id_estacao == self.id_objetivo;
+                self.estado_atual = if eh_objetivo {
+                    EstadoAlgoritmo::CaminhoEncontrado
                 } else {
-                    self.estado_atual = EstadoAlgoritmo::VerificandoSeJaExplorado;
-                }
+                    EstadoAlgoritmo::MarcandoComoExplorado
+                };
                 
-                EventoVisual::VerificandoSeEhObjetivo {
-                    id_estacao: no_atual.id_estacao,
-                    eh_objetivo,
-                }
+                EventoVisual::VerificandoSeEhObjetivo { id_estacao, eh_objetivo }
             },
             
-            EstadoAlgoritmo::VerificandoSeJaExplorado => {
+            EstadoAlgoritmo::MarcandoComoExplorado => {
                 let no_atual = self.no_atual.as_ref().unwrap();
-                let estado_atual = (no_atual.id_estacao, no_atual.linha_chegada);
-                let ja_explorado = self.explorados.contains(&estado_atual);
+                let id_estacao = no_atual.id_estacao;
+                let estado_atual = (id_estacao, no_atual.linha_chegada);
                 
-                if ja_explorado {
-                    // Pular para seleção do próximo nó
-                    self.estado_atual = EstadoAlgoritmo::SelecionandoNoDaFronteira;
-                    EventoVisual::NoJaExplorado {
-                        id_estacao: no_atual.id_estacao,
-                    }
-                } else {
-                    // Marcar como explorado e preparar para expansão
-                    self.explorados.insert(estado_atual);
-                    self.status_estacoes.insert(no_atual.id_estacao, StatusEstacao::SelecionadaParaExpansao);
-                    self.estacao_sendo_explorada_no_momento = Some(no_atual.id_estacao);
-                    
-                    // Preparar lista de vizinhos
-                    if let Some(conexoes) = self.grafo.lista_adjacencia.get(no_atual.id_estacao) {
-                        self.vizinhos_atuais = conexoes.clone();
-                    } else {
-                        self.vizinhos_atuais = Vec::new();
-                    }
-                    self.indice_vizinho_atual = 0;
-                    self.vizinhos_adicionados_neste_passo = 0;
-                    
-                    self.estado_atual = EstadoAlgoritmo::IniciandoExpansao;
-                    
-                    EventoVisual::InicioDaExpansaoDeNo {
-                        id_estacao: no_atual.id_estacao,
-                        numero_vizinhos: self.vizinhos_atuais.len(),
-                    }
+                // Cada estado (estação, linha) entra na fronteira uma vez só e um estado explorado
+                // nunca volta para ela (ver AvaliandoVizinho), então o nó retirado nunca é obsoleto
+                let novo = self.explorados.insert(estado_atual);
+                debug_assert!(novo, "estado {:?} retirado da fronteira depois de explorado", estado_atual);
+                
+                // Preparar para expansão
+                self.status_estacoes.insert(id_estacao, StatusEstacao::SelecionadaParaExpansao);
+                self.estacao_sendo_explorada_no_momento = Some(id_estacao);
+                
+                // Preparar lista de vizinhos e limpar a análise do passo anterior
+                self.vizinhos_atuais = self.grafo.lista_adjacencia.get(id_estacao).cloned().unwrap_or_default();
+                self.indice_vizinho_atual = 0;
+                self.vizinhos_adicionados_neste_passo = 0;
+                self.vizinhos_sendo_analisados.clear();
+                self.ultima_analise = Some(DetalhesAnalise {
+                    estacao_expandida: id_estacao,
+                    ids_vizinhos_analisados: Vec::new(),
+                    vizinhos_analisados: Vec::new(),
+                    fronteira_atual: Vec::new(),
+                });
+                
+                self.estado_atual = EstadoAlgoritmo::IniciandoExpansao;
+                
+                EventoVisual::InicioDaExpansaoDeNo {
+                    id_estacao,
+                    numero_vizinhos: self.vizinhos_atuais.len(),
                 }
             },
             
             EstadoAlgoritmo::IniciandoExpansao => {
                 // Atualizar status visual
-                let no_atual = self.no_atual.as_ref().unwrap();
-                self.status_estacoes.insert(no_atual.id_estacao, StatusEstacao::ExpandindoVizinhos);
+                let id_estacao = self.no_atual.as_ref().unwrap().id_estacao;
+                self.status_estacoes.insert(id_estacao, StatusEstacao::ExpandindoVizinhos);
                 
-                if self.vizinhos_atuais.is_empty() {
-                    self.estado_atual = EstadoAlgoritmo::FinalizandoExpansao;
-                    return self.proximo_evento(); // Chama recursivamente para ir direto ao fim da expansão
-                } else {
-                    self.estado_atual = EstadoAlgoritmo::AvaliandoVizinho { indice_vizinho: 0 };
-                    return self.proximo_evento(); // Chama recursivamente para avaliar o primeiro vizinho
-                }
+                // Vai direto ao primeiro vizinho (ou ao fim da expansão, se não houver vizinhos)
+                self.estado_atual = EstadoAlgoritmo::AvaliandoVizinho { indice_vizinho: 0 };
+                self.gerar_proximo_evento()
             },
             
             EstadoAlgoritmo::AvaliandoVizinho { indice_vizinho } => {
-                let indice = *indice_vizinho;
-                
-                if indice >= self.vizinhos_atuais.len() {
+                if indice_vizinho >= self.vizinhos_atuais.len() {
                     // Terminou de avaliar todos os vizinhos
                     self.estado_atual = EstadoAlgoritmo::FinalizandoExpansao;
-                    return self.proximo_evento();
+                    return self.gerar_proximo_evento();
                 }
+                self.indice_vizinho_atual = indice_vizinho;
                 
-                let conexao = &self.vizinhos_atuais[indice];
+                let conexao = self.vizinhos_atuais[indice_vizinho].clone();
                 let no_atual = self.no_atual.as_ref().unwrap();
+                let id_estacao_atual = no_atual.id_estacao;
                 let id_vizinho = conexao.para_estacao;
-                let estado_vizinho = (id_vizinho, Some(conexao.cor_linha));
+                let estado_vizinho = (id_vizinho, Some(conexao.linha));
                 
-                // Verificar se vizinho já foi explorado
-                if self.explorados.contains(&estado_vizinho) {
-                    self.estado_atual = EstadoAlgoritmo::AvaliandoVizinho { 
-                        indice_vizinho: indice + 1 
-                    };
-                    return EventoVisual::VizinhoJaExplorado { id_vizinho };
-                }
-                
-                // Calcular custos
-                let custo_baldeacao = if let Some(linha_atual) = no_atual.linha_chegada {
-                    if linha_atual != conexao.cor_linha {
-                        TEMPO_BALDEACAO_MINUTOS
-                    } else {
-                        0.0
+                // Calcular custos para este vizinho
+                let custo_baldeacao = match no_atual.linha_chegada {
+                    Some(linha_atual) if linha_atual != conexao.linha => self.grafo.tempo_baldeacao_em(id_estacao_atual),
+                    _ => 0.0,
+                };
+                // Com horário, embarcar numa linha (sem linha atual ou trocando) espera o próximo trem
+                let custo_espera = match &self.horarios {
+                    Some(horarios) if no_atual.linha_chegada != Some(conexao.linha) => {
+                        horarios.espera_minutos(conexao.linha, id_estacao_atual, no_atual.custo_g_viagem + custo_baldeacao)
                     }
-                } else {
-                    0.0
+                    _ => 0.0,
                 };
+                let custo_g_novo = no_atual.custo_g_viagem + custo_baldeacao + custo_espera + conexao.tempo_minutos;
+                let custo_h = self.heuristica(id_vizinho);
+                let custo_f_novo = self.estrategia.prioridade(custo_g_novo, custo_h, no_atual.trechos + 1);
                 
-                let custo_g_novo = no_atual.custo_g_viagem + conexao.tempo_minutos + custo_baldeacao;
-                let custo_h = self.grafo.obter_tempo_heuristico_minutos(id_vizinho, self.id_objetivo)
-                    .unwrap_or(0.0);
-                let custo_f_novo = custo_g_novo + custo_h;
-                
-                // Verificar se já existe caminho melhor - VERSÃO CORRIGIDA CONFORME LITERATURA A*
-                let mut ja_tem_melhor_caminho = false;
-                let mut custo_g_existente = f32::INFINITY;
+                self.vizinhos_sendo_analisados.insert(id_vizinho);
                 
-                // 1. Verificar no mapa de custos g (nós já processados)
-                if let Some(&custo_g_registrado) = self.custos_g_viagem_mapa.get(&estado_vizinho) {
-                    if custo_g_registrado <= custo_g_novo {
-                        ja_tem_melhor_caminho = true;
-                        custo_g_existente = custo_g_registrado;
-                    }
-                }
-                
-                // 2. Verificar na fronteira (implementação otimizada)
-                // Nota: Em uma implementação mais eficiente, usaríamos um HashMap adicional 
-                // para mapear estados para nós na fronteira, evitando busca linear
-                if !ja_tem_melhor_caminho {
-                    for no_fronteira in self.fronteira.iter() {
-                        if no_fronteira.id_estacao == id_vizinho && 
-                           no_fronteira.linha_chegada == Some(conexao.cor_linha) &&
-                           no_fronteira.custo_g_viagem <= custo_g_novo {
-                            ja_tem_melhor_caminho = true;
-                            custo_g_existente = no_fronteira.custo_g_viagem;
-                            break;
-                        }
-                    }
+                // Vizinho já explorado: mostrar os valores originais salvos, com os trechos do caminho
+                // pelo qual ele foi alcançado (não os do nó atual)
+                if self.explorados.contains(&estado_vizinho) {
+                    let custo_g_original = self.custos_g_viagem_mapa.get(&estado_vizinho).copied().unwrap_or(custo_g_novo);
+                    let trechos_originais = self.estados_do_caminho(estado_vizinho).len() - 1;
+                    let custo_f_original = self.estrategia.prioridade(custo_g_original, custo_h, trechos_originais);
+                    self.registrar_vizinho_analisado(id_vizinho, custo_g_original, custo_h, custo_f_original, "JÁ EXPLORADO");
+                    self.estado_atual = EstadoAlgoritmo::AvaliandoVizinho { indice_vizinho: indice_vizinho + 1 };
+                    return EventoVisual::VizinhoJaExplorado { id_vizinho };
                 }
                 
+                // Na próxima chamada o vizinho é adicionado na fronteira ou descartado
+                self.estado_atual = EstadoAlgoritmo::DecidindoVizinho { indice_vizinho, custo_g_novo, custo_h, custo_f_novo };
                 
-                self.estado_atual = EstadoAlgoritmo::AvaliandoVizinho { 
-                    indice_vizinho: indice + 1 
-                };
-                
-                // Primeiro retorna o evento de avaliação com os custos calculados
-                let evento_avaliacao = EventoVisual::AvaliandoVizinho {
-                    id_estacao_atual: no_atual.id_estacao,
+                EventoVisual::AvaliandoVizinho {
+                    id_estacao_atual,
                     id_vizinho,
-                    linha_conexao: conexao.cor_linha,
+                    linha_conexao: conexao.linha,
                     tempo_conexao: conexao.tempo_minutos,
                     custo_baldeacao,
+                    custo_espera,
                     custo_g_novo,
                     custo_h,
                     custo_f_novo,
-                };
+                }
+            },
+            
+            EstadoAlgoritmo::DecidindoVizinho { indice_vizinho, custo_g_novo, custo_h, custo_f_novo } => {
+                let conexao = &self.vizinhos_atuais[indice_vizinho];
+                let id_vizinho = conexao.para_estacao;
+                let linha_conexao = conexao.linha;
+                let estado_vizinho = (id_vizinho, Some(linha_conexao));
+                self.estado_atual = EstadoAlgoritmo::AvaliandoVizinho { indice_vizinho: indice_vizinho + 1 };
                 
-                if ja_tem_melhor_caminho {
-                    // Na próxima chamada, retornará VizinhoJaTemCaminhoMelhor
-                    return evento_avaliacao;
-                } else {
-                    // Adicionar na fronteira - CONFORME LITERATURA A*
-                    self.custos_g_viagem_mapa.insert(estado_vizinho, custo_g_novo);
-                    self.predecessores_info.insert(
-                        id_vizinho, 
-                        (no_atual.id_estacao, no_atual.linha_chegada, conexao.cor_linha)
-                    );
-                    
-                    let mut novo_caminho = no_atual.caminho.clone();
-                    novo_caminho.push(id_vizinho);
-                    
-                    let novo_no = EstadoNoFronteira {
-                        id_estacao: id_vizinho,
-                        linha_chegada: Some(conexao.cor_linha),
-                        custo_f: custo_f_novo,
-                        custo_g_viagem: custo_g_novo,
-                        caminho: novo_caminho.clone(),
-                    };
-                    
-                    self.fronteira.push(novo_no);
-                    self.vizinhos_adicionados_neste_passo += 1;
-                    
-                    // Na próxima chamada, retornará VizinhoAdicionadoNaFronteira
-                    return evento_avaliacao;
+                // Verificar se a fronteira já tem este estado com prioridade menor ou igual (consulta pelo índice).
+                // No A*, no Dijkstra e no A* ponderado o h do estado é o mesmo, então isso equivale a comparar g
+                let no_existente = self.fronteira.obter(&estado_vizinho)
+                    .filter(|no| no.custo_f <= custo_f_novo)
+                    .map(|no| (no.custo_g_viagem, no.custo_f));
+                
+                if let Some((custo_g_existente, custo_f_existente)) = no_existente {
+                    // Mostrar os valores do caminho melhor existente, não os novos calculados
+                    self.registrar_vizinho_analisado(id_vizinho, custo_g_existente, custo_h, custo_f_existente, "CAMINHO MELHOR JÁ EXISTE");
+                    return EventoVisual::VizinhoJaTemCaminhoMelhor { id_vizinho, custo_g_existente, custo_g_novo };
+                }
+                
+                // Adicionar na fronteira - CONFORME LITERATURA A*
+                let no_atual = self.no_atual.as_ref().unwrap();
+                let (estado_atual, trechos) = (no_atual.estado(), no_atual.trechos + 1);
+                self.predecessores_info.insert(estado_vizinho, estado_atual);
+                let custo_f = custo_f_novo;
+                
+                self.custos_g_viagem_mapa.insert(estado_vizinho, custo_g_novo);
+                // Se o estado já está na fronteira com g maior, o nó é atualizado no lugar (decrease-key)
+                self.fronteira.inserir_ou_diminuir(EstadoNoFronteira {
+                    id_estacao: id_vizinho,
+                    linha_chegada: Some(linha_conexao),
+                    custo_f,
+                    custo_g_viagem: custo_g_novo,
+                    custo_h,
+                    trechos,
+                });
+                self.vizinhos_adicionados_neste_passo += 1;
+                self.registrar_vizinho_analisado(id_vizinho, custo_g_novo, custo_h, custo_f, "ADICIONADO");
+                
+                EventoVisual::VizinhoAdicionadoNaFronteira {
+                    id_vizinho,
+                    custo_f,
+                    custo_g: custo_g_novo,
+                    custo_h,
+                    novo_caminho: self.caminho_ate(estado_vizinho),
                 }
             },
             
             EstadoAlgoritmo::FinalizandoExpansao => {
-                let no_atual = self.no_atual.as_ref().unwrap();
-                self.status_estacoes.insert(no_atual.id_estacao, StatusEstacao::Explorada);
+                let id_estacao = self.no_atual.as_ref().unwrap().id_estacao;
+                self.status_estacoes.insert(id_estacao, StatusEstacao::Explorada);
                 self.estado_atual = EstadoAlgoritmo::SelecionandoNoDaFronteira;
                 
+                // Capturar os 5 melhores nós da fronteira para o painel de análise
+                let fronteira_atual = self.fronteira.ordenados().iter().take(5)
+                    .map(|no| format!("{}: f={:.1}", self.nome(no.id_estacao), no.custo_f))
+                    .collect();
+                if let Some(analise) = self.ultima_analise.as_mut() {
+                    analise.fronteira_atual = fronteira_atual;
+                }
+                
                 EventoVisual::FimDaExpansaoDeNo {
-                    id_estacao: no_atual.id_estacao,
+                    id_estacao,
                     vizinhos_adicionados: self.vizinhos_adicionados_neste_passo,
                     tamanho_fronteira: self.fronteira.len(),
                 }
@@ -515,397 +684,92 @@ impl SolucionadorAEstrela {
         }
     }
 
+    /// Acrescenta uma linha ao painel de análise da expansão em andamento
+    fn registrar_vizinho_analisado(&mut self, id_vizinho: IdEstacao, custo_g: f32, custo_h: f32, custo_f: f32, situacao: &str) {
+        let descricao = format!("{}: g={:.1}, h={:.1}, f={:.1} - {}",
+                                self.nome(id_vizinho), custo_g, custo_h, custo_f, situacao);
+        if let Some(analise) = self.ultima_analise.as_mut() {
+            analise.ids_vizinhos_analisados.push(id_vizinho);
+            analise.vizinhos_analisados.push(descricao);
+        }
+    }
+
+    // Passo completo: avança o mesmo motor de `proximo_evento` até terminar a expansão de um nó,
+    // encontrar o objetivo ou esgotar a fronteira
     pub fn proximo_passo(&mut self) -> ResultadoPassoAEstrela {
         // Salvar estado atual no histórico antes de fazer modificações
         self.salvar_estado_no_historico();
         
-        self.passo_atual += 1;
-        println!("\n=== PASSO {} ===", self.passo_atual);
-        
-        // Continuar com o algoritmo normal
-        if let Some(no_da_fronteira_atual) = self.fronteira.pop() {
-            println!("SELECIONANDO: Estação E{} (f={:.1}, g={:.1}, h={:.1})", 
-                     no_da_fronteira_atual.id_estacao + 1,
-                     no_da_fronteira_atual.custo_f,
-                     no_da_fronteira_atual.custo_g_viagem,
-                     no_da_fronteira_atual.custo_f - no_da_fronteira_atual.custo_g_viagem);
-            
-            // CONDIÇÃO DE PARADA: Verificar se chegamos ao objetivo
-            if no_da_fronteira_atual.id_estacao == self.id_objetivo {
-                let info_caminho = self.criar_info_caminho_do_no(&no_da_fronteira_atual);
-                return ResultadoPassoAEstrela::CaminhoEncontrado(info_caminho);
-            }
-            
-            // Ignorar estações já exploradas - CORRIGIDO PARA USAR ESTADO COMPLETO
-            if self.explorados.contains(&(no_da_fronteira_atual.id_estacao, no_da_fronteira_atual.linha_chegada)) {
-                println!("  Estação E{} já explorada, pulando.", no_da_fronteira_atual.id_estacao + 1);
-                return ResultadoPassoAEstrela::EmProgresso;
+        loop {
+            match self.proximo_evento() {
+                EventoVisual::CaminhoEncontrado { info_caminho } => return ResultadoPassoAEstrela::CaminhoEncontrado(info_caminho),
+                EventoVisual::NenhumCaminho => return ResultadoPassoAEstrela::NenhumCaminhoPossivel,
+                EventoVisual::FimDaExpansaoDeNo { .. } => return ResultadoPassoAEstrela::EmProgresso,
+                _ => continue,
             }
-            
-            // Atualizar status para "selecionada para expansão"
-            self.status_estacoes.insert(no_da_fronteira_atual.id_estacao, StatusEstacao::SelecionadaParaExpansao);
-            self.estacao_sendo_explorada_no_momento = Some(no_da_fronteira_atual.id_estacao);
-            
-            // Marcar como explorada - USANDO ESTADO COMPLETO
-            self.explorados.insert((no_da_fronteira_atual.id_estacao, no_da_fronteira_atual.linha_chegada));
-            
-            // Limpar vizinhos sendo analisados do passo anterior
-            self.vizinhos_sendo_analisados.clear();
-            
-            // Prepara estruturas para armazenar detalhes da análise
-            let mut vizinhos_analisados = Vec::new();
-            let mut fronteira_atual = Vec::new();
-            
-            // EXPANSÃO: Analisa todas as estações vizinhas (conexões diretas)
-            if let Some(conexoes) = self.grafo.lista_adjacencia.get(no_da_fronteira_atual.id_estacao) {
-                for conexao in conexoes {
-                    let id_vizinho = conexao.para_estacao;
-                    
-                    // Adicionar à lista de vizinhos sendo analisados
-                    self.vizinhos_sendo_analisados.insert(id_vizinho);
-                    
-                    // Calcular custos para este vizinho (sempre, para fins educativos)
-                    let custo_baldeacao = if let Some(linha_atual) = no_da_fronteira_atual.linha_chegada {
-                        if linha_atual != conexao.cor_linha {
-                            println!("      Adicionando custo de baldeação: +{}min", TEMPO_BALDEACAO_MINUTOS);
-                            TEMPO_BALDEACAO_MINUTOS
-                        } else {
-                            0.0
-                        }
-                    } else {
-                        0.0
-                    };
-                    
-                    let custo_g_novo = no_da_fronteira_atual.custo_g_viagem + conexao.tempo_minutos + custo_baldeacao;
-                    let custo_h = self.grafo.obter_tempo_heuristico_minutos(id_vizinho, self.id_objetivo)
-                        .unwrap_or(0.0);
-                    let custo_f = custo_g_novo + custo_h;
-                    
-                    // Verificar se já foi explorado - mostrar valores originais salvos
-                    let estado_vizinho = (id_vizinho, Some(conexao.cor_linha));
-                    if self.explorados.contains(&estado_vizinho) {
-                        println!("    Ignorando E{}: já explorado", id_vizinho + 1);
-                        
-                        // Buscar os valores originais salvos no mapa de custos
-                        let custo_g_original = self.custos_g_viagem_mapa.get(&estado_vizinho).copied().unwrap_or(custo_g_novo);
-                        let custo_h_original = self.grafo.obter_tempo_heuristico_minutos(id_vizinho, self.id_objetivo).unwrap_or(0.0);
-                        let custo_f_original = custo_g_original + custo_h_original;
-                        
-                        vizinhos_analisados.push(format!("E{}: g={:.1}, h={:.1}, f={:.1} - JÁ EXPLORADO", 
-                                                         id_vizinho + 1, custo_g_original, custo_h_original, custo_f_original));
-                        continue;
-                    }
-                    
-                    println!("      Analisando E{}: g={:.1}, h={:.1}, f={:.1}", 
-                             id_vizinho + 1, custo_g_novo, custo_h, custo_f);
-                    
-                    // Verificar se já existe um caminho melhor - CORRIGIDO CONFORME LITERATURA A*
-                    let mut ja_tem_melhor_caminho = false;
-                    let mut custo_g_melhor_existente = custo_g_novo;
-                    
-                    // Verificar no mapa de custos g
-                    if let Some(&custo_g_registrado) = self.custos_g_viagem_mapa.get(&estado_vizinho) {
-                        if custo_g_registrado <= custo_g_novo {
-                            ja_tem_melhor_caminho = true;
-                            custo_g_melhor_existente = custo_g_registrado;
-                        }
-                    }
-                    
-                    // Verificar na fronteira
-                    if !ja_tem_melhor_caminho {
-                        for no_fronteira in self.fronteira.iter() {
-                            if no_fronteira.id_estacao == id_vizinho && 
-                               no_fronteira.linha_chegada == Some(conexao.cor_linha) &&
-                               no_fronteira.custo_g_viagem <= custo_g_novo {
-                                ja_tem_melhor_caminho = true;
-                                custo_g_melhor_existente = no_fronteira.custo_g_viagem;
-                                break;
-                            }
-                        }
-                    }
-                    
-                    if !ja_tem_melhor_caminho {
-                        // Registrar este novo caminho - USANDO ESTADO COMPLETO
-                        self.custos_g_viagem_mapa.insert(estado_vizinho, custo_g_novo);
-                        self.predecessores_info.insert(
-                            id_vizinho, 
-                            (no_da_fronteira_atual.id_estacao, no_da_fronteira_atual.linha_chegada, conexao.cor_linha)
-                        );
-                        
-                        // Criar novo caminho
-                        let mut novo_caminho = no_da_fronteira_atual.caminho.clone();
-                        novo_caminho.push(id_vizinho);
-                        
-                        // Adicionar na fronteira
-                        let novo_no = EstadoNoFronteira {
-                            id_estacao: id_vizinho,
-                            linha_chegada: Some(conexao.cor_linha),
-                            custo_f: custo_f,
-                            custo_g_viagem: custo_g_novo,
-                            caminho: novo_caminho,
-                        };
-                        
-                        self.fronteira.push(novo_no);
-                        vizinhos_analisados.push(format!("E{}: g={:.1}, h={:.1}, f={:.1} - ADICIONADO", 
-                                                         id_vizinho + 1, custo_g_novo, custo_h, custo_f));
-                    } else {
-                        // Mostrar os valores do caminho melhor existente, não os novos calculados
-                        let custo_h_melhor = self.grafo.obter_tempo_heuristico_minutos(id_vizinho, self.id_objetivo).unwrap_or(0.0);
-                        let custo_f_melhor = custo_g_melhor_existente + custo_h_melhor;
-                        vizinhos_analisados.push(format!("E{}: g={:.1}, h={:.1}, f={:.1} - CAMINHO MELHOR JÁ EXISTE", 
-                                                         id_vizinho + 1, custo_g_melhor_existente, custo_h_melhor, custo_f_melhor));
-                    }
-                }
-            }
-            
-            // Atualizar status visual para "expandindo vizinhos"
-            self.status_estacoes.insert(no_da_fronteira_atual.id_estacao, StatusEstacao::ExpandindoVizinhos);
-            
-            // Capturar estado atual da fronteira
-            let mut nodes_fronteira: Vec<_> = self.fronteira.iter().collect();
-            nodes_fronteira.sort_by(|a, b| a.custo_f.partial_cmp(&b.custo_f)
-                .unwrap_or(std::cmp::Ordering::Equal));
-            
-            for node in nodes_fronteira.iter().take(5) {
-                fronteira_atual.push(format!("E{}: f={:.1}", node.id_estacao + 1, node.custo_f));
-            }
-            
-            // Armazenar detalhes da análise
-            self.ultima_analise = Some(DetalhesAnalise {
-                estacao_expandida: no_da_fronteira_atual.id_estacao,
-                vizinhos_analisados,
-                fronteira_atual,
-            });
-            
-            self.debug_print_fronteira();
-            return ResultadoPassoAEstrela::EmProgresso;
         }
-        
-        ResultadoPassoAEstrela::NenhumCaminhoPossivel
+    }
+
+    /// Estados do caminho que a busca registrou até `estado_final`, da partida até ele
+    fn estados_do_caminho(&self, estado_final: EstadoBusca) -> Vec<EstadoBusca> {
+        let mut estados = vec![estado_final];
+        while let Some(&anterior) = self.predecessores_info.get(estados.last().unwrap()) {
+            estados.push(anterior);
+        }
+        estados.reverse();
+        estados
+    }
+
+    /// Estações do caminho até o estado, como nos eventos da busca
+    pub fn caminho_ate(&self, estado: EstadoBusca) -> Vec<IdEstacao> {
+        self.estados_do_caminho(estado).into_iter().map(|(id_estacao, _)| id_estacao).collect()
     }
 
     // PARTE 3: APRESENTAÇÃO DO RESULTADO - Constrói o itinerário final detalhado
     // Esta função é chamada quando o destino é alcançado
-    // Reconstrói o caminho encontrado e calcula tempo total e baldeações
+    // Reconstrói o caminho pelos predecessores; linhas, baldeações e tempo são os que a busca escolheu
     fn criar_info_caminho_do_no(&self, no_final: &EstadoNoFronteira) -> InfoCaminho {
-        let mut estacoes_com_linhas = Vec::new();
-        let mut tempo_total = 0.0;
-        let mut baldeacoes = 0;
-        let _linha_atual: Option<CorLinha> = self.linha_de_partida_busca;
-        
-        println!("\nDETALHES DO CAMINHO ENCONTRADO:");
-        let caminho_str = no_final.caminho.iter()
-            .map(|&id| format!("E{}", id+1))
-            .collect::<Vec<_>>()
-            .join(" -> ");
-        println!("Caminho: {}", caminho_str);
-        
-        // Primeira estação não tem linha de chegada
-        if !no_final.caminho.is_empty() {
-            estacoes_com_linhas.push((no_final.caminho[0], None));
-        }
-        
-        // Processa cada trecho do caminho para calcular tempos e identificar baldeações
-        for i in 1..no_final.caminho.len() {
-            let id_estacao_atual = no_final.caminho[i];
-            let id_estacao_anterior = no_final.caminho[i-1];
-            
-            let mut linha_usada: Option<CorLinha> = None;
-            #[allow(unused_assignments)]
-            let mut tempo_conexao = 0.0;
-            
-            println!("  {}: E{} -> E{} verificando conexão direta...",
-                   i, id_estacao_anterior + 1, id_estacao_atual + 1);
-            
-            // Busca a conexão específica entre as duas estações
-            if let Some(conexoes) = self.grafo.lista_adjacencia.get(id_estacao_anterior) {
-                for conexao in conexoes {
-                    if conexao.para_estacao == id_estacao_atual {
-                        linha_usada = Some(conexao.cor_linha);
-                        tempo_conexao = conexao.tempo_minutos;
-                        
-                        tempo_total += tempo_conexao;
-                        
-                        // Verifica se houve mudança de linha (baldeação)
-                        if i > 1 {
-                            let linha_anterior = estacoes_com_linhas[i-1].1;
-                            if linha_anterior != linha_usada {
-                                baldeacoes += 1;
-                                tempo_total += TEMPO_BALDEACAO_MINUTOS;
-                                println!("  Baldeação em E{}: {:?} -> {:?} (+{}min)",
-                                       id_estacao_anterior + 1, linha_anterior, linha_usada, TEMPO_BALDEACAO_MINUTOS);
-                            }
-                        }
-                        
-                        println!("    Encontrada conexão direta: via linha {:?}, tempo={:.1}min",
-                               linha_usada.unwrap_or(CorLinha::Nenhuma), tempo_conexao);
-                        
-                        println!("  E{} -> E{} | Linha: {:?} | Tempo: {:.1}min | Total: {:.1}min",
-                               id_estacao_anterior + 1, id_estacao_atual + 1, 
-                               linha_usada.unwrap_or(CorLinha::Nenhuma), 
-                               tempo_conexao, tempo_total);
-                        
-                        break;
-                    }
-                }
-            } else {
-                println!("  ERRO: Nenhuma conexão encontrada de E{} para E{}!",
-                       id_estacao_anterior + 1, id_estacao_atual + 1);
-            }
-            
-            estacoes_com_linhas.push((id_estacao_atual, linha_usada));
-        }
-        
-        // Formata e exibe o resultado final
-        let horas = (tempo_total as i32) / 60;
-        let minutos = (tempo_total as i32) % 60;
-        println!("Número de baldeações: {}", baldeacoes);
-        println!("Tempo total: {} h {} min ({:.1} min)", horas, minutos, tempo_total);
-        
-        // Retorna estrutura com informações completas do itinerário
-        InfoCaminho {
-            estacoes_do_caminho: estacoes_com_linhas,
-            tempo_total_minutos: tempo_total,
-            baldeacoes,
+        let caminho = InfoCaminho::dos_estados(&self.grafo, self.estados_do_caminho(no_final.estado()), no_final.custo_g_viagem);
+        match &self.horarios {
+            Some(horarios) => caminho.com_horarios(&self.grafo, horarios),
+            None => caminho,
         }
     }
 
-    // Função auxiliar para depuração da fronteira atual
-    pub fn debug_print_fronteira(&self) {
-        println!("\nFRONTEIRA ATUAL (ordenada por f-cost crescente):");
+    /// Descreve os nós da fronteira em ordem crescente de f (até 10), para depuração
+    pub fn descrever_fronteira(&self) -> String {
+        let nodes = self.fronteira.ordenados();
         
-        // Ordena nós por f-cost para entender as decisões do algoritmo
-        let mut nodes: Vec<_> = self.fronteira.iter().collect();
-        nodes.sort_by(|a, b| a.custo_f.partial_cmp(&b.custo_f)
-            .unwrap_or(Ordering::Equal));
-        
-        for (idx, node) in nodes.iter().enumerate().take(10) {  // Mostra apenas os 10 melhores
-            let caminho_str = node.caminho.iter()
-                .map(|&id| format!("E{}", id+1))
+        let mut linhas = vec!["FRONTEIRA ATUAL (ordenada por f-cost crescente):".to_string()];
+        for (idx, node) in nodes.iter().enumerate().take(10) {
+            let caminho_str = self.caminho_ate(node.estado()).iter()
+                .map(|&id| self.codigo(id))
                 .collect::<Vec<_>>()
                 .join(" -> ");
                 
-            println!("  {}. f={:.1} g={:.1} h={:.1} | E{} | {}",
+            linhas.push(format!("  {}. f={:.1} g={:.1} h={:.1} | {} | {}",
                      idx + 1, 
                      node.custo_f,
                      node.custo_g_viagem,
-                     node.custo_f - node.custo_g_viagem,
-                     node.id_estacao + 1,
-                     caminho_str);
+                     node.custo_h,
+                     self.codigo(node.id_estacao),
+                     caminho_str));
         }
         
         if nodes.len() > 10 {
-            println!("  ... e mais {} nós", nodes.len() - 10);
+            linhas.push(format!("  ... e mais {} nós", nodes.len() - 10));
         }
-        println!();
+        linhas.join("\n")
     }
 
-    // Método especial para verificar o caminho direto
-    pub fn check_direct_path(&self) {
-        println!("\nVERIFICANDO CAMINHO DIRETO E6 -> E5 -> E4 -> E13:");
-        let path = vec![5, 4, 3, 12]; // E6->E5->E4->E13 (índices base-zero)
-        let mut total_time = 0.0;
-        let mut transfers = 0;
-        let mut last_line: Option<CorLinha> = None;
-        
-        for i in 0..(path.len()-1) {
-            let from = path[i];
-            let to = path[i+1];
-            let edge = self.grafo.lista_adjacencia[from].iter()
-                .find(|c| c.para_estacao == to);
-            
-            if let Some(conn) = edge {
-                total_time += conn.tempo_minutos;
-                
-                if let Some(prev_line) = last_line {
-                    if prev_line != conn.cor_linha {
-                        transfers += 1;
-                        total_time += TEMPO_BALDEACAO_MINUTOS;
-                        println!("  Baldeação em E{}: {:?} -> {:?} (+4min)",
-                                 from+1, prev_line, conn.cor_linha);
-                    }
-                }
-                
-                println!("  E{} -> E{}: Linha {:?}, {:.1}min (total: {:.1}min)",
-                         from+1, to+1, conn.cor_linha, conn.tempo_minutos, total_time);
-                
-                last_line = Some(conn.cor_linha);
-            } else {
-                println!("  ERRO: Conexão não encontrada entre E{} e E{}", from+1, to+1);
-            }
-        }
-        
-        println!("Caminho E6 -> E5 -> E4 -> E13:");
-        println!("  Tempo total: {:.1}min ({} h {} min)", 
-                 total_time, 
-                 (total_time as i32) / 60, 
-                 (total_time as i32) % 60);
-        println!("  Baldeações: {}", transfers);
+    fn codigo(&self, id_estacao: IdEstacao) -> &str {
+        &self.grafo.estacoes[id_estacao].codigo
     }
 
-    pub fn validar_rota_especifica(&self) {
-        let estacoes = [5, 4, 3, 12]; // Índices zero-based 
-        let mut tempo_total = 0.0;
-        let mut baldeacoes = 0;
-        let mut linha_anterior: Option<CorLinha> = None;
-        
-        println!("\n=== VALIDAÇÃO DA ROTA E6 -> E5 -> E4 -> E13 ===");
-        
-        for i in 0..estacoes.len()-1 {
-            let id_origem = estacoes[i];
-            let id_destino = estacoes[i+1];
-            
-            // Encontra a conexão correta
-            let mut conexao_tempo = 0.0;
-            let mut cor_linha = CorLinha::Nenhuma;
-            let mut encontrou = false;
-            
-            if let Some(conexoes) = self.grafo.lista_adjacencia.get(id_origem) {
-                for conexao in conexoes {
-                    if conexao.para_estacao == id_destino {
-                        conexao_tempo = conexao.tempo_minutos;
-                        cor_linha = conexao.cor_linha;
-                        encontrou = true;
-                        break;
-                    }
-                }
-            }
-            
-            if encontrou {
-                tempo_total += conexao_tempo;
-                
-                // Verificar baldeação
-                if let Some(linha) = linha_anterior {
-                    if linha != cor_linha {
-                        baldeacoes += 1;
-                        tempo_total += TEMPO_BALDEACAO_MINUTOS;
-                        println!("  Baldeação em E{}: {:?} -> {:?} (+{}min)",
-                               id_origem + 1, linha, cor_linha, TEMPO_BALDEACAO_MINUTOS);
-                    }
-                }
-                
-                println!("  E{} -> E{}: Linha {:?}, Tempo={:.1}min, Total acumulado={:.1}min",
-                       id_origem + 1, id_destino + 1, cor_linha, conexao_tempo, tempo_total);
-                
-                linha_anterior = Some(cor_linha);
-            } else {
-                println!("  ERRO: Não existe conexão direta de E{} para E{}!",
-                       id_origem + 1, id_destino + 1);
-            }
-        }
-        
-        println!("TEMPO TOTAL: {:.1}min ({} h {} min)", 
-               tempo_total, 
-               (tempo_total as i32) / 60, 
-               (tempo_total as i32) % 60);
-        println!("BALDEAÇÕES: {}", baldeacoes);
-        println!("=================================================\n");
+    fn nome(&self, id_estacao: IdEstacao) -> &str {
+        &self.grafo.estacoes[id_estacao].nome
     }
-    
-    pub fn verificar_dados(&self) {
-        self.validar_rota_especifica();
-    }
-    
+
     // Obtém status de uma estação
     pub fn obter_status_estacao(&self, id_estacao: IdEstacao) -> StatusEstacao {
         self.status_estacoes.get(&id_estacao).cloned().unwrap_or(StatusEstacao::Disponivel)
@@ -930,38 +794,27 @@ impl SolucionadorAEstrela {
         self.estacao_sendo_explorada_no_momento = None;
         self.passo_atual = 0;
         self.vizinhos_sendo_analisados.clear();
+        self.ultimo_evento = None;
         
         // Limpar histórico de estados
         self.historico_estados.clear();
         
         // Reconstruir fronteira inicial
-        self.fronteira.clear();
+        self.fronteira.limpar();
         self.custos_g_viagem_mapa.clear();
         self.predecessores_info.clear();
         
-        let custo_h_inicial = self.grafo
-            .obter_tempo_heuristico_minutos(self.id_inicio, self.id_objetivo)
-            .unwrap_or(0.0);
-        let custo_g_viagem_inicial = 0.0;
-        let custo_f_inicial = custo_g_viagem_inicial + custo_h_inicial;
-        
-        let mut caminho_inicial = Vec::new();
-        caminho_inicial.push(self.id_inicio);
-        
-        self.fronteira.push(EstadoNoFronteira {
-            id_estacao: self.id_inicio,
-            linha_chegada: self.linha_de_partida_busca,
-            custo_f: custo_f_inicial,
-            custo_g_viagem: custo_g_viagem_inicial,
-            caminho: caminho_inicial,
-        });
-        
-        self.custos_g_viagem_mapa.insert((self.id_inicio, self.linha_de_partida_busca), custo_g_viagem_inicial);
+        self.inserir_no_inicial();
     }
     
-    /// Salva o estado atual no histórico antes de fazer modificações
-    fn salvar_estado_no_historico(&mut self) {
-        let snapshot = SnapshotEstado {
+    /// Inserções, diminuições de chave e remoções feitas na fronteira desde o início da busca
+    pub fn estatisticas_fronteira(&self) -> EstatisticasFila {
+        self.fronteira.estatisticas()
+    }
+
+    /// Cópia do estado atual da busca (o mesmo guardado no histórico para voltar passos)
+    pub fn snapshot(&self) -> SnapshotEstado {
+        SnapshotEstado {
             fronteira: self.fronteira.clone(),
             explorados: self.explorados.clone(),
             custos_g_viagem_mapa: self.custos_g_viagem_mapa.clone(),
@@ -976,8 +829,13 @@ impl SolucionadorAEstrela {
             indice_vizinho_atual: self.indice_vizinho_atual,
             vizinhos_adicionados_neste_passo: self.vizinhos_adicionados_neste_passo,
             ultima_analise: self.ultima_analise.clone(),
-        };
-        
+            ultimo_evento: self.ultimo_evento.clone(),
+        }
+    }
+
+    /// Salva o estado atual no histórico antes de fazer modificações
+    fn salvar_estado_no_historico(&mut self) {
+        let snapshot = self.snapshot();
         self.historico_estados.push(snapshot);
         
         // Limitar o tamanho do histórico para evitar uso excessivo de memória
@@ -1003,6 +861,7 @@ impl SolucionadorAEstrela {
             self.indice_vizinho_atual = snapshot.indice_vizinho_atual;
             self.vizinhos_adicionados_neste_passo = snapshot.vizinhos_adicionados_neste_passo;
             self.ultima_analise = snapshot.ultima_analise;
+            self.ultimo_evento = snapshot.ultimo_evento;
             
             true // Sucesso ao voltar
         } else {
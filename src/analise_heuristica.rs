@@ -0,0 +1,236 @@
+use crate::algoritmo_a_estrela::{ResultadoPassoAEstrela, SolucionadorAEstrela};
+use crate::grafo_metro::*;
+use crate::heuristica_alt::FonteHeuristica;
+use crate::validacao_rede::tempos_minimos_a_partir;
+use std::fmt;
+use std::sync::Arc;
+
+/// Folga para comparar tempos calculados em f32
+const TOLERANCIA: f32 = 1e-3;
+
+/// Par (origem, objetivo) em que a heurística passa do menor tempo real
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViolacaoAdmissibilidade {
+    pub origem: String,
+    pub destino: String,
+    pub heuristica_minutos: f32,
+    pub tempo_real_minutos: f32,
+}
+
+/// Conexão u -> v em que h(u) > c(u, v) + h(v) para um objetivo
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViolacaoConsistencia {
+    pub origem: String,
+    pub vizinho: String,
+    pub objetivo: String,
+    pub linha: String,
+    pub heuristica_origem_minutos: f32,
+    pub custo_conexao_minutos: f32,
+    pub heuristica_vizinho_minutos: f32,
+}
+
+/// Resultado da análise da heurística sobre todos os pares de estações.
+///
+/// A admissibilidade é verificada contra dois custos reais: com as penalidades de baldeação
+/// (o custo que o A* minimiza) e sem elas (só o tempo das conexões). Uma heurística que respeita
+/// o segundo continua admissível mesmo que as penalidades mudem.
+#[derive(Debug, Clone, Default)]
+pub struct AnaliseHeuristica {
+    pub pares_avaliados: usize,
+    pub inadmissiveis_com_baldeacao: Vec<ViolacaoAdmissibilidade>,
+    pub inadmissiveis_sem_baldeacao: Vec<ViolacaoAdmissibilidade>,
+    pub conexoes_avaliadas: usize,
+    pub inconsistencias: Vec<ViolacaoConsistencia>,
+    /// Média de h / custo real (com baldeação) nos pares com custo positivo: quanto mais perto de 1,
+    /// mais informativa a heurística e menos nós o A* expande
+    pub razao_media: f32,
+}
+
+impl AnaliseHeuristica {
+    pub fn eh_admissivel(&self) -> bool {
+        self.inadmissiveis_com_baldeacao.is_empty()
+    }
+
+    pub fn eh_consistente(&self) -> bool {
+        self.inconsistencias.is_empty()
+    }
+}
+
+impl fmt::Display for AnaliseHeuristica {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sim_nao = |valor: bool| if valor { "sim" } else { "NÃO" };
+        writeln!(f, "Análise da heurística ({} pares, {} conexões)", self.pares_avaliados, self.conexoes_avaliadas)?;
+        writeln!(f, "  Admissível (com baldeações): {} ({} violações)",
+            sim_nao(self.eh_admissivel()), self.inadmissiveis_com_baldeacao.len())?;
+        writeln!(f, "  Admissível (sem baldeações): {} ({} violações)",
+            sim_nao(self.inadmissiveis_sem_baldeacao.is_empty()), self.inadmissiveis_sem_baldeacao.len())?;
+        writeln!(f, "  Consistente: {} ({} violações)", sim_nao(self.eh_consistente()), self.inconsistencias.len())?;
+        write!(f, "  Razão média h / h*: {:.3}", self.razao_media)?;
+
+        for violacao in &self.inadmissiveis_com_baldeacao {
+            write!(f, "\n  h({} -> {}) = {:.2} min > custo real {:.2} min",
+                violacao.origem, violacao.destino, violacao.heuristica_minutos, violacao.tempo_real_minutos)?;
+        }
+        for violacao in &self.inconsistencias {
+            write!(f, "\n  objetivo {}: h({}) = {:.2} > c({} -> {}, linha {}) + h({}) = {:.2} + {:.2}",
+                violacao.objetivo, violacao.origem, violacao.heuristica_origem_minutos,
+                violacao.origem, violacao.vizinho, violacao.linha, violacao.vizinho,
+                violacao.custo_conexao_minutos, violacao.heuristica_vizinho_minutos)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compara a heurística do grafo com os menores tempos reais entre todos os pares de estações
+/// e verifica a desigualdade triangular h(u) <= c(u, v) + h(v) em todas as conexões
+pub fn analisar_heuristica(grafo: &GrafoMetro) -> AnaliseHeuristica {
+    analisar_fonte_heuristica(grafo, &FonteHeuristica::LinhaReta)
+}
+
+/// Mesma análise de `analisar_heuristica` para qualquer fonte de h (linha reta ou ALT)
+pub fn analisar_fonte_heuristica(grafo: &GrafoMetro, fonte: &FonteHeuristica) -> AnaliseHeuristica {
+    let mut analise = AnaliseHeuristica::default();
+    let codigo = |id: IdEstacao| grafo.estacoes[id].codigo.clone();
+    let mut soma_razoes = 0.0;
+    let mut pares_com_custo = 0;
+
+    for id_origem in 0..grafo.numero_estacoes() {
+        let tempos_com_baldeacao = tempos_minimos_a_partir(grafo, id_origem, true);
+        let tempos_sem_baldeacao = tempos_minimos_a_partir(grafo, id_origem, false);
+
+        for id_destino in 0..grafo.numero_estacoes() {
+            let (Some(tempo_com), Some(tempo_sem)) = (tempos_com_baldeacao[id_destino], tempos_sem_baldeacao[id_destino]) else {
+                continue;
+            };
+            // Sem valor na tabela a busca usa h = 0, que é sempre admissível
+            let heuristica = fonte.estimar_minutos(grafo, id_origem, id_destino);
+            analise.pares_avaliados += 1;
+
+            if tempo_com > 0.0 {
+                soma_razoes += heuristica / tempo_com;
+                pares_com_custo += 1;
+            }
+            let violacao = |tempo_real_minutos| ViolacaoAdmissibilidade {
+                origem: codigo(id_origem),
+                destino: codigo(id_destino),
+                heuristica_minutos: heuristica,
+                tempo_real_minutos,
+            };
+            if heuristica > tempo_com + TOLERANCIA {
+                analise.inadmissiveis_com_baldeacao.push(violacao(tempo_com));
+            }
+            if heuristica > tempo_sem + TOLERANCIA {
+                analise.inadmissiveis_sem_baldeacao.push(violacao(tempo_sem));
+            }
+        }
+    }
+
+    for (id_origem, conexoes) in grafo.lista_adjacencia.iter().enumerate() {
+        for conexao in conexoes {
+            analise.conexoes_avaliadas += 1;
+            for id_objetivo in 0..grafo.numero_estacoes() {
+                let heuristica_origem = fonte.estimar_minutos(grafo, id_origem, id_objetivo);
+                let heuristica_vizinho = fonte.estimar_minutos(grafo, conexao.para_estacao, id_objetivo);
+                if heuristica_origem > conexao.tempo_minutos + heuristica_vizinho + TOLERANCIA {
+                    analise.inconsistencias.push(ViolacaoConsistencia {
+                        origem: codigo(id_origem),
+                        vizinho: codigo(conexao.para_estacao),
+                        objetivo: codigo(id_objetivo),
+                        linha: grafo.linhas.get(conexao.linha).map_or_else(|| "?".to_string(), |linha| linha.codigo.clone()),
+                        heuristica_origem_minutos: heuristica_origem,
+                        custo_conexao_minutos: conexao.tempo_minutos,
+                        heuristica_vizinho_minutos: heuristica_vizinho,
+                    });
+                }
+            }
+        }
+    }
+
+    if pares_com_custo > 0 {
+        analise.razao_media = soma_razoes / pares_com_custo as f32;
+    }
+    analise
+}
+
+/// Estados expandidos pelo A* com duas fontes de heurística, somados sobre todos os pares de estações
+#[derive(Debug, Clone, Default)]
+pub struct ComparacaoHeuristicas {
+    pub nome_base: String,
+    pub nome_alternativa: String,
+    pub pares_avaliados: usize,
+    pub expandidos_base: usize,
+    pub expandidos_alternativa: usize,
+    /// Pares em que a alternativa expandiu menos / mais estados que a base
+    pub pares_com_menos_expansoes: usize,
+    pub pares_com_mais_expansoes: usize,
+    /// Pares em que as rotas encontradas têm tempos diferentes (nenhum, se as duas forem admissíveis)
+    pub pares_com_tempo_diferente: usize,
+}
+
+impl ComparacaoHeuristicas {
+    /// Fração de expansões economizada pela alternativa (negativa se ela expandir mais)
+    pub fn reducao(&self) -> f32 {
+        if self.expandidos_base == 0 {
+            return 0.0;
+        }
+        1.0 - self.expandidos_alternativa as f32 / self.expandidos_base as f32
+    }
+}
+
+impl fmt::Display for ComparacaoHeuristicas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let media = |total: usize| if self.pares_avaliados == 0 { 0.0 } else { total as f32 / self.pares_avaliados as f32 };
+        writeln!(f, "Comparação de heurísticas ({} pares)", self.pares_avaliados)?;
+        writeln!(f, "  {}: {} estados expandidos ({:.1} por busca)", self.nome_base, self.expandidos_base, media(self.expandidos_base))?;
+        writeln!(f, "  {}: {} estados expandidos ({:.1} por busca)",
+            self.nome_alternativa, self.expandidos_alternativa, media(self.expandidos_alternativa))?;
+        writeln!(f, "  Redução: {:.1}% | menos expansões em {} pares, mais em {}",
+            self.reducao() * 100.0, self.pares_com_menos_expansoes, self.pares_com_mais_expansoes)?;
+        write!(f, "  Rotas com tempo diferente: {}", self.pares_com_tempo_diferente)
+    }
+}
+
+/// Roda o A* entre todos os pares de estações distintas com as duas heurísticas e compara
+/// quantos estados cada uma expandiu
+pub fn comparar_heuristicas(grafo: &Arc<GrafoMetro>, base: &FonteHeuristica, alternativa: &FonteHeuristica) -> ComparacaoHeuristicas {
+    let buscar = |fonte: &FonteHeuristica, origem: IdEstacao, destino: IdEstacao| {
+        let mut solucionador = SolucionadorAEstrela::novo(Arc::clone(grafo), origem, None, destino).com_heuristica(fonte.clone());
+        loop {
+            match solucionador.proximo_passo() {
+                ResultadoPassoAEstrela::EmProgresso => continue,
+                ResultadoPassoAEstrela::CaminhoEncontrado(caminho) => return (Some(caminho.tempo_total_minutos), solucionador.explorados.len()),
+                _ => return (None, solucionador.explorados.len()),
+            }
+        }
+    };
+
+    let mut comparacao = ComparacaoHeuristicas {
+        nome_base: base.nome().to_string(),
+        nome_alternativa: alternativa.nome().to_string(),
+        ..Default::default()
+    };
+    for origem in 0..grafo.numero_estacoes() {
+        for destino in (0..grafo.numero_estacoes()).filter(|&destino| destino != origem) {
+            let (tempo_base, expandidos_base) = buscar(base, origem, destino);
+            let (tempo_alternativa, expandidos_alternativa) = buscar(alternativa, origem, destino);
+
+            comparacao.pares_avaliados += 1;
+            comparacao.expandidos_base += expandidos_base;
+            comparacao.expandidos_alternativa += expandidos_alternativa;
+            if expandidos_alternativa < expandidos_base {
+                comparacao.pares_com_menos_expansoes += 1;
+            } else if expandidos_alternativa > expandidos_base {
+                comparacao.pares_com_mais_expansoes += 1;
+            }
+            let tempos_iguais = match (tempo_base, tempo_alternativa) {
+                (Some(a), Some(b)) => (a - b).abs() <= TOLERANCIA,
+                (None, None) => true,
+                _ => false,
+            };
+            if !tempos_iguais {
+                comparacao.pares_com_tempo_diferente += 1;
+            }
+        }
+    }
+    comparacao
+}
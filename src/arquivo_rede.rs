@@ -0,0 +1,387 @@
+use crate::grafo_metro::*;
+use crate::dados_metro::parsear_cor_hex;
+use crate::erro_carregamento::{AvisoCarregamento, ColetorAvisos, ErroCarregamento, LocalDados, ModoCarregamento};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Versão mais recente do formato; arquivos com versão maior são recusados
+pub const VERSAO_ARQUIVO_REDE: u32 = 1;
+
+/// Formatos aceitos para o arquivo de rede, escolhidos pela extensão
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatoArquivoRede {
+    Json,
+    Toml,
+}
+
+impl FormatoArquivoRede {
+    pub fn do_caminho(caminho: &str) -> Result<Self, ErroCarregamento> {
+        match Path::new(caminho).extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("json") => Ok(FormatoArquivoRede::Json),
+            Some("toml") => Ok(FormatoArquivoRede::Toml),
+            _ => Err(ErroCarregamento::formato(caminho, "extensão desconhecida (use .json ou .toml)")),
+        }
+    }
+}
+
+/// Documento único com toda a rede: linhas, estações, conexões e heurística.
+/// As estações, linhas e conexões se referenciam pelos códigos, não pelos ids internos.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArquivoRede {
+    pub versao: u32,
+    #[serde(default = "tempo_baldeacao_padrao")]
+    pub tempo_baldeacao_minutos: f64,
+    #[serde(default)]
+    pub linhas: Vec<LinhaArquivo>,
+    #[serde(default)]
+    pub estacoes: Vec<EstacaoArquivo>,
+    #[serde(default)]
+    pub conexoes: Vec<ConexaoArquivo>,
+    /// Distâncias em linha reta usadas pela heurística
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distancias_heuristicas: Vec<DistanciaHeuristicaArquivo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinhaArquivo {
+    pub codigo: String,
+    pub nome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cor: Option<String>, // "#RRGGBB"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocidade_kmh: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EstacaoArquivo {
+    pub codigo: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nome: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_km: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y_km: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zona: Option<u8>,
+    #[serde(default, skip_serializing_if = "eh_falso")]
+    pub acessivel: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tempo_baldeacao_minutos: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConexaoArquivo {
+    pub de: String,
+    pub para: String,
+    pub linha: String,
+    pub distancia_km: f64,
+    /// Se ausente, calculado pela velocidade da linha
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tempo_minutos: Option<f64>,
+    /// Cria também a conexão de volta, com os mesmos valores
+    #[serde(default, skip_serializing_if = "eh_falso")]
+    pub bidirecional: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DistanciaHeuristicaArquivo {
+    pub de: String,
+    pub para: String,
+    pub distancia_km: f64,
+    /// Vale também de `para` até `de` (o padrão); `false` nas tabelas assimétricas
+    #[serde(default = "verdadeiro", skip_serializing_if = "eh_verdadeiro")]
+    pub bidirecional: bool,
+}
+
+fn tempo_baldeacao_padrao() -> f64 {
+    f64::from(TEMPO_BALDEACAO_MINUTOS)
+}
+
+fn eh_falso(valor: &bool) -> bool {
+    !*valor
+}
+
+fn verdadeiro() -> bool {
+    true
+}
+
+fn eh_verdadeiro(valor: &bool) -> bool {
+    *valor
+}
+
+/// Converte pelo texto para que 2.1f32 seja escrito como 2.1, e não 2.0999999046325684
+fn f32_para_f64(valor: f32) -> f64 {
+    valor.to_string().parse().unwrap_or(f64::from(valor))
+}
+
+fn cor_para_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+impl GrafoMetro {
+    /// Carrega uma rede a partir de um arquivo .json ou .toml
+    pub fn carregar_arquivo_rede(
+        &mut self,
+        caminho_arquivo: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let formato = FormatoArquivoRede::do_caminho(caminho_arquivo)?;
+        let conteudo = std::fs::read_to_string(caminho_arquivo).map_err(ErroCarregamento::io(caminho_arquivo))?;
+        self.carregar_arquivo_rede_de_texto(&conteudo, formato, caminho_arquivo, modo_carregamento)
+    }
+
+    /// Carrega a rede de um documento já lido; `origem` só identifica o documento nos erros e avisos
+    pub fn carregar_arquivo_rede_de_texto(
+        &mut self,
+        conteudo: &str,
+        formato: FormatoArquivoRede,
+        origem: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let arquivo: ArquivoRede = match formato {
+            FormatoArquivoRede::Json => serde_json::from_str(conteudo).map_err(|e| ErroCarregamento::formato(origem, e))?,
+            FormatoArquivoRede::Toml => toml::from_str(conteudo).map_err(|e| ErroCarregamento::formato(origem, e))?,
+        };
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        self.aplicar_arquivo_rede(&arquivo, origem, &mut coletor)?;
+        Ok(coletor.concluir())
+    }
+
+    /// Escreve a rede no formato indicado pela extensão do arquivo
+    pub fn salvar_arquivo_rede(&self, caminho_arquivo: &str) -> Result<(), ErroCarregamento> {
+        let formato = FormatoArquivoRede::do_caminho(caminho_arquivo)?;
+        let conteudo = self.arquivo_rede_para_texto(formato).map_err(|e| ErroCarregamento::formato(caminho_arquivo, e))?;
+        std::fs::write(caminho_arquivo, conteudo).map_err(ErroCarregamento::io(caminho_arquivo))?;
+        Ok(())
+    }
+
+    pub fn arquivo_rede_para_texto(&self, formato: FormatoArquivoRede) -> Result<String, Box<dyn std::error::Error>> {
+        let arquivo = self.para_arquivo_rede();
+        Ok(match formato {
+            FormatoArquivoRede::Json => serde_json::to_string_pretty(&arquivo)? + "\n",
+            FormatoArquivoRede::Toml => toml::to_string(&arquivo)?,
+        })
+    }
+
+    /// Aplica o documento ao grafo. Referências a estações inexistentes e valores inválidos
+    /// viram avisos (o item é ignorado ou recebe o valor padrão); uma versão não suportada é erro.
+    fn aplicar_arquivo_rede(
+        &mut self,
+        arquivo: &ArquivoRede,
+        origem: &str,
+        coletor: &mut ColetorAvisos,
+    ) -> Result<(), ErroCarregamento> {
+        if arquivo.versao > VERSAO_ARQUIVO_REDE {
+            return Err(ErroCarregamento::formato(origem, format!(
+                "versão {} do arquivo de rede não suportada (máximo {})",
+                arquivo.versao, VERSAO_ARQUIVO_REDE
+            )));
+        }
+        self.tempo_baldeacao_minutos = arquivo.tempo_baldeacao_minutos as f32;
+
+        // Os itens do documento são localizados pelo caminho do campo, ex.: "linhas[2].cor"
+        let local = |campo: String| LocalDados::celula(origem, None, &campo);
+
+        for (indice, linha) in arquivo.linhas.iter().enumerate() {
+            let cor_rgb = match linha.cor.as_deref().map(|cor| (cor, parsear_cor_hex(cor))) {
+                Some((_, Some(cor_rgb))) => cor_rgb,
+                Some((cor, None)) => {
+                    coletor.registrar(AvisoCarregamento::ValorInvalido {
+                        local: local(format!("linhas[{}].cor", indice)),
+                        valor: cor.to_string(),
+                        esperado: "cor hexadecimal #RRGGBB",
+                    })?;
+                    COR_LINHA_PADRAO
+                }
+                None => COR_LINHA_PADRAO,
+            };
+            let modo = match linha.modo.as_deref().map(|modo| (modo, ModoTransporte::de_texto(modo))) {
+                Some((_, Some(modo))) => modo,
+                Some((modo, None)) => {
+                    coletor.registrar(AvisoCarregamento::ValorInvalido {
+                        local: local(format!("linhas[{}].modo", indice)),
+                        valor: modo.to_string(),
+                        esperado: "metro, rer, tram, onibus ou a_pe",
+                    })?;
+                    ModoTransporte::default()
+                }
+                None => ModoTransporte::default(),
+            };
+            let id = self.adicionar_linha(&linha.codigo, &linha.nome, cor_rgb);
+            let definicao = &mut self.linhas[id];
+            definicao.nome = linha.nome.clone();
+            definicao.cor_rgb = cor_rgb;
+            definicao.modo = modo;
+            definicao.velocidade_kmh = linha.velocidade_kmh.map(|v| v as f32);
+        }
+
+        for estacao in &arquivo.estacoes {
+            let id = self.adicionar_estacao(&estacao.codigo);
+            if let Some(nome) = &estacao.nome {
+                self.renomear_estacao(id, nome);
+            }
+            let coordenadas = match (estacao.latitude, estacao.longitude, estacao.x_km, estacao.y_km) {
+                (Some(latitude), Some(longitude), _, _) => Some(Coordenadas::Geograficas { latitude, longitude }),
+                (_, _, Some(x_km), Some(y_km)) => Some(Coordenadas::Planas { x_km: x_km as f32, y_km: y_km as f32 }),
+                _ => None,
+            };
+            let definicao = &mut self.estacoes[id];
+            definicao.coordenadas = coordenadas;
+            definicao.zona = estacao.zona;
+            definicao.acessivel = estacao.acessivel;
+            definicao.tempo_baldeacao_minutos = estacao.tempo_baldeacao_minutos.map(|t| t as f32);
+        }
+
+        // Resolve o código de uma estação citada no campo indicado, registrando aviso se não existir
+        let id_estacao = |grafo: &GrafoMetro, coletor: &mut ColetorAvisos, codigo: &str, campo: String| {
+            match grafo.obter_id_estacao(codigo) {
+                Some(id) => Ok(Some(id)),
+                None => coletor
+                    .registrar(AvisoCarregamento::EstacaoDesconhecida { local: local(campo), codigo: codigo.to_string() })
+                    .map(|_| None),
+            }
+        };
+
+        for (indice, conexao) in arquivo.conexoes.iter().enumerate() {
+            let id_de = id_estacao(self, coletor, &conexao.de, format!("conexoes[{}].de", indice))?;
+            let id_para = id_estacao(self, coletor, &conexao.para, format!("conexoes[{}].para", indice))?;
+            let (Some(id_de), Some(id_para)) = (id_de, id_para) else { continue };
+            let id_linha = match self.linhas.iter().find(|linha| linha.codigo == conexao.linha) {
+                Some(linha) => linha.id,
+                None => {
+                    // Como nas tabelas CSV, no modo tolerante a linha é criada com a cor padrão
+                    coletor.registrar(AvisoCarregamento::LinhaDesconhecida {
+                        local: local(format!("conexoes[{}].linha", indice)),
+                        codigo_linha: conexao.linha.clone(),
+                        estacao_origem: conexao.de.clone(),
+                        estacao_destino: conexao.para.clone(),
+                    })?;
+                    self.adicionar_linha(&conexao.linha, &format!("Linha {}", conexao.linha), COR_LINHA_PADRAO)
+                }
+            };
+            let distancia_km = conexao.distancia_km as f32;
+            let tempo_minutos = conexao.tempo_minutos
+                .map_or_else(|| self.tempo_viagem_minutos(id_linha, distancia_km), |t| t as f32);
+
+            self.lista_adjacencia[id_de].push(Conexao { para_estacao: id_para, linha: id_linha, distancia_km, tempo_minutos });
+            if conexao.bidirecional {
+                self.lista_adjacencia[id_para].push(Conexao { para_estacao: id_de, linha: id_linha, distancia_km, tempo_minutos });
+            }
+        }
+
+        for (indice, distancia) in arquivo.distancias_heuristicas.iter().enumerate() {
+            let id_de = id_estacao(self, coletor, &distancia.de, format!("distancias_heuristicas[{}].de", indice))?;
+            let id_para = id_estacao(self, coletor, &distancia.para, format!("distancias_heuristicas[{}].para", indice))?;
+            let (Some(id_de), Some(id_para)) = (id_de, id_para) else { continue };
+            if distancia.distancia_km < 0.0 {
+                coletor.registrar(AvisoCarregamento::DistanciaNegativa {
+                    local: local(format!("distancias_heuristicas[{}].distancia_km", indice)),
+                    estacao_origem: distancia.de.clone(),
+                    estacao_destino: distancia.para.clone(),
+                    valor: distancia.distancia_km as f32,
+                })?;
+                continue;
+            }
+            let distancia_km = distancia.distancia_km as f32;
+            self.definir_distancia_heuristica(id_de, id_para, distancia_km);
+            if distancia.bidirecional {
+                self.definir_distancia_heuristica(id_para, id_de, distancia_km);
+            }
+        }
+        Ok(())
+    }
+
+    /// Monta o documento da rede. Pares de conexões (ou de distâncias heurísticas) idênticas nos dois
+    /// sentidos viram uma só entrada `bidirecional`, para que o arquivo fique curto e as diferenças
+    /// fáceis de revisar.
+    pub fn para_arquivo_rede(&self) -> ArquivoRede {
+        let linhas = self.linhas.iter()
+            .map(|linha| LinhaArquivo {
+                codigo: linha.codigo.clone(),
+                nome: linha.nome.clone(),
+                cor: Some(cor_para_hex(linha.cor_rgb)),
+                modo: Some(linha.modo.codigo().to_string()),
+                velocidade_kmh: linha.velocidade_kmh.map(f32_para_f64),
+            })
+            .collect();
+
+        let estacoes = self.estacoes.iter()
+            .map(|estacao| {
+                let (latitude, longitude, x_km, y_km) = match estacao.coordenadas {
+                    Some(Coordenadas::Geograficas { latitude, longitude }) => (Some(latitude), Some(longitude), None, None),
+                    Some(Coordenadas::Planas { x_km, y_km }) => (None, None, Some(f32_para_f64(x_km)), Some(f32_para_f64(y_km))),
+                    None => (None, None, None, None),
+                };
+                EstacaoArquivo {
+                    codigo: estacao.codigo.clone(),
+                    nome: (estacao.nome != estacao.codigo).then(|| estacao.nome.clone()),
+                    latitude,
+                    longitude,
+                    x_km,
+                    y_km,
+                    zona: estacao.zona,
+                    acessivel: estacao.acessivel,
+                    tempo_baldeacao_minutos: estacao.tempo_baldeacao_minutos.map(f32_para_f64),
+                }
+            })
+            .collect();
+
+        let mesma_conexao = |a: &Conexao, b: &Conexao| {
+            a.linha == b.linha && a.distancia_km == b.distancia_km && a.tempo_minutos == b.tempo_minutos
+        };
+        let mut conexoes = Vec::new();
+        for (id_de, conexoes_de) in self.lista_adjacencia.iter().enumerate() {
+            for conexao in conexoes_de {
+                let id_para = conexao.para_estacao;
+                let tem_volta_identica = self.lista_adjacencia[id_para].iter()
+                    .any(|volta| volta.para_estacao == id_de && mesma_conexao(volta, conexao));
+                // O par bidirecional é escrito uma vez, a partir da estação de menor id
+                if tem_volta_identica && id_para < id_de {
+                    continue;
+                }
+                conexoes.push(ConexaoArquivo {
+                    de: self.estacoes[id_de].codigo.clone(),
+                    para: self.estacoes[id_para].codigo.clone(),
+                    linha: self.linhas[conexao.linha].codigo.clone(),
+                    distancia_km: f32_para_f64(conexao.distancia_km),
+                    tempo_minutos: Some(f32_para_f64(conexao.tempo_minutos)),
+                    bidirecional: tem_volta_identica && id_para != id_de,
+                });
+            }
+        }
+
+        let mut distancias_heuristicas = Vec::new();
+        let distancia_heuristica = |id_de: IdEstacao, id_para: IdEstacao, distancia_km: f32, bidirecional: bool| DistanciaHeuristicaArquivo {
+            de: self.estacoes[id_de].codigo.clone(),
+            para: self.estacoes[id_para].codigo.clone(),
+            distancia_km: f32_para_f64(distancia_km),
+            bidirecional,
+        };
+        for (id_de, linha_matriz) in self.distancias_heuristicas_km.iter().flatten().enumerate() {
+            for (id_para, &ida) in linha_matriz.iter().enumerate().skip(id_de) {
+                match (ida, self.distancia_heuristica_tabela_km(id_para, id_de)) {
+                    (Some(ida), Some(volta)) if ida == volta => distancias_heuristicas.push(distancia_heuristica(id_de, id_para, ida, true)),
+                    (ida, volta) => {
+                        distancias_heuristicas.extend(ida.map(|ida| distancia_heuristica(id_de, id_para, ida, false)));
+                        distancias_heuristicas.extend(volta.map(|volta| distancia_heuristica(id_para, id_de, volta, false)));
+                    }
+                }
+            }
+        }
+
+        ArquivoRede {
+            versao: VERSAO_ARQUIVO_REDE,
+            tempo_baldeacao_minutos: f32_para_f64(self.tempo_baldeacao_minutos),
+            linhas,
+            estacoes,
+            conexoes,
+            distancias_heuristicas,
+        }
+    }
+}
@@ -0,0 +1,620 @@
+//! Planejador de rotas em linha de comando, sem abrir a janela:
+//!
+//!     metro-route --from E6 --to E13 [--start-line Azul] [--data data] [--estrito]
+//!                 [--json <arquivo|->] [--eventos <arquivo.jsonl>] [--detalhado] [--estatisticas]
+//!                 [--estrategia a-estrela|dijkstra|gulosa|largura|ponderado [--epsilon 1.5]] [--bidirecional]
+//!                 [--marcos mais-distantes[:N]|aleatorios[:N[:semente]]|E1,E7,...] [--pareto] [--alternativas 3]
+//!                 [--partida 08:00 | --chegada 09:00] [--horarios <arquivo.toml|.json>]
+//!     metro-route --validar | --analisar-heuristica | --comparar-heuristicas | --exportar-rede <arquivo.json|.toml>
+//!                 [--data data] [--estrito] [--marcos ...]
+//!
+//! `--data` aceita o diretório das tabelas CSV (padrão `data`), um arquivo de rede .json/.toml
+//! ou um feed GTFS. `--json` grava a rota em JSON (`-` para a saída padrão), `--eventos` grava
+//! todos os eventos da busca, um JSON por linha, `--detalhado` descreve cada passo na tela
+//! e `--estatisticas` mostra, na saída de erro, os estados expandidos e as operações feitas na
+//! fronteira. `--estrategia` troca o A* por outra ordem de busca; `--epsilon` é o peso de h no
+//! A* ponderado (só com `--estrategia ponderado`). `--bidirecional` usa o A* bidirecional (sem eventos nem outras estratégias).
+//! `--marcos` troca a heurística em linha reta pela ALT, com os marcos escolhidos. `--pareto` lista
+//! todas as rotas que trocam tempo por menos baldeações (com `--json`, um array de rotas);
+//! `--alternativas N` mostra a melhor rota e as N seguintes em ordem de tempo. `--partida` parte no
+//! horário dado e mostra o horário de cada trecho; com `--horarios`, a busca inclui a espera pelo
+//! próximo trem na origem e em cada baldeação, segundo o quadro de horários. `--chegada` faz o
+//! contrário: procura o horário mais tarde para sair da origem e chegar até o horário dado, e por
+//! qual rota (busca reversa a partir do destino, sem estratégias, eventos nem marcos).
+//!
+//! Sem `--from`/`--to`, as ferramentas sobre a rede inteira: `--validar` lista os avisos de
+//! carregamento e os problemas da rede; `--analisar-heuristica` verifica se a heurística (a ALT, com
+//! `--marcos`) é admissível e consistente; `--comparar-heuristicas` compara os estados expandidos
+//! pelo A* com a linha reta e com a ALT; `--exportar-rede` grava a rede carregada em .json/.toml.
+//! Códigos de saída: 0 rota encontrada (ou rede/heurística sem problemas, ou `--help`), 1 sem
+//! caminho (ou com problemas), 2 entrada inválida.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::process::ExitCode;
+use std::sync::Arc;
+use metro_paris_astar::{analise_heuristica, validacao_rede};
+use serde::Serialize;
+use metro_paris_astar::algoritmo_a_estrela::{EventoVisual, InfoCaminho, ResultadoPassoAEstrela, SolucionadorAEstrela};
+use metro_paris_astar::busca_bidirecional::SolucionadorBidirecional;
+use metro_paris_astar::busca_chegada::{buscar_partida_mais_tarde, PartidaMaisTarde};
+use metro_paris_astar::busca_pareto::{buscar_rotas_pareto, RotasPareto};
+use metro_paris_astar::rotas_alternativas::{buscar_rotas_alternativas, RotaAlternativa};
+use metro_paris_astar::observador_busca::{ObservadorBusca, ObservadorConsole, ObservadorJsonl};
+use metro_paris_astar::dados_metro::FonteRede;
+use metro_paris_astar::exportacao_busca::{self, RotaDetalhada};
+use metro_paris_astar::erro_carregamento::ModoCarregamento;
+use metro_paris_astar::estrategia_busca::{EstrategiaBusca, EPSILON_PADRAO};
+use metro_paris_astar::grafo_metro::{GrafoMetro, IdEstacao};
+use metro_paris_astar::heuristica_alt::{FonteHeuristica, HeuristicaAlt, SelecaoMarcos};
+use metro_paris_astar::quadro_horarios::{formatar_horario, interpretar_horario, ConsultaHorarios, QuadroHorarios};
+
+const SAIDA_SEM_CAMINHO: u8 = 1;
+const SAIDA_ENTRADA_INVALIDA: u8 = 2;
+
+const USO: &str = "uso: metro-route --from <estação> --to <estação> [--start-line <linha>] [--data <diretório|arquivo>] [--estrito] \
+                   [--json <arquivo|->] [--eventos <arquivo.jsonl>] [--detalhado] [--estatisticas] \
+                   [--estrategia a-estrela|dijkstra|gulosa|largura|ponderado [--epsilon <ε>]] [--bidirecional] \
+                   [--marcos mais-distantes[:N]|aleatorios[:N[:semente]]|<estação>,<estação>,...] [--pareto] [--alternativas <N>] \
+                   [--partida <HH:MM> | --chegada <HH:MM>] [--horarios <arquivo.toml|.json>]\n\
+                   ou:  metro-route --validar | --analisar-heuristica | --comparar-heuristicas | --exportar-rede <arquivo.json|.toml> \
+                   [--data <diretório|arquivo>] [--estrito] [--marcos <seleção>]";
+
+/// Ferramentas que trabalham sobre a rede inteira, sem origem nem destino
+enum Ferramenta {
+    Validar,
+    AnalisarHeuristica,
+    CompararHeuristicas,
+    ExportarRede(String),
+}
+
+enum Comando {
+    Ajuda,
+    Rota(Argumentos),
+    Ferramenta { ferramenta: Ferramenta, dados: String, modo_carregamento: ModoCarregamento, marcos: Option<String> },
+}
+
+struct Argumentos {
+    origem: String,
+    destino: String,
+    linha_inicio: Option<String>,
+    dados: String,
+    modo_carregamento: ModoCarregamento,
+    saida_json: Option<String>,
+    saida_eventos: Option<String>,
+    detalhado: bool,
+    estatisticas: bool,
+    estrategia: EstrategiaBusca,
+    bidirecional: bool,
+    marcos: Option<String>,
+    pareto: bool,
+    alternativas: Option<usize>,
+    partida: Option<f32>,
+    chegada: Option<f32>,
+    horarios: Option<String>,
+}
+
+fn ler_argumentos() -> Result<Comando, String> {
+    let mut origem = None;
+    let mut destino = None;
+    let mut linha_inicio = None;
+    let mut dados = "data".to_string();
+    let mut modo_carregamento = ModoCarregamento::Tolerante;
+    let mut saida_json = None;
+    let mut saida_eventos = None;
+    let mut detalhado = false;
+    let mut estatisticas = false;
+    let mut estrategia = None;
+    let mut epsilon = None;
+    let mut bidirecional = false;
+    let mut marcos = None;
+    let mut pareto = false;
+    let mut alternativas = None;
+    let mut partida = None;
+    let mut chegada = None;
+    let mut horarios = None;
+    let mut ferramentas = Vec::new();
+
+    let mut argumentos = std::env::args().skip(1);
+    while let Some(argumento) = argumentos.next() {
+        let mut valor = || argumentos.next().ok_or_else(|| format!("{} precisa de um valor", argumento));
+        match argumento.as_str() {
+            "--from" => origem = Some(valor()?),
+            "--to" => destino = Some(valor()?),
+            "--start-line" => linha_inicio = Some(valor()?),
+            "--data" => dados = valor()?,
+            "--estrito" => modo_carregamento = ModoCarregamento::Estrito,
+            "--json" => saida_json = Some(valor()?),
+            "--eventos" => saida_eventos = Some(valor()?),
+            "--detalhado" => detalhado = true,
+            "--estatisticas" => estatisticas = true,
+            "--estrategia" => estrategia = Some(valor()?),
+            "--bidirecional" => bidirecional = true,
+            "--marcos" => marcos = Some(valor()?),
+            "--pareto" => pareto = true,
+            "--alternativas" => {
+                let texto = valor()?;
+                alternativas = Some(texto.parse::<usize>().ok().filter(|quantidade| *quantidade >= 1)
+                    .ok_or_else(|| format!("--alternativas deve ser um número maior ou igual a 1: {}", texto))?);
+            }
+            "--partida" => {
+                let texto = valor()?;
+                partida = Some(interpretar_horario(&texto).ok_or_else(|| format!("--partida deve ser um horário HH:MM: {}", texto))?);
+            }
+            "--chegada" => {
+                let texto = valor()?;
+                chegada = Some(interpretar_horario(&texto).ok_or_else(|| format!("--chegada deve ser um horário HH:MM: {}", texto))?);
+            }
+            "--horarios" => horarios = Some(valor()?),
+            "--epsilon" => {
+                let texto = valor()?;
+                epsilon = Some(texto.parse::<f32>().ok().filter(|epsilon| *epsilon >= 1.0)
+                    .ok_or_else(|| format!("--epsilon deve ser um número maior ou igual a 1: {}", texto))?);
+            }
+            "--validar" => ferramentas.push(Ferramenta::Validar),
+            "--analisar-heuristica" => ferramentas.push(Ferramenta::AnalisarHeuristica),
+            "--comparar-heuristicas" => ferramentas.push(Ferramenta::CompararHeuristicas),
+            "--exportar-rede" => ferramentas.push(Ferramenta::ExportarRede(valor()?)),
+            "--help" | "-h" => return Ok(Comando::Ajuda),
+            outro => return Err(format!("argumento desconhecido: {}", outro)),
+        }
+    }
+
+    if ferramentas.len() > 1 {
+        return Err("use só uma de --validar, --analisar-heuristica, --comparar-heuristicas e --exportar-rede".to_string());
+    }
+    if let Some(ferramenta) = ferramentas.pop() {
+        if origem.is_some() || destino.is_some() {
+            return Err("--validar, --analisar-heuristica, --comparar-heuristicas e --exportar-rede não aceitam --from nem --to".to_string());
+        }
+        return Ok(Comando::Ferramenta { ferramenta, dados, modo_carregamento, marcos });
+    }
+    if bidirecional && (estrategia.is_some() || saida_eventos.is_some() || detalhado || marcos.is_some()) {
+        return Err("--bidirecional não aceita --estrategia, --eventos, --detalhado nem --marcos".to_string());
+    }
+    if pareto && (bidirecional || estrategia.is_some() || saida_eventos.is_some() || detalhado || marcos.is_some()) {
+        return Err("--pareto não aceita --bidirecional, --estrategia, --eventos, --detalhado nem --marcos".to_string());
+    }
+    if alternativas.is_some() && (pareto || bidirecional || estrategia.is_some() || saida_eventos.is_some() || detalhado || marcos.is_some()) {
+        return Err("--alternativas não aceita --pareto, --bidirecional, --estrategia, --eventos, --detalhado nem --marcos".to_string());
+    }
+    if partida.is_some() && (bidirecional || pareto || alternativas.is_some()) {
+        return Err("--partida não aceita --bidirecional, --pareto nem --alternativas".to_string());
+    }
+    if chegada.is_some() && (partida.is_some() || bidirecional || pareto || alternativas.is_some() || estrategia.is_some()
+        || saida_eventos.is_some() || detalhado || marcos.is_some())
+    {
+        return Err("--chegada não aceita --partida, --bidirecional, --pareto, --alternativas, --estrategia, --eventos, --detalhado nem --marcos".to_string());
+    }
+    if horarios.is_some() && partida.is_none() && chegada.is_none() {
+        return Err("--horarios precisa de --partida ou --chegada".to_string());
+    }
+    let estrategia = match estrategia {
+        Some(nome) => EstrategiaBusca::de_texto(&nome, epsilon.unwrap_or(EPSILON_PADRAO))
+            .ok_or_else(|| format!("estratégia desconhecida: {}", nome))?,
+        None => EstrategiaBusca::default(),
+    };
+    if epsilon.is_some() && !matches!(estrategia, EstrategiaBusca::AEstrelaPonderado { .. }) {
+        return Err("--epsilon precisa de --estrategia ponderado".to_string());
+    }
+
+    Ok(Comando::Rota(Argumentos {
+        origem: origem.ok_or("--from é obrigatório")?,
+        destino: destino.ok_or("--to é obrigatório")?,
+        linha_inicio,
+        dados,
+        modo_carregamento,
+        saida_json,
+        saida_eventos,
+        detalhado,
+        estatisticas,
+        estrategia,
+        bidirecional,
+        marcos,
+        pareto,
+        alternativas,
+        partida,
+        chegada,
+        horarios,
+    }))
+}
+
+fn imprimir_itinerario(grafo: &GrafoMetro, caminho: &InfoCaminho) {
+    let descrever = |id: IdEstacao| format!("{} ({})", grafo.estacoes[id].nome, grafo.estacoes[id].codigo);
+    let (Some(&(id_inicio, _)), Some(&(id_fim, _))) = (caminho.estacoes_do_caminho.first(), caminho.estacoes_do_caminho.last()) else {
+        return;
+    };
+
+    println!();
+    println!("Rota: {} -> {}", descrever(id_inicio), descrever(id_fim));
+    if let Some(partida) = caminho.horario_partida {
+        println!("Partida: {}", formatar_horario(partida));
+    }
+    println!("  1. {}", descrever(id_inicio));
+    for (indice, par) in caminho.estacoes_do_caminho.windows(2).enumerate() {
+        let ((id_anterior, linha_anterior), (id_estacao, linha)) = (par[0], par[1]);
+        let linha_anterior = if indice == 0 { caminho.linha_partida } else { linha_anterior };
+        if let (Some(anterior), Some(atual)) = (linha_anterior, linha)
+            && anterior != atual
+        {
+            println!("     baldeação em {}: {} -> {} (+{:.1} min)",
+                grafo.estacoes[id_anterior].nome, grafo.nome_linha(anterior), grafo.nome_linha(atual),
+                grafo.tempo_baldeacao_em(id_anterior));
+        }
+        let nome_linha = linha.map_or("-", |id_linha| grafo.nome_linha(id_linha));
+        let tempo = caminho.tempos_trechos_minutos[indice];
+        match caminho.horarios_trechos.get(indice) {
+            Some(horario) => {
+                if horario.espera_minutos > 0.0 {
+                    println!("     espera de {:.1} min pelo trem da linha {} em {}",
+                        horario.espera_minutos, nome_linha, grafo.estacoes[id_anterior].nome);
+                }
+                println!("  {}. {}  [linha {}, {:.1} min, {} -> {}]", indice + 2, descrever(id_estacao), nome_linha, tempo,
+                    formatar_horario(horario.partida), formatar_horario(horario.chegada));
+            }
+            None => println!("  {}. {}  [linha {}, {:.1} min]", indice + 2, descrever(id_estacao), nome_linha, tempo),
+        }
+    }
+    println!("Baldeações: {}", caminho.baldeacoes);
+    match caminho.horario_partida {
+        Some(partida) => {
+            println!("Tempo total: {:.1} min, com {:.1} min de espera", caminho.tempo_total_minutos, caminho.espera_total_minutos());
+            println!("Chegada: {}", formatar_horario(partida + caminho.tempo_total_minutos));
+        }
+        None => println!("Tempo total: {:.1} min", caminho.tempo_total_minutos),
+    }
+}
+
+/// Valida a rede, analisa ou compara as heurísticas, ou exporta a rede; devolve o código de saída
+fn executar_ferramenta(ferramenta: &Ferramenta, dados: &str, modo_carregamento: ModoCarregamento, marcos: Option<&str>) -> ExitCode {
+    let (grafo, avisos) = match FonteRede::do_caminho(dados).carregar(modo_carregamento) {
+        Ok(resultado) => resultado,
+        Err(e) => {
+            eprintln!("Erro ao carregar a rede: {}", e);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+    };
+    let sem_problemas = |ok: bool| if ok { ExitCode::SUCCESS } else { ExitCode::from(SAIDA_SEM_CAMINHO) };
+
+    match ferramenta {
+        Ferramenta::Validar => {
+            let relatorio = validacao_rede::validar_rede(&grafo);
+            for aviso in &avisos {
+                println!("AVISO: {}", aviso);
+            }
+            for problema in &relatorio.problemas {
+                println!("PROBLEMA: {}", problema);
+            }
+            if avisos.is_empty() && relatorio.esta_valida() {
+                println!("Rede válida: {} estações, {} linhas", grafo.numero_estacoes(), grafo.linhas.len());
+            } else {
+                println!("{} aviso(s) de carregamento, {} problema(s) na rede", avisos.len(), relatorio.problemas.len());
+            }
+            sem_problemas(avisos.is_empty() && relatorio.esta_valida())
+        }
+        Ferramenta::ExportarRede(caminho_saida) => {
+            for aviso in &avisos {
+                eprintln!("Aviso: {}", aviso);
+            }
+            if let Err(e) = grafo.salvar_arquivo_rede(caminho_saida) {
+                eprintln!("Erro ao exportar a rede: {}", e);
+                return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+            }
+            ExitCode::SUCCESS
+        }
+        Ferramenta::AnalisarHeuristica | Ferramenta::CompararHeuristicas => {
+            let grafo = Arc::new(grafo);
+            let selecao = match marcos.map(|texto| SelecaoMarcos::de_texto(&grafo, texto)).transpose() {
+                Ok(selecao) => selecao,
+                Err(mensagem) => {
+                    eprintln!("{}", mensagem);
+                    return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+                }
+            };
+            let fonte_alt = |selecao: &SelecaoMarcos| {
+                let alt = HeuristicaAlt::calcular(&grafo, selecao);
+                println!("Marcos ALT, {}: {}", selecao, alt.descrever_marcos(&grafo));
+                FonteHeuristica::Alt(Arc::new(alt))
+            };
+
+            if let Ferramenta::CompararHeuristicas = ferramenta {
+                let alternativa = fonte_alt(&selecao.unwrap_or_default());
+                println!("{}", analise_heuristica::comparar_heuristicas(&grafo, &FonteHeuristica::LinhaReta, &alternativa));
+                return ExitCode::SUCCESS;
+            }
+            let fonte = selecao.as_ref().map_or(FonteHeuristica::LinhaReta, fonte_alt);
+            let analise = analise_heuristica::analisar_fonte_heuristica(&grafo, &fonte);
+            println!("{}", analise);
+            sem_problemas(analise.eh_admissivel() && analise.eh_consistente())
+        }
+    }
+}
+
+/// Grava em JSON no arquivo indicado, ou na saída padrão com "-"
+fn gravar_json<T: Serialize>(valor: &T, destino: &str) -> std::io::Result<()> {
+    if destino == "-" {
+        exportacao_busca::escrever_json(valor, std::io::stdout().lock())
+    } else {
+        exportacao_busca::escrever_json(valor, BufWriter::new(File::create(destino)?))
+    }
+}
+
+/// Lista as rotas do conjunto de Pareto, da mais rápida à com menos baldeações; devolve o código de saída
+fn mostrar_rotas_pareto(grafo: &GrafoMetro, argumentos: &Argumentos, rotas_pareto: &RotasPareto) -> ExitCode {
+    if argumentos.estatisticas {
+        eprintln!("Busca de Pareto (tempo × baldeações) | rótulos expandidos: {}", rotas_pareto.rotulos_expandidos);
+    }
+    if rotas_pareto.rotas.is_empty() {
+        eprintln!("Nenhum caminho de {} para {}", argumentos.origem, argumentos.destino);
+        return ExitCode::from(SAIDA_SEM_CAMINHO);
+    }
+
+    if let Some(destino) = argumentos.saida_json.as_deref() {
+        let rotas: Vec<RotaDetalhada> = rotas_pareto.rotas.iter().map(|caminho| RotaDetalhada::do_caminho(grafo, caminho)).collect();
+        if let Err(e) = gravar_json(&rotas, destino) {
+            eprintln!("Erro ao gravar as rotas: {}", e);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+        return ExitCode::SUCCESS;
+    }
+    println!("{} opção(ões) entre tempo e baldeações:", rotas_pareto.rotas.len());
+    for (indice, caminho) in rotas_pareto.rotas.iter().enumerate() {
+        println!("  Opção {}: {:.1} min, {} baldeação(ões)", indice + 1, caminho.tempo_total_minutos, caminho.baldeacoes);
+    }
+    for (indice, caminho) in rotas_pareto.rotas.iter().enumerate() {
+        println!();
+        print!("Opção {}:", indice + 1);
+        imprimir_itinerario(grafo, caminho);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Mostra a melhor rota e as alternativas com a diferença para ela; devolve o código de saída
+fn mostrar_rotas_alternativas(grafo: &GrafoMetro, argumentos: &Argumentos, rotas: &[RotaAlternativa]) -> ExitCode {
+    let Some(melhor) = rotas.first() else {
+        eprintln!("Nenhum caminho de {} para {}", argumentos.origem, argumentos.destino);
+        return ExitCode::from(SAIDA_SEM_CAMINHO);
+    };
+
+    if let Some(destino) = argumentos.saida_json.as_deref() {
+        let rotas: Vec<RotaDetalhada> = rotas.iter().map(|rota| RotaDetalhada::do_caminho(grafo, &rota.caminho)).collect();
+        if let Err(e) = gravar_json(&rotas, destino) {
+            eprintln!("Erro ao gravar as rotas: {}", e);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+        return ExitCode::SUCCESS;
+    }
+    println!("Melhor rota: {:.1} min, {} baldeação(ões)", melhor.caminho.tempo_total_minutos, melhor.caminho.baldeacoes);
+    for (indice, rota) in rotas.iter().enumerate().skip(1) {
+        println!("  Alternativa {}: {:.1} min ({:+.1} min), {} baldeação(ões) ({:+})", indice,
+            rota.caminho.tempo_total_minutos, rota.diferenca_tempo_minutos, rota.caminho.baldeacoes, rota.diferenca_baldeacoes);
+    }
+    if rotas.len() - 1 < argumentos.alternativas.unwrap_or(0) {
+        println!("  (só há {} alternativa(s) sem repetir estações)", rotas.len() - 1);
+    }
+    for (indice, rota) in rotas.iter().enumerate() {
+        println!();
+        if indice == 0 {
+            print!("Melhor rota:");
+        } else {
+            print!("Alternativa {}:", indice);
+        }
+        imprimir_itinerario(grafo, &rota.caminho);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Mostra o horário mais tarde de partida e o itinerário que chega a tempo; devolve o código de saída
+fn mostrar_partida_mais_tarde(grafo: &GrafoMetro, argumentos: &Argumentos, horario_chegada: f32, resultado: &PartidaMaisTarde) -> ExitCode {
+    if argumentos.estatisticas {
+        eprintln!("Busca reversa (chegar até) | estados expandidos: {}", resultado.estados_expandidos);
+    }
+    let Some(caminho) = &resultado.caminho else {
+        eprintln!("Nenhum caminho de {} para {}", argumentos.origem, argumentos.destino);
+        return ExitCode::from(SAIDA_SEM_CAMINHO);
+    };
+
+    if let Some(destino) = argumentos.saida_json.as_deref() {
+        if let Err(e) = gravar_json(&RotaDetalhada::do_caminho(grafo, caminho), destino) {
+            eprintln!("Erro ao gravar a rota: {}", e);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+        return ExitCode::SUCCESS;
+    }
+    if let Some(partida) = caminho.horario_partida {
+        println!("Para chegar até {}, parta às {}", formatar_horario(horario_chegada), formatar_horario(partida));
+    }
+    imprimir_itinerario(grafo, caminho);
+    ExitCode::SUCCESS
+}
+
+/// Observadores pedidos na linha de comando: o passo a passo na tela e/ou os eventos em JSON Lines
+fn criar_observadores(argumentos: &Argumentos) -> std::io::Result<Vec<Box<dyn ObservadorBusca>>> {
+    let mut observadores: Vec<Box<dyn ObservadorBusca>> = Vec::new();
+    if argumentos.detalhado {
+        observadores.push(Box::new(ObservadorConsole::novo()));
+    }
+    if let Some(caminho) = &argumentos.saida_eventos {
+        observadores.push(Box::new(ObservadorJsonl::novo(BufWriter::new(File::create(caminho)?))));
+    }
+    Ok(observadores)
+}
+
+/// Roda a busca até o fim; com `por_evento`, micro-passo a micro-passo, para que os observadores
+/// recebam a sequência completa de eventos
+fn executar_busca(solucionador: &mut SolucionadorAEstrela, por_evento: bool) -> std::io::Result<ResultadoPassoAEstrela> {
+    let resultado = if por_evento {
+        loop {
+            match solucionador.proximo_evento() {
+                EventoVisual::CaminhoEncontrado { info_caminho } => break ResultadoPassoAEstrela::CaminhoEncontrado(info_caminho),
+                EventoVisual::NenhumCaminho => break ResultadoPassoAEstrela::NenhumCaminhoPossivel,
+                _ => continue,
+            }
+        }
+    } else {
+        loop {
+            match solucionador.proximo_passo() {
+                ResultadoPassoAEstrela::EmProgresso => continue,
+                resultado => break resultado,
+            }
+        }
+    };
+    solucionador.finalizar_observador()?;
+    Ok(resultado)
+}
+
+/// Roda o A* bidirecional até o fim; com `estatisticas`, mostra quantos estados cada metade expandiu
+fn executar_busca_bidirecional(solucionador: &mut SolucionadorBidirecional, estatisticas: bool) -> ResultadoPassoAEstrela {
+    let resultado = loop {
+        match solucionador.proximo_passo() {
+            ResultadoPassoAEstrela::EmProgresso => continue,
+            resultado => break resultado,
+        }
+    };
+    if estatisticas {
+        eprintln!("Busca bidirecional | estados expandidos: {} (ida {}, volta {})",
+            solucionador.estados_expandidos(), solucionador.ida.explorados.len(), solucionador.volta.explorados.len());
+    }
+    resultado
+}
+
+fn main() -> ExitCode {
+    let argumentos = match ler_argumentos() {
+        Ok(Comando::Ajuda) => {
+            println!("{}", USO);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Comando::Rota(argumentos)) => argumentos,
+        Ok(Comando::Ferramenta { ferramenta, dados, modo_carregamento, marcos }) => {
+            return executar_ferramenta(&ferramenta, &dados, modo_carregamento, marcos.as_deref());
+        }
+        Err(mensagem) => {
+            eprintln!("{}", mensagem);
+            eprintln!("{}", USO);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+    };
+
+    let grafo = match FonteRede::do_caminho(&argumentos.dados).carregar(argumentos.modo_carregamento) {
+        Ok((grafo, avisos)) => {
+            for aviso in &avisos {
+                eprintln!("Aviso: {}", aviso);
+            }
+            grafo
+        }
+        Err(e) => {
+            eprintln!("Erro ao carregar a rede: {}", e);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+    };
+
+    let estacao = |nome: &str| grafo.obter_id_estacao(nome).ok_or_else(|| format!("estação desconhecida: {}", nome));
+    let linha_inicio = match argumentos.linha_inicio.as_deref() {
+        Some(nome) => grafo.obter_id_linha(nome).map(Some).ok_or_else(|| format!("linha desconhecida: {}", nome)),
+        None => Ok(None),
+    };
+    let (id_origem, id_destino, linha_inicio) = match (estacao(&argumentos.origem), estacao(&argumentos.destino), linha_inicio) {
+        (Ok(origem), Ok(destino), Ok(linha)) => (origem, destino, linha),
+        (Err(mensagem), _, _) | (_, Err(mensagem), _) | (_, _, Err(mensagem)) => {
+            eprintln!("{}", mensagem);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+    };
+    let fonte_heuristica = match argumentos.marcos.as_deref().map(|texto| SelecaoMarcos::de_texto(&grafo, texto)) {
+        Some(Ok(selecao)) => FonteHeuristica::Alt(Arc::new(HeuristicaAlt::calcular(&grafo, &selecao))),
+        Some(Err(mensagem)) => {
+            eprintln!("{}", mensagem);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+        None => FonteHeuristica::LinhaReta,
+    };
+    let quadro = match argumentos.horarios.as_deref().map(|caminho| QuadroHorarios::carregar(&grafo, caminho, argumentos.modo_carregamento)) {
+        Some(Ok((quadro, avisos))) => {
+            for aviso in &avisos {
+                eprintln!("Aviso: {}", aviso);
+            }
+            quadro
+        }
+        Some(Err(e)) => {
+            eprintln!("Erro ao carregar o quadro de horários: {}", e);
+            return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+        }
+        None => QuadroHorarios::novo(),
+    };
+
+    if let Some(horario_chegada) = argumentos.chegada {
+        let resultado = buscar_partida_mais_tarde(&grafo, &Arc::new(quadro), id_origem, linha_inicio, id_destino, horario_chegada);
+        return mostrar_partida_mais_tarde(&grafo, &argumentos, horario_chegada, &resultado);
+    }
+    if argumentos.pareto {
+        let rotas_pareto = buscar_rotas_pareto(&grafo, id_origem, linha_inicio, id_destino);
+        return mostrar_rotas_pareto(&grafo, &argumentos, &rotas_pareto);
+    }
+    if let Some(alternativas) = argumentos.alternativas {
+        let rotas = buscar_rotas_alternativas(&grafo, id_origem, linha_inicio, id_destino, alternativas);
+        return mostrar_rotas_alternativas(&grafo, &argumentos, &rotas);
+    }
+
+    let grafo = Arc::new(grafo);
+    let resultado = if argumentos.bidirecional {
+        let mut solucionador = SolucionadorBidirecional::novo(Arc::clone(&grafo), id_origem, linha_inicio, id_destino);
+        executar_busca_bidirecional(&mut solucionador, argumentos.estatisticas)
+    } else {
+        let observadores = match criar_observadores(&argumentos) {
+            Ok(observadores) => observadores,
+            Err(e) => {
+                eprintln!("Erro ao criar o arquivo de eventos: {}", e);
+                return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+            }
+        };
+        let mut solucionador = SolucionadorAEstrela::novo(Arc::clone(&grafo), id_origem, linha_inicio, id_destino)
+            .com_estrategia(argumentos.estrategia)
+            .com_heuristica(fonte_heuristica)
+            .com_observador(Box::new(observadores));
+        if let Some(horario_partida) = argumentos.partida {
+            solucionador = solucionador.com_horarios(ConsultaHorarios { quadro: Arc::new(quadro), horario_partida });
+        }
+        let resultado = match executar_busca(&mut solucionador, argumentos.saida_eventos.is_some()) {
+            Ok(resultado) => resultado,
+            Err(e) => {
+                eprintln!("Erro ao gravar os eventos: {}", e);
+                return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+            }
+        };
+        if argumentos.estatisticas {
+            let heuristica = match solucionador.fonte_heuristica() {
+                FonteHeuristica::Alt(alt) => format!("ALT (marcos {})", alt.descrever_marcos(&grafo)),
+                fonte => fonte.nome().to_string(),
+            };
+            eprintln!("Estratégia: {} | heurística: {} | estados expandidos: {}",
+                solucionador.estrategia(), heuristica, solucionador.explorados.len());
+            let fronteira = solucionador.estatisticas_fronteira();
+            eprintln!("Fronteira: {}", fronteira);
+            eprintln!("  (um heap sem índice teria {} inserções a mais e até {} remoções obsoletas)",
+                fronteira.diminuicoes_de_chave, fronteira.diminuicoes_de_chave);
+        }
+        resultado
+    };
+    match resultado {
+        ResultadoPassoAEstrela::CaminhoEncontrado(caminho) => {
+            match argumentos.saida_json.as_deref() {
+                Some(destino) => {
+                    if let Err(e) = gravar_json(&RotaDetalhada::do_caminho(&grafo, &caminho), destino) {
+                        eprintln!("Erro ao gravar a rota: {}", e);
+                        return ExitCode::from(SAIDA_ENTRADA_INVALIDA);
+                    }
+                }
+                None => imprimir_itinerario(&grafo, &caminho),
+            }
+            ExitCode::SUCCESS
+        }
+        ResultadoPassoAEstrela::NenhumCaminhoPossivel | ResultadoPassoAEstrela::EmProgresso => {
+            eprintln!("Nenhum caminho de {} para {}", argumentos.origem, argumentos.destino);
+            ExitCode::from(SAIDA_SEM_CAMINHO)
+        }
+        ResultadoPassoAEstrela::Erro(mensagem) => {
+            eprintln!("Erro na busca: {}", mensagem);
+            ExitCode::from(SAIDA_ENTRADA_INVALIDA)
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use serde::Serialize;
+
+use crate::algoritmo_a_estrela::{EstadoBusca, EstadoNoFronteira, InfoCaminho, ResultadoPassoAEstrela};
+use crate::fila_prioridade::{FilaPrioridadeIndexada, ResultadoInsercao};
+use crate::grafo_metro::{Conexao, GrafoMetro, IdEstacao, IdLinha};
+
+/// Sentido de uma das duas metades da busca bidirecional
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SentidoBusca {
+    /// Da origem para o objetivo; o estado guarda a linha de chegada à estação
+    Ida,
+    /// Do objetivo para a origem, pelas conexões invertidas; o estado guarda a linha de saída da estação
+    Volta,
+}
+
+impl SentidoBusca {
+    fn oposto(self) -> Self {
+        match self {
+            SentidoBusca::Ida => SentidoBusca::Volta,
+            SentidoBusca::Volta => SentidoBusca::Ida,
+        }
+    }
+}
+
+/// Uma das metades da busca: um A* comum sobre a adjacência do seu sentido. Na volta,
+/// `linha_chegada` dos nós é a linha pela qual se sai da estação rumo ao objetivo.
+#[derive(Debug, Clone)]
+pub struct MeiaBusca {
+    pub sentido: SentidoBusca,
+    pub fronteira: FilaPrioridadeIndexada<EstadoNoFronteira>,
+    pub explorados: HashSet<EstadoBusca>,
+    pub estacoes_exploradas: HashSet<IdEstacao>,
+    custos_g: HashMap<EstadoBusca, f32>,
+    predecessores: HashMap<EstadoBusca, EstadoBusca>,
+    linhas_por_estacao: HashMap<IdEstacao, Vec<Option<IdLinha>>>, // Estados com custo g conhecido, por estação
+    nos_na_fronteira_por_estacao: HashMap<IdEstacao, usize>,
+}
+
+impl MeiaBusca {
+    fn nova(sentido: SentidoBusca, estado_inicial: EstadoBusca, custo_h: f32) -> Self {
+        let mut meia_busca = MeiaBusca {
+            sentido,
+            fronteira: FilaPrioridadeIndexada::nova(),
+            explorados: HashSet::new(),
+            estacoes_exploradas: HashSet::new(),
+            custos_g: HashMap::new(),
+            predecessores: HashMap::new(),
+            linhas_por_estacao: HashMap::new(),
+            nos_na_fronteira_por_estacao: HashMap::new(),
+        };
+        meia_busca.inserir(EstadoNoFronteira {
+            id_estacao: estado_inicial.0,
+            linha_chegada: estado_inicial.1,
+            custo_f: custo_h,
+            custo_g_viagem: 0.0,
+            custo_h,
+            trechos: 0,
+        }, None);
+        meia_busca
+    }
+
+    /// Menor f da fronteira: limite inferior do custo de qualquer caminho que ainda passe por ela
+    pub fn menor_f(&self) -> Option<f32> {
+        self.fronteira.minimo().map(|no| no.custo_f)
+    }
+
+    pub fn custo_g(&self, estado: &EstadoBusca) -> Option<f32> {
+        self.custos_g.get(estado).copied()
+    }
+
+    pub fn estacao_na_fronteira(&self, id_estacao: IdEstacao) -> bool {
+        self.nos_na_fronteira_por_estacao.contains_key(&id_estacao)
+    }
+
+    fn remover_minimo(&mut self) -> Option<EstadoNoFronteira> {
+        let no = self.fronteira.remover_minimo()?;
+        if let Some(quantidade) = self.nos_na_fronteira_por_estacao.get_mut(&no.id_estacao) {
+            *quantidade -= 1;
+            if *quantidade == 0 {
+                self.nos_na_fronteira_por_estacao.remove(&no.id_estacao);
+            }
+        }
+        self.explorados.insert(no.estado());
+        self.estacoes_exploradas.insert(no.id_estacao);
+        Some(no)
+    }
+
+    /// Põe o nó na fronteira se ele melhora o custo g do estado; devolve se houve melhora
+    fn inserir(&mut self, no: EstadoNoFronteira, predecessor: Option<EstadoBusca>) -> bool {
+        let estado = no.estado();
+        if self.explorados.contains(&estado) || self.custos_g.get(&estado).is_some_and(|&custo_g| custo_g <= no.custo_g_viagem) {
+            return false;
+        }
+
+        if self.custos_g.insert(estado, no.custo_g_viagem).is_none() {
+            self.linhas_por_estacao.entry(estado.0).or_default().push(estado.1);
+        }
+        if let Some(predecessor) = predecessor {
+            self.predecessores.insert(estado, predecessor);
+        }
+        if self.fronteira.inserir_ou_diminuir(no) == ResultadoInsercao::Inserido {
+            *self.nos_na_fronteira_por_estacao.entry(estado.0).or_default() += 1;
+        }
+        true
+    }
+
+    /// Estados do início desta metade (origem na ida, objetivo na volta) até `estado_final`
+    fn estados_do_caminho(&self, estado_final: EstadoBusca) -> Vec<EstadoBusca> {
+        let mut estados = vec![estado_final];
+        while let Some(&anterior) = self.predecessores.get(estados.last().unwrap()) {
+            estados.push(anterior);
+        }
+        estados.reverse();
+        estados
+    }
+}
+
+/// Melhor ponto de encontro visto até agora: um estado da ida e um da volta na mesma estação
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Encontro {
+    pub estado_ida: EstadoBusca,
+    pub estado_volta: EstadoBusca,
+    pub custo_total: f32, // g da ida + g da volta + baldeação na estação de encontro, se as linhas diferem
+}
+
+/// A* bidirecional: uma busca parte da origem, outra do objetivo pela adjacência invertida, e os
+/// passos alternam entre elas. Cada estado relaxado é combinado com os estados da outra metade na
+/// mesma estação para atualizar o melhor encontro; a busca para quando nenhuma das fronteiras pode
+/// mais levar a um caminho mais barato (menor f de uma delas ≥ custo do encontro). Como no A*
+/// simples, a rota é ótima com heurística consistente.
+#[derive(Debug)]
+pub struct SolucionadorBidirecional {
+    grafo: Arc<GrafoMetro>,
+    adjacencia_reversa: Vec<Vec<Conexao>>,
+    id_inicio: IdEstacao,
+    id_objetivo: IdEstacao,
+    pub ida: MeiaBusca,
+    pub volta: MeiaBusca,
+    pub melhor_encontro: Option<Encontro>,
+    pub ultima_expansao: Option<(SentidoBusca, IdEstacao)>,
+    pub passo_atual: usize,
+    proximo_sentido: SentidoBusca,
+    resultado: Option<Option<InfoCaminho>>, // Preenchido quando a busca termina (None: sem caminho)
+}
+
+impl SolucionadorBidirecional {
+    pub fn novo(
+        grafo_compartilhado: Arc<GrafoMetro>,
+        id_inicio: IdEstacao,
+        linha_inicial_opcional: Option<IdLinha>,
+        id_objetivo: IdEstacao,
+    ) -> Self {
+        let custo_h_ida = grafo_compartilhado.obter_tempo_heuristico_minutos(id_inicio, id_objetivo).unwrap_or(0.0);
+        let mut solucionador = SolucionadorBidirecional {
+            adjacencia_reversa: grafo_compartilhado.lista_adjacencia_reversa(),
+            grafo: grafo_compartilhado,
+            id_inicio,
+            id_objetivo,
+            ida: MeiaBusca::nova(SentidoBusca::Ida, (id_inicio, linha_inicial_opcional), custo_h_ida),
+            // Ao chegar no objetivo não se sai por linha nenhuma
+            volta: MeiaBusca::nova(SentidoBusca::Volta, (id_objetivo, None), custo_h_ida),
+            melhor_encontro: None,
+            ultima_expansao: None,
+            passo_atual: 0,
+            proximo_sentido: SentidoBusca::Ida,
+            resultado: None,
+        };
+        // Origem igual ao objetivo: as duas partidas já se encontram
+        solucionador.atualizar_encontro(SentidoBusca::Ida, (id_inicio, linha_inicial_opcional));
+        solucionador
+    }
+
+    pub fn meia_busca(&self, sentido: SentidoBusca) -> &MeiaBusca {
+        match sentido {
+            SentidoBusca::Ida => &self.ida,
+            SentidoBusca::Volta => &self.volta,
+        }
+    }
+
+    fn meia_busca_mut(&mut self, sentido: SentidoBusca) -> &mut MeiaBusca {
+        match sentido {
+            SentidoBusca::Ida => &mut self.ida,
+            SentidoBusca::Volta => &mut self.volta,
+        }
+    }
+
+    /// Estimativa até o fim da metade: do vizinho ao objetivo na ida, da origem até ele na volta
+    fn heuristica(&self, sentido: SentidoBusca, id_estacao: IdEstacao) -> f32 {
+        let (de, para) = match sentido {
+            SentidoBusca::Ida => (id_estacao, self.id_objetivo),
+            SentidoBusca::Volta => (self.id_inicio, id_estacao),
+        };
+        self.grafo.obter_tempo_heuristico_minutos(de, para).unwrap_or(0.0)
+    }
+
+    pub fn esta_finalizado(&self) -> bool {
+        self.resultado.is_some()
+    }
+
+    /// Estados expandidos pelas duas metades
+    pub fn estados_expandidos(&self) -> usize {
+        self.ida.explorados.len() + self.volta.explorados.len()
+    }
+
+    /// Um passo: testa o critério de parada e, se a busca continua, expande um nó de uma das
+    /// metades (alternando ida e volta)
+    pub fn proximo_passo(&mut self) -> ResultadoPassoAEstrela {
+        if self.resultado.is_none() {
+            // Com uma fronteira vazia, todo caminho possível já foi combinado com a outra metade
+            let limite = self.ida.menor_f().zip(self.volta.menor_f()).map(|(f_ida, f_volta)| f_ida.max(f_volta));
+            let pode_parar = match (limite, self.melhor_encontro) {
+                (None, _) => true,
+                (Some(limite), Some(encontro)) => encontro.custo_total <= limite,
+                (Some(_), None) => false,
+            };
+            if !pode_parar {
+                let sentido = self.proximo_sentido;
+                self.proximo_sentido = sentido.oposto();
+                self.expandir(sentido);
+                return ResultadoPassoAEstrela::EmProgresso;
+            }
+            self.resultado = Some(self.melhor_encontro.map(|encontro| self.montar_caminho(encontro)));
+        }
+
+        match self.resultado.clone().flatten() {
+            Some(info_caminho) => ResultadoPassoAEstrela::CaminhoEncontrado(info_caminho),
+            None => ResultadoPassoAEstrela::NenhumCaminhoPossivel,
+        }
+    }
+
+    fn expandir(&mut self, sentido: SentidoBusca) {
+        let Some(no) = self.meia_busca_mut(sentido).remover_minimo() else { return };
+        self.passo_atual += 1;
+        self.ultima_expansao = Some((sentido, no.id_estacao));
+
+        let conexoes = match sentido {
+            SentidoBusca::Ida => self.grafo.lista_adjacencia[no.id_estacao].clone(),
+            SentidoBusca::Volta => self.adjacencia_reversa[no.id_estacao].clone(),
+        };
+        for conexao in conexoes {
+            // A mesma penalidade do A* simples: trocar de linha na estação expandida. Na volta a
+            // troca é entre a linha da conexão (chegada) e a linha de saída guardada no nó
+            let custo_baldeacao = match no.linha_chegada {
+                Some(linha) if linha != conexao.linha => self.grafo.tempo_baldeacao_em(no.id_estacao),
+                _ => 0.0,
+            };
+            let custo_g = no.custo_g_viagem + conexao.tempo_minutos + custo_baldeacao;
+            let custo_h = self.heuristica(sentido, conexao.para_estacao);
+            let vizinho = EstadoNoFronteira {
+                id_estacao: conexao.para_estacao,
+                linha_chegada: Some(conexao.linha),
+                custo_f: custo_g + custo_h,
+                custo_g_viagem: custo_g,
+                custo_h,
+                trechos: no.trechos + 1,
+            };
+            let estado_vizinho = vizinho.estado();
+            if self.meia_busca_mut(sentido).inserir(vizinho, Some(no.estado())) {
+                self.atualizar_encontro(sentido, estado_vizinho);
+            }
+        }
+    }
+
+    /// Combina um estado que acabou de ganhar custo g com os estados da outra metade na mesma estação
+    fn atualizar_encontro(&mut self, sentido: SentidoBusca, estado: EstadoBusca) {
+        let (id_estacao, linha) = estado;
+        let (busca, outra) = (self.meia_busca(sentido), self.meia_busca(sentido.oposto()));
+        let Some(custo_g) = busca.custo_g(&estado) else { return };
+
+        let melhor_nesta_estacao = outra.linhas_por_estacao.get(&id_estacao).into_iter().flatten()
+            .map(|&linha_outra| {
+                let estado_outro = (id_estacao, linha_outra);
+                let custo_baldeacao = match (linha, linha_outra) {
+                    (Some(a), Some(b)) if a != b => self.grafo.tempo_baldeacao_em(id_estacao),
+                    _ => 0.0,
+                };
+                (estado_outro, custo_g + outra.custos_g[&estado_outro] + custo_baldeacao)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((estado_outro, custo_total)) = melhor_nesta_estacao
+            && self.melhor_encontro.is_none_or(|encontro| custo_total < encontro.custo_total)
+        {
+            let (estado_ida, estado_volta) = match sentido {
+                SentidoBusca::Ida => (estado, estado_outro),
+                SentidoBusca::Volta => (estado_outro, estado),
+            };
+            self.melhor_encontro = Some(Encontro { estado_ida, estado_volta, custo_total });
+        }
+    }
+
+    /// Junta o caminho da ida até o encontro com o da volta, reescrito no sentido da viagem
+    fn montar_caminho(&self, encontro: Encontro) -> InfoCaminho {
+        let mut estados = self.ida.estados_do_caminho(encontro.estado_ida);
+        let mut volta = self.volta.estados_do_caminho(encontro.estado_volta);
+        volta.reverse();
+        // Na volta cada estado guarda a linha de saída: o trecho até a estação seguinte usa essa linha
+        for par in volta.windows(2) {
+            estados.push((par[1].0, par[0].1));
+        }
+        InfoCaminho::dos_estados(&self.grafo, estados, encontro.custo_total)
+    }
+}
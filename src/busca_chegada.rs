@@ -0,0 +1,135 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::algoritmo_a_estrela::{EstadoBusca, InfoCaminho};
+use crate::fila_prioridade::{FilaPrioridadeIndexada, ItemFila, ResultadoInsercao};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+use crate::quadro_horarios::{ConsultaHorarios, QuadroHorarios};
+
+/// Estado da busca reversa, (estação, linha de saída), com o horário mais tarde em que se pode
+/// partir dela por essa linha e ainda chegar ao objetivo no horário
+#[derive(Debug, Clone)]
+struct RotuloChegada {
+    estado: EstadoBusca,
+    partida_mais_tarde: f32,
+}
+
+// A fila devolve a menor prioridade; aqui sai primeiro o horário mais tarde
+impl ItemFila for RotuloChegada {
+    type Chave = EstadoBusca;
+
+    fn chave(&self) -> Self::Chave {
+        self.estado
+    }
+
+    fn prioridade(&self) -> f32 {
+        -self.partida_mais_tarde
+    }
+}
+
+/// Resultado de uma consulta "chegar até": o itinerário que parte mais tarde, se houver
+#[derive(Debug, Clone, Default)]
+pub struct PartidaMaisTarde {
+    /// Com os horários de cada trecho a partir do horário de partida encontrado
+    pub caminho: Option<InfoCaminho>,
+    pub estados_expandidos: usize,
+}
+
+/// Consulta "chegar até": o horário mais tarde para sair da origem e chegar ao objetivo até
+/// `horario_chegada`, e por qual rota. É um Dijkstra reverso a partir do objetivo, sobre as
+/// conexões invertidas e o mesmo modelo de custo da ida (baldeação e espera pelo próximo trem ao
+/// embarcar), em ordem decrescente de horário. Como a espera é monotônica (quem chega antes à
+/// plataforma nunca parte depois), o primeiro horário fixado em cada estado é o mais tarde possível.
+/// O itinerário devolvido é refeito no sentido da viagem a partir do horário de partida, então
+/// pode chegar antes do horário pedido, nunca depois.
+pub fn buscar_partida_mais_tarde(
+    grafo: &GrafoMetro,
+    quadro: &Arc<QuadroHorarios>,
+    id_inicio: IdEstacao,
+    linha_inicial: Option<IdLinha>,
+    id_objetivo: IdEstacao,
+    horario_chegada: f32,
+) -> PartidaMaisTarde {
+    let consulta = |horario_partida| ConsultaHorarios { quadro: Arc::clone(quadro), horario_partida };
+    if id_inicio == id_objetivo {
+        let caminho = InfoCaminho::dos_estados(grafo, vec![(id_inicio, linha_inicial)], 0.0);
+        return PartidaMaisTarde { caminho: Some(caminho.com_horarios(grafo, &consulta(horario_chegada))), estados_expandidos: 0 };
+    }
+
+    let adjacencia_reversa = grafo.lista_adjacencia_reversa();
+    let mut fronteira = FilaPrioridadeIndexada::nova();
+    let mut fixados: HashSet<EstadoBusca> = HashSet::new();
+    // Estado seguinte no sentido da viagem; None quando a conexão já chega ao objetivo
+    let mut sucessores: HashMap<EstadoBusca, Option<EstadoBusca>> = HashMap::new();
+    let mut melhor_partida: Option<(f32, EstadoBusca)> = None;
+    let mut estados_expandidos = 0;
+
+    let mut relaxar = |fronteira: &mut FilaPrioridadeIndexada<RotuloChegada>, fixados: &HashSet<EstadoBusca>,
+                       estado: EstadoBusca, partida_mais_tarde: f32, sucessor: Option<EstadoBusca>| {
+        if !fixados.contains(&estado)
+            && fronteira.inserir_ou_diminuir(RotuloChegada { estado, partida_mais_tarde }) != ResultadoInsercao::Ignorado
+        {
+            sucessores.insert(estado, sucessor);
+        }
+    };
+
+    // Cada conexão que chega ao objetivo: parte-se dela até o horário de chegada menos o trecho
+    for conexao in &adjacencia_reversa[id_objetivo] {
+        relaxar(&mut fronteira, &fixados, (conexao.para_estacao, Some(conexao.linha)),
+            horario_chegada - conexao.tempo_minutos, None);
+    }
+
+    while let Some(rotulo) = fronteira.remover_minimo() {
+        // Daqui em diante os horários só diminuem e a partida da origem nunca é depois deles
+        if melhor_partida.is_some_and(|(melhor, _)| rotulo.partida_mais_tarde < melhor) {
+            break;
+        }
+        let ((id_estacao, linha_saida), partida_mais_tarde) = (rotulo.estado, rotulo.partida_mais_tarde);
+        let Some(id_linha_saida) = linha_saida else { continue };
+        fixados.insert(rotulo.estado);
+        estados_expandidos += 1;
+
+        // Embarcar na linha de saída: o trem tem que partir até o horário do estado
+        let ultimo_embarque = quadro.partida_anterior(id_linha_saida, id_estacao, partida_mais_tarde);
+        let baldeacao = grafo.tempo_baldeacao_em(id_estacao);
+
+        if id_estacao == id_inicio {
+            // Mesma regra da ida: já na linha de partida não há espera; sem linha, o primeiro embarque não é baldeação
+            let partida = match linha_inicial {
+                Some(linha) if linha == id_linha_saida => partida_mais_tarde,
+                Some(_) => ultimo_embarque - baldeacao,
+                None => ultimo_embarque,
+            };
+            if melhor_partida.is_none_or(|(melhor, _)| partida > melhor) {
+                melhor_partida = Some((partida, rotulo.estado));
+            }
+        }
+
+        // A viagem termina na primeira chegada ao objetivo, então ele não é ponto de passagem
+        for conexao in adjacencia_reversa[id_estacao].iter().filter(|conexao| conexao.para_estacao != id_objetivo) {
+            // Seguindo na mesma linha não há espera; trocando, chega-se a tempo da baldeação e do embarque
+            let chegada_mais_tarde = if conexao.linha == id_linha_saida { partida_mais_tarde } else { ultimo_embarque - baldeacao };
+            relaxar(&mut fronteira, &fixados, (conexao.para_estacao, Some(conexao.linha)),
+                chegada_mais_tarde - conexao.tempo_minutos, Some(rotulo.estado));
+        }
+    }
+
+    let caminho = melhor_partida.map(|(horario_partida, estado_inicial)| {
+        let mut estados = vec![(id_inicio, linha_inicial)];
+        let mut atual = estado_inicial;
+        loop {
+            match sucessores[&atual] {
+                Some(seguinte) => {
+                    estados.push((seguinte.0, atual.1));
+                    atual = seguinte;
+                }
+                None => {
+                    estados.push((id_objetivo, atual.1));
+                    break;
+                }
+            }
+        }
+        InfoCaminho::dos_estados(grafo, estados, 0.0).com_horarios(grafo, &consulta(horario_partida))
+    });
+    PartidaMaisTarde { caminho, estados_expandidos }
+}
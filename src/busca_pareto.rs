@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::algoritmo_a_estrela::{EstadoBusca, InfoCaminho};
+use crate::fila_prioridade::{FilaPrioridadeIndexada, ItemFila, ResultadoInsercao};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+
+/// Folga para considerar dois tempos iguais
+const TOLERANCIA: f32 = 1e-3;
+
+/// Estado da busca multicritério: estação, linha de chegada e baldeações feitas até ali
+pub type EstadoPareto = (IdEstacao, Option<IdLinha>, u32);
+
+/// Rótulo (tempo, baldeações) de um estado; as baldeações fazem parte da chave, então
+/// o mesmo (estação, linha) pode estar na fila com várias contagens de baldeação
+#[derive(Debug, Clone)]
+struct RotuloPareto {
+    estado: EstadoPareto,
+    tempo_minutos: f32,
+}
+
+impl ItemFila for RotuloPareto {
+    type Chave = EstadoPareto;
+
+    fn chave(&self) -> Self::Chave {
+        self.estado
+    }
+
+    fn prioridade(&self) -> f32 {
+        self.tempo_minutos
+    }
+}
+
+/// Conjunto de Pareto entre duas estações: nenhuma rota é ao mesmo tempo mais rápida (ou tão
+/// rápida) e com menos baldeações que outra do conjunto
+#[derive(Debug, Clone, Default)]
+pub struct RotasPareto {
+    /// Da mais rápida à com menos baldeações: o tempo cresce e as baldeações diminuem
+    pub rotas: Vec<InfoCaminho>,
+    /// Rótulos retirados da fila e não descartados por dominância
+    pub rotulos_expandidos: usize,
+}
+
+/// Busca multicritério (tempo, baldeações) por fixação de rótulos: um Dijkstra sobre o estado
+/// (estação, linha, baldeações), em ordem de tempo. Um rótulo é descartado quando o mesmo
+/// (estação, linha) já foi fixado com no máximo as mesmas baldeações, porque esse chegou antes
+/// e tudo o que vem depois depende só da estação e da linha. O tempo inclui as penalidades de
+/// baldeação, como no A*; a rota mais rápida do conjunto é a mesma que o A* encontra.
+pub fn buscar_rotas_pareto(
+    grafo: &GrafoMetro,
+    id_inicio: IdEstacao,
+    linha_inicial: Option<IdLinha>,
+    id_objetivo: IdEstacao,
+) -> RotasPareto {
+    let mut fronteira = FilaPrioridadeIndexada::nova();
+    let mut predecessores: HashMap<EstadoPareto, EstadoPareto> = HashMap::new();
+    let mut menor_baldeacoes_fixada: HashMap<EstadoBusca, u32> = HashMap::new();
+    let mut menor_baldeacoes_no_objetivo: Option<u32> = None;
+    let mut chegadas: Vec<(EstadoPareto, f32)> = Vec::new();
+    let mut rotulos_expandidos = 0;
+
+    // Dominado por um rótulo já fixado no mesmo estado ou por uma rota já encontrada
+    let dominado = |fixadas: &HashMap<EstadoBusca, u32>, no_objetivo: Option<u32>, (id_estacao, linha, baldeacoes): EstadoPareto| {
+        fixadas.get(&(id_estacao, linha)).is_some_and(|&menor| menor <= baldeacoes)
+            || no_objetivo.is_some_and(|menor| menor <= baldeacoes)
+    };
+
+    fronteira.inserir_ou_diminuir(RotuloPareto { estado: (id_inicio, linha_inicial, 0), tempo_minutos: 0.0 });
+    while let Some(rotulo) = fronteira.remover_minimo() {
+        if dominado(&menor_baldeacoes_fixada, menor_baldeacoes_no_objetivo, rotulo.estado) {
+            fronteira.registrar_remocao_obsoleta();
+            continue;
+        }
+        let (id_estacao, linha, baldeacoes) = rotulo.estado;
+        menor_baldeacoes_fixada.insert((id_estacao, linha), baldeacoes);
+        rotulos_expandidos += 1;
+
+        if id_estacao == id_objetivo {
+            menor_baldeacoes_no_objetivo = Some(baldeacoes);
+            chegadas.push((rotulo.estado, rotulo.tempo_minutos));
+            continue;
+        }
+
+        for conexao in &grafo.lista_adjacencia[id_estacao] {
+            // Mesma regra do A*: partindo sem linha definida, o primeiro embarque não é baldeação
+            let troca_de_linha = linha.is_some_and(|linha_atual| linha_atual != conexao.linha);
+            let penalidade = if troca_de_linha { grafo.tempo_baldeacao_em(id_estacao) } else { 0.0 };
+            let vizinho = (conexao.para_estacao, Some(conexao.linha), baldeacoes + troca_de_linha as u32);
+            if dominado(&menor_baldeacoes_fixada, menor_baldeacoes_no_objetivo, vizinho) {
+                continue;
+            }
+            let novo_rotulo = RotuloPareto { estado: vizinho, tempo_minutos: rotulo.tempo_minutos + conexao.tempo_minutos + penalidade };
+            if fronteira.inserir_ou_diminuir(novo_rotulo) != ResultadoInsercao::Ignorado {
+                predecessores.insert(vizinho, rotulo.estado);
+            }
+        }
+    }
+
+    // As chegadas saem em ordem de tempo com baldeações decrescentes; com tempos empatados,
+    // fica só a de menos baldeações
+    let rotas = chegadas.iter().enumerate()
+        .filter(|&(indice, &(_, tempo))| chegadas.get(indice + 1).is_none_or(|&(_, proximo)| proximo > tempo + TOLERANCIA))
+        .map(|(_, &(estado_final, tempo))| {
+            let mut estados = vec![estado_final];
+            while let Some(&anterior) = predecessores.get(estados.last().unwrap()) {
+                estados.push(anterior);
+            }
+            let estados = estados.into_iter().rev().map(|(id_estacao, linha, _)| (id_estacao, linha)).collect();
+            InfoCaminho::dos_estados(grafo, estados, tempo)
+        })
+        .collect();
+
+    RotasPareto { rotas, rotulos_expandidos }
+}
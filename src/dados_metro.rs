@@ -1,138 +1,434 @@
 use crate::grafo_metro::*;
-use std::error::Error;
+use crate::erro_carregamento::{AvisoCarregamento, ColetorAvisos, ErroCarregamento, LocalDados, ModoCarregamento};
+use std::collections::HashMap;
 use std::fs::File;
-use csv::ReaderBuilder;
+use std::path::Path;
+use csv::{ReaderBuilder, StringRecord};
 
-impl GrafoMetro {
-    pub fn carregar_distancias_heuristicas(&mut self, caminho_arquivo: &str) -> Result<(), Box<dyn Error>> {
-        println!("Carregando distâncias heurísticas de: {}", caminho_arquivo);
-        let arquivo = File::open(caminho_arquivo)?;
+/// Matriz quadrada lida de um CSV: nomes das estações das colunas e, para cada
+/// linha do arquivo, o nome da estação de origem e as células na ordem das colunas
+struct MatrizCsv {
+    nomes_colunas: Vec<String>,
+    linhas: Vec<(String, StringRecord)>,
+}
+
+fn ler_matriz_csv(caminho_arquivo: &str) -> Result<MatrizCsv, ErroCarregamento> {
+    let arquivo = File::open(caminho_arquivo).map_err(ErroCarregamento::io(caminho_arquivo))?;
+    let mut leitor_csv = ReaderBuilder::new()
+        .delimiter(b';')
+        .has_headers(true)
+        .from_reader(arquivo);
+
+    // A primeira célula do cabeçalho é vazia; as demais são os nomes das estações
+    let nomes_colunas = leitor_csv.headers().map_err(ErroCarregamento::csv(caminho_arquivo))?
+        .iter()
+        .skip(1)
+        .map(|nome| nome.trim().to_string())
+        .collect();
+
+    let mut linhas = Vec::new();
+    for resultado_linha in leitor_csv.records() {
+        let registro = resultado_linha.map_err(ErroCarregamento::csv(caminho_arquivo))?;
+        let nome_origem = registro.get(0).unwrap_or("").trim().to_string();
+        if nome_origem.is_empty() {
+            continue;
+        }
+        linhas.push((nome_origem, registro));
+    }
+
+    Ok(MatrizCsv { nomes_colunas, linhas })
+}
+
+/// Tabela CSV com cabeçalho nomeado (linhas.csv, estacoes.csv)
+struct TabelaCsv {
+    cabecalho: StringRecord,
+    registros: Vec<StringRecord>,
+}
+
+impl TabelaCsv {
+    fn ler(caminho_arquivo: &str) -> Result<Self, ErroCarregamento> {
+        let arquivo = File::open(caminho_arquivo).map_err(ErroCarregamento::io(caminho_arquivo))?;
         let mut leitor_csv = ReaderBuilder::new()
             .delimiter(b';')
             .has_headers(true)
+            .flexible(true)
             .from_reader(arquivo);
+        let cabecalho = leitor_csv.headers().map_err(ErroCarregamento::csv(caminho_arquivo))?.clone();
+        let registros = leitor_csv.records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ErroCarregamento::csv(caminho_arquivo))?;
+        Ok(TabelaCsv { cabecalho, registros })
+    }
+
+    fn coluna(&self, nome: &str) -> Option<usize> {
+        self.cabecalho.iter().position(|c| c.trim().eq_ignore_ascii_case(nome))
+    }
+}
+
+/// Valor de uma célula sem espaços; `None` se a coluna não existe ou a célula está vazia
+fn celula(registro: &StringRecord, coluna: Option<usize>) -> Option<&str> {
+    coluna.and_then(|c| registro.get(c)).map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Interpreta uma célula opcional. Valores que não puderem ser interpretados geram
+/// um aviso (ou erro, no modo estrito) e são tratados como ausentes.
+fn interpretar_celula<T>(
+    registro: &StringRecord,
+    coluna: Option<usize>,
+    caminho_arquivo: &str,
+    nome_coluna: &str,
+    esperado: &'static str,
+    interpretar: impl FnOnce(&str) -> Option<T>,
+    coletor: &mut ColetorAvisos,
+) -> Result<Option<T>, ErroCarregamento> {
+    let Some(valor) = celula(registro, coluna) else { return Ok(None) };
+    match interpretar(valor) {
+        Some(interpretado) => Ok(Some(interpretado)),
+        None => {
+            coletor.registrar(AvisoCarregamento::ValorInvalido {
+                local: LocalDados::celula(caminho_arquivo, registro.position().map(|p| p.line()), nome_coluna),
+                valor: valor.to_string(),
+                esperado,
+            })?;
+            Ok(None)
+        }
+    }
+}
+
+fn parsear_celula_f32(valor_str: &str) -> Option<Result<f32, std::num::ParseFloatError>> {
+    let valor_limpo = valor_str.trim();
+    if valor_limpo.is_empty() {
+        return None;
+    }
+    Some(valor_limpo.replace([',', ';'], ".").parse::<f32>())
+}
+
+fn parsear_numero<T: std::str::FromStr>(valor_str: &str) -> Option<T> {
+    valor_str.replace(',', ".").parse::<T>().ok()
+}
+
+fn parsear_booleano(valor_str: &str) -> Option<bool> {
+    match valor_str.trim().to_lowercase().as_str() {
+        "1" | "sim" | "s" | "true" | "yes" => Some(true),
+        "0" | "nao" | "não" | "n" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// De onde carregar a rede
+#[derive(Debug, Clone)]
+pub enum FonteRede {
+    /// Diretório com as tabelas CSV (linhas.csv, estacoes.csv e a tabela de distâncias diretas são opcionais)
+    TabelasCsv(String),
+    /// Feed GTFS estático, em diretório ou .zip
+    Gtfs(String),
+    /// Arquivo de rede único (.json ou .toml)
+    ArquivoRede(String),
+}
+
+impl FonteRede {
+    /// Escolhe a fonte pelo caminho: .json/.toml é um arquivo de rede, .zip ou um diretório com
+    /// stops.txt é um feed GTFS, e qualquer outro diretório contém as tabelas CSV
+    pub fn do_caminho(caminho: &str) -> Self {
+        let extensao = Path::new(caminho).extension().and_then(|ext| ext.to_str()).map(str::to_lowercase);
+        match extensao.as_deref() {
+            Some("json" | "toml") => FonteRede::ArquivoRede(caminho.to_string()),
+            Some("zip") => FonteRede::Gtfs(caminho.to_string()),
+            _ if Path::new(caminho).join("stops.txt").exists() => FonteRede::Gtfs(caminho.to_string()),
+            _ => FonteRede::TabelasCsv(caminho.to_string()),
+        }
+    }
+
+    /// Carrega a rede e devolve os avisos acumulados (sempre vazios no modo estrito)
+    pub fn carregar(&self, modo: ModoCarregamento) -> Result<(GrafoMetro, Vec<AvisoCarregamento>), ErroCarregamento> {
+        let mut grafo = GrafoMetro::novo();
+        let mut avisos = Vec::new();
+        match self {
+            FonteRede::TabelasCsv(diretorio) => {
+                let arquivo = |nome: &str| Path::new(diretorio).join(nome).to_string_lossy().into_owned();
+                if Path::new(&arquivo("linhas.csv")).exists() {
+                    avisos.extend(grafo.carregar_linhas(&arquivo("linhas.csv"), modo)?);
+                }
+                if Path::new(&arquivo("estacoes.csv")).exists() {
+                    avisos.extend(grafo.carregar_estacoes(&arquivo("estacoes.csv"), modo)?);
+                }
+                // Sem a tabela de distâncias diretas a heurística usa as coordenadas de estacoes.csv
+                if Path::new(&arquivo("tabela1_distancias_diretas.csv")).exists() {
+                    avisos.extend(grafo.carregar_distancias_heuristicas(&arquivo("tabela1_distancias_diretas.csv"), modo)?);
+                }
+                avisos.extend(grafo.carregar_conexoes(
+                    &arquivo("tabela2_distancias_reais.csv"),
+                    &arquivo("tabela_linhas_conexao.csv"),
+                    modo,
+                )?);
+            }
+            FonteRede::Gtfs(caminho) => avisos.extend(grafo.carregar_gtfs(caminho, modo)?),
+            FonteRede::ArquivoRede(caminho) => avisos.extend(grafo.carregar_arquivo_rede(caminho, modo)?),
+        }
+        Ok((grafo, avisos))
+    }
+}
+
+/// Converte uma cor hexadecimal ("#RRGGBB" ou "RRGGBB") em RGB
+pub(crate) fn parsear_cor_hex(valor: &str) -> Option<[u8; 3]> {
+    let hex = valor.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let componente = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([componente(0)?, componente(2)?, componente(4)?])
+}
+
+impl GrafoMetro {
+    /// Carrega o arquivo de linhas (`codigo;nome;cor;modo;velocidade_kmh`).
+    /// `codigo` é o valor usado na tabela de linhas de conexão; `cor` é hexadecimal (#RRGGBB),
+    /// `modo` é metro, rer, tram, onibus ou a_pe, e `velocidade_kmh` pode ficar vazia.
+    pub fn carregar_linhas(
+        &mut self,
+        caminho_arquivo: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        let tabela = TabelaCsv::ler(caminho_arquivo)?;
+        let col_codigo = tabela.coluna("codigo")
+            .ok_or_else(|| ErroCarregamento::coluna_ausente(caminho_arquivo, "codigo"))?;
+        let col_nome = tabela.coluna("nome");
+        let col_cor = tabela.coluna("cor");
+        let col_modo = tabela.coluna("modo");
+        let col_velocidade = tabela.coluna("velocidade_kmh");
+
+        for registro in &tabela.registros {
+            let Some(codigo) = celula(registro, Some(col_codigo)) else { continue };
+            let nome = celula(registro, col_nome).unwrap_or(codigo);
+            let cor_rgb = interpretar_celula(registro, col_cor, caminho_arquivo, "cor",
+                "cor hexadecimal #RRGGBB", parsear_cor_hex, &mut coletor)?
+                .unwrap_or(COR_LINHA_PADRAO);
+            let modo = interpretar_celula(registro, col_modo, caminho_arquivo, "modo",
+                "metro, rer, tram, onibus ou a_pe", ModoTransporte::de_texto, &mut coletor)?
+                .unwrap_or_default();
+            let velocidade_kmh = interpretar_celula(registro, col_velocidade, caminho_arquivo, "velocidade_kmh",
+                "velocidade positiva em km/h", |v| parsear_numero::<f32>(v).filter(|&v| v > 0.0), &mut coletor)?;
 
-        for (id_linha_atual, resultado_linha) in leitor_csv.records().enumerate() {
-            if id_linha_atual >= NUMERO_ESTACOES { break; }
-            let registro = resultado_linha?;
-            for id_coluna_destino in 0..NUMERO_ESTACOES {
-                let indice_no_csv = id_coluna_destino + 1;
-                if indice_no_csv < registro.len() {
-                    match registro.get(indice_no_csv) {
-                        Some(valor_str) if !valor_str.trim().is_empty() => {
-                            let valor_normalizado = valor_str.trim().replace(',', ".").replace(';', ".");
-                            match valor_normalizado.parse::<f32>() {
-                                Ok(valor_f32) => {
-                                    if valor_f32 >= 0.0 {
-                                        self.distancias_heuristicas_km[id_linha_atual][id_coluna_destino] = Some(valor_f32);
-                                        if id_linha_atual < 2 && id_coluna_destino < 2 {
-                                            println!("Heurística E{} -> E{}: {}", 
-                                                 id_linha_atual+1, id_coluna_destino+1, valor_f32);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!(
-                                        "Aviso: Erro ao parsear heurística na linha {} (E{}), coluna {} (E{}): '{}' ({})",
-                                        id_linha_atual + 1, id_linha_atual + 1,
-                                        id_coluna_destino + 1, id_coluna_destino + 1,
-                                        valor_str, e
-                                    );
-                                }
-                            }
-                        }
-                        _ => {} 
+            let id = self.adicionar_linha(codigo, nome, cor_rgb);
+            let linha = &mut self.linhas[id];
+            linha.nome = nome.to_string();
+            linha.cor_rgb = cor_rgb;
+            linha.modo = modo;
+            linha.velocidade_kmh = velocidade_kmh;
+        }
+        Ok(coletor.concluir())
+    }
+
+    /// Carrega o arquivo de estações (`codigo;nome;latitude;longitude;x_km;y_km;zona;acessivel`).
+    /// Só `codigo` e `nome` são obrigatórios; as demais colunas podem faltar ou ficar vazias.
+    /// As estações são identificadas pelo código, o mesmo usado nos cabeçalhos das matrizes.
+    pub fn carregar_estacoes(
+        &mut self,
+        caminho_arquivo: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        let tabela = TabelaCsv::ler(caminho_arquivo)?;
+        let col_codigo = tabela.coluna("codigo")
+            .ok_or_else(|| ErroCarregamento::coluna_ausente(caminho_arquivo, "codigo"))?;
+        let col_nome = tabela.coluna("nome")
+            .ok_or_else(|| ErroCarregamento::coluna_ausente(caminho_arquivo, "nome"))?;
+        let col_latitude = tabela.coluna("latitude");
+        let col_longitude = tabela.coluna("longitude");
+        let col_x = tabela.coluna("x_km");
+        let col_y = tabela.coluna("y_km");
+        let col_zona = tabela.coluna("zona");
+        let col_acessivel = tabela.coluna("acessivel");
+
+        for registro in &tabela.registros {
+            let Some(codigo) = celula(registro, Some(col_codigo)) else { continue };
+            let id = self.adicionar_estacao(codigo);
+            if let Some(nome) = celula(registro, Some(col_nome)) {
+                self.renomear_estacao(id, nome);
+            }
+
+            let mut numero = |coluna: Option<usize>, nome_coluna: &str| {
+                interpretar_celula(registro, coluna, caminho_arquivo, nome_coluna, "número", parsear_numero::<f64>, &mut coletor)
+            };
+            let latitude = numero(col_latitude, "latitude")?;
+            let longitude = numero(col_longitude, "longitude")?;
+            let x_km = numero(col_x, "x_km")?;
+            let y_km = numero(col_y, "y_km")?;
+            let coordenadas = match (latitude, longitude, x_km, y_km) {
+                (Some(latitude), Some(longitude), _, _) => Some(Coordenadas::Geograficas { latitude, longitude }),
+                (_, _, Some(x_km), Some(y_km)) => Some(Coordenadas::Planas { x_km: x_km as f32, y_km: y_km as f32 }),
+                _ => None,
+            };
+            let zona = interpretar_celula(registro, col_zona, caminho_arquivo, "zona",
+                "número de zona (0 a 255)", parsear_numero::<u8>, &mut coletor)?;
+            let acessivel = interpretar_celula(registro, col_acessivel, caminho_arquivo, "acessivel",
+                "sim ou nao", parsear_booleano, &mut coletor)?;
+
+            let estacao = &mut self.estacoes[id];
+            estacao.coordenadas = coordenadas;
+            estacao.zona = zona;
+            estacao.acessivel = acessivel.unwrap_or(false);
+        }
+        Ok(coletor.concluir())
+    }
+
+    /// Garante que todas as estações citadas na matriz existam no grafo e devolve
+    /// os ids das colunas, na ordem em que aparecem no arquivo
+    fn registrar_estacoes_da_matriz(&mut self, matriz: &MatrizCsv) -> Vec<IdEstacao> {
+        let ids_colunas = matriz.nomes_colunas.iter()
+            .map(|nome| self.adicionar_estacao(nome))
+            .collect();
+        for (nome_origem, _) in &matriz.linhas {
+            self.adicionar_estacao(nome_origem);
+        }
+        ids_colunas
+    }
+
+    pub fn carregar_distancias_heuristicas(
+        &mut self,
+        caminho_arquivo: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        let matriz = ler_matriz_csv(caminho_arquivo)?;
+        let ids_colunas = self.registrar_estacoes_da_matriz(&matriz);
+
+        for (nome_origem, registro) in &matriz.linhas {
+            let id_origem = self.adicionar_estacao(nome_origem);
+            for (indice_coluna, &id_destino) in ids_colunas.iter().enumerate() {
+                let Some(valor_str) = registro.get(indice_coluna + 1) else { continue };
+                let nome_destino = &matriz.nomes_colunas[indice_coluna];
+                let local = || LocalDados::celula(caminho_arquivo, registro.position().map(|p| p.line()), nome_destino);
+                match parsear_celula_f32(valor_str) {
+                    Some(Ok(valor_f32)) if valor_f32 >= 0.0 => {
+                        self.definir_distancia_heuristica(id_origem, id_destino, valor_f32);
                     }
+                    Some(Ok(valor_f32)) => {
+                        coletor.registrar(AvisoCarregamento::DistanciaNegativa {
+                            local: local(),
+                            estacao_origem: nome_origem.clone(),
+                            estacao_destino: nome_destino.clone(),
+                            valor: valor_f32,
+                        })?;
+                    }
+                    Some(Err(_)) => {
+                        coletor.registrar(AvisoCarregamento::ValorInvalido {
+                            local: local(),
+                            valor: valor_str.trim().to_string(),
+                            esperado: "distância em km",
+                        })?;
+                    }
+                    None => {}
                 }
             }
         }
-        Ok(())
+        Ok(coletor.concluir())
     }
 
+    /// Cria as conexões a partir da matriz de distâncias reais (valores <= 0 significam
+    /// "sem conexão") e da matriz com o código da linha de cada conexão (0 ou vazio: sem conexão).
+    /// As duas matrizes precisam concordar: uma linha sem distância, ou uma distância sem
+    /// linha, é uma inconsistência.
     pub fn carregar_conexoes(
         &mut self,
         caminho_dist_reais: &str,
         caminho_linhas_conexao: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        println!("Carregando distâncias reais de: {}", caminho_dist_reais);
-        let mut matriz_distancias_reais = vec![vec![None::<f32>; NUMERO_ESTACOES]; NUMERO_ESTACOES];
-        let arquivo_dist = File::open(caminho_dist_reais)?;
-        let mut leitor_dist = ReaderBuilder::new().delimiter(b';').has_headers(true).from_reader(arquivo_dist);
-
-        for (id_linha_atual, resultado_linha) in leitor_dist.records().enumerate() {
-            if id_linha_atual >= NUMERO_ESTACOES { break; }
-            let registro = resultado_linha?;
-            for id_coluna_destino in 0..NUMERO_ESTACOES {
-                let indice_no_csv = id_coluna_destino + 1;
-                if indice_no_csv < registro.len() {
-                    if let Some(valor_str) = registro.get(indice_no_csv) {
-                        if !valor_str.trim().is_empty() {
-                            let valor_normalizado = valor_str.trim().replace(',', ".").replace(';', ".");
-                            if let Ok(valor_f32) = valor_normalizado.parse::<f32>() {
-                                if valor_f32 > 0.0 {
-                                    matriz_distancias_reais[id_linha_atual][id_coluna_destino] = Some(valor_f32);
-                                    if id_linha_atual < 2 && id_coluna_destino < 2 {
-                                        println!("Distância real E{} -> E{}: {}", 
-                                             id_linha_atual+1, id_coluna_destino+1, valor_f32);
-                                    }
-                                }
-                            }
-                        }
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        let matriz_dist = ler_matriz_csv(caminho_dist_reais)?;
+        let ids_colunas_dist = self.registrar_estacoes_da_matriz(&matriz_dist);
+
+        // Distância de cada par, com a linha do arquivo em que foi lida
+        let mut distancias_reais: HashMap<(IdEstacao, IdEstacao), (f32, Option<u64>)> = HashMap::new();
+        for (nome_origem, registro) in &matriz_dist.linhas {
+            let id_origem = self.adicionar_estacao(nome_origem);
+            for (indice_coluna, &id_destino) in ids_colunas_dist.iter().enumerate() {
+                let Some(valor_str) = registro.get(indice_coluna + 1) else { continue };
+                let nome_destino = &matriz_dist.nomes_colunas[indice_coluna];
+                match parsear_celula_f32(valor_str) {
+                    Some(Ok(valor_f32)) if valor_f32 > 0.0 => {
+                        distancias_reais.insert((id_origem, id_destino), (valor_f32, registro.position().map(|p| p.line())));
+                    }
+                    Some(Err(_)) => {
+                        coletor.registrar(AvisoCarregamento::ValorInvalido {
+                            local: LocalDados::celula(caminho_dist_reais, registro.position().map(|p| p.line()), nome_destino),
+                            valor: valor_str.trim().to_string(),
+                            esperado: "distância em km (ou -1 sem conexão)",
+                        })?;
                     }
+                    _ => {}
                 }
             }
         }
 
-        println!("Carregando linhas de conexão de: {}", caminho_linhas_conexao);
-        let arquivo_linhas = File::open(caminho_linhas_conexao)?;
-        let mut leitor_linhas = ReaderBuilder::new().delimiter(b';').has_headers(true).from_reader(arquivo_linhas);
+        let matriz_linhas = ler_matriz_csv(caminho_linhas_conexao)?;
+        let ids_colunas_linhas = self.registrar_estacoes_da_matriz(&matriz_linhas);
+        let mut distancias_usadas = std::collections::HashSet::new();
 
-        for (id_estacao_origem, resultado_linha) in leitor_linhas.records().enumerate() {
-            if id_estacao_origem >= NUMERO_ESTACOES { break; }
-            let registro_linha = resultado_linha?;
+        for (nome_origem, registro_linha) in &matriz_linhas.linhas {
+            let id_estacao_origem = self.adicionar_estacao(nome_origem);
 
-            for id_estacao_destino in 0..NUMERO_ESTACOES {
+            for (indice_coluna, &id_estacao_destino) in ids_colunas_linhas.iter().enumerate() {
                 if id_estacao_origem == id_estacao_destino { continue; }
 
-                let indice_no_csv = id_estacao_destino + 1;
-                if indice_no_csv < registro_linha.len() {
-                    if let Some(cor_linha_str) = registro_linha.get(indice_no_csv) {
-                        if !cor_linha_str.trim().is_empty() {
-                            if let Ok(cor_linha_int) = cor_linha_str.trim().parse::<u8>() {
-                                if cor_linha_int > 0 {
-                                    let cor_da_linha = CorLinha::de_inteiro(cor_linha_int);
-                                    if cor_da_linha == CorLinha::Nenhuma {
-                                        eprintln!("Aviso: Cor de linha inválida ({}) entre E{} e E{}",
-                                            cor_linha_int, id_estacao_origem + 1, id_estacao_destino + 1);
-                                        continue;
-                                    }
-                                    
-                                    if let Some(distancia_real_km) = matriz_distancias_reais[id_estacao_origem][id_estacao_destino] {
-                                        let tempo_viagem_minutos = distancia_real_km * 2.0;
-                                        
-                                        println!("CONEXÃO: E{} -> E{} (Linha: {:?}, Dist: {:.2}km, Tempo: {:.2}min)",
-                                            id_estacao_origem+1, id_estacao_destino+1, cor_da_linha, 
-                                            distancia_real_km, tempo_viagem_minutos);
-                                        
-                                        self.lista_adjacencia[id_estacao_origem].push(Conexao {
-                                            para_estacao: id_estacao_destino,
-                                            cor_linha: cor_da_linha,
-                                            distancia_km: distancia_real_km,
-                                            tempo_minutos: tempo_viagem_minutos,
-                                        });
-                                    }
-                                }
-                            }
-                        }
+                // Célula vazia ou "0": estações sem conexão direta
+                let Some(codigo_linha) = registro_linha.get(indice_coluna + 1).map(str::trim) else { continue };
+                if codigo_linha.is_empty() || codigo_linha == "0" { continue; }
+
+                let nome_destino = &matriz_linhas.nomes_colunas[indice_coluna];
+                let local = || LocalDados::celula(caminho_linhas_conexao, registro_linha.position().map(|p| p.line()), nome_destino);
+
+                let id_linha = match self.linhas.iter().find(|linha| linha.codigo == codigo_linha) {
+                    Some(linha) => linha.id,
+                    None => {
+                        // No modo tolerante a linha é criada com a cor padrão
+                        coletor.registrar(AvisoCarregamento::LinhaDesconhecida {
+                            local: local(),
+                            codigo_linha: codigo_linha.to_string(),
+                            estacao_origem: nome_origem.clone(),
+                            estacao_destino: nome_destino.clone(),
+                        })?;
+                        self.adicionar_linha(codigo_linha, &format!("Linha {}", codigo_linha), COR_LINHA_PADRAO)
                     }
-                }
+                };
+
+                let Some(&(distancia_real_km, _)) = distancias_reais.get(&(id_estacao_origem, id_estacao_destino)) else {
+                    coletor.registrar(AvisoCarregamento::ConexaoSemDistancia {
+                        local: local(),
+                        codigo_linha: codigo_linha.to_string(),
+                        estacao_origem: nome_origem.clone(),
+                        estacao_destino: nome_destino.clone(),
+                    })?;
+                    continue;
+                };
+                distancias_usadas.insert((id_estacao_origem, id_estacao_destino));
+
+                let tempo_viagem_minutos = self.tempo_viagem_minutos(id_linha, distancia_real_km);
+
+                self.lista_adjacencia[id_estacao_origem].push(Conexao {
+                    para_estacao: id_estacao_destino,
+                    linha: id_linha,
+                    distancia_km: distancia_real_km,
+                    tempo_minutos: tempo_viagem_minutos,
+                });
             }
         }
-        
-        for i in 0..NUMERO_ESTACOES {
-            println!("E{} tem {} conexões", i+1, self.lista_adjacencia[i].len());
+
+        let mut distancias_sem_linha: Vec<_> = distancias_reais.iter()
+            .filter(|(par, _)| !distancias_usadas.contains(*par))
+            .collect();
+        distancias_sem_linha.sort_by_key(|&(&par, _)| par);
+        for (&(id_origem, id_destino), &(distancia_km, linha_arquivo)) in distancias_sem_linha {
+            let codigo_destino = &self.estacoes[id_destino].codigo;
+            coletor.registrar(AvisoCarregamento::DistanciaSemLinha {
+                local: LocalDados::celula(caminho_dist_reais, linha_arquivo, codigo_destino),
+                estacao_origem: self.estacoes[id_origem].codigo.clone(),
+                estacao_destino: codigo_destino.clone(),
+                distancia_km,
+            })?;
         }
-        
-        Ok(())
+
+        Ok(coletor.concluir())
     }
-}
\ No newline at end of file
+}
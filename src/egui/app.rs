@@ -3,24 +3,25 @@ use std::sync::Arc;
 use std::cell::RefCell;
 use egui::{Color32, Vec2};
 
-use crate::grafo_metro::{CorLinha, GrafoMetro, IdEstacao, NUMERO_ESTACOES};
+use crate::dados_metro::FonteRede;
+use crate::erro_carregamento::{AvisoCarregamento, ModoCarregamento};
+use crate::grafo_metro::{Coordenadas, GrafoMetro, IdEstacao, IdLinha};
 use crate::algoritmo_a_estrela::{InfoCaminho, SolucionadorAEstrela};
+use crate::busca_bidirecional::SolucionadorBidirecional;
+use crate::estrategia_busca::EstrategiaBusca;
+use crate::quadro_horarios::QuadroHorarios;
+use crate::rotas_alternativas::{RotaAlternativa, QUANTIDADE_ALTERNATIVAS_PADRAO};
 
 #[derive(Clone, Debug)]
 pub struct PopupInfo {
-    pub id_estacao: IdEstacao,
     pub conteudo: String,
     pub posicao: RefCell<Vec2>,
     pub visivel: bool,
-    pub esta_sendo_arrastado: bool,
-    pub tamanho: Vec2,
 }
 
 pub enum TipoAcaoPopup {
     Fechar,
-    Iniciar,
     MoverDelta,
-    Soltar,
 }
 
 pub struct AcaoPopup {
@@ -32,9 +33,10 @@ pub struct AcaoPopup {
 pub struct MinhaAplicacaoGUI {
     pub grafo_metro: Option<Arc<GrafoMetro>>,
     pub solucionador_a_estrela: Option<SolucionadorAEstrela>,
+    pub solucionador_bidirecional: Option<SolucionadorBidirecional>, // Mantido após o fim, para mostrar onde as buscas se encontraram
     pub id_estacao_inicio_selecionada: IdEstacao,
     pub id_estacao_objetivo_selecionada: IdEstacao,
-    pub linha_inicio_opcional: Option<CorLinha>,
+    pub linha_inicio_opcional: Option<IdLinha>,
     pub resultado_caminho_ui: Option<InfoCaminho>,
     pub mensagem_status_ui: String,
     pub posicoes_estacoes_tela: Vec<egui::Pos2>,
@@ -47,60 +49,78 @@ pub struct MinhaAplicacaoGUI {
     pub mostrar_marcadores_estacoes: bool,
     pub mostrar_ids_estacoes: bool,
     pub offset_rolagem: Vec2,
-    pub arrastando: bool,
     pub ultima_posicao_mouse: Option<egui::Pos2>,
     pub popups_info: HashMap<IdEstacao, PopupInfo>,
-    pub estacoes_com_popup_automatico: HashSet<IdEstacao>,
-    pub offset_arrasto_popup_atual: Option<Vec2>,
-    pub estacao_sendo_arrastada: Option<IdEstacao>,
     pub ultimo_tempo_animacao: f32,
     pub ja_centralizou: bool,
+    pub avisos_carregamento: Vec<AvisoCarregamento>,
+    pub modo_micro_passo: bool, // Um evento da busca por clique em vez de uma expansão inteira
+    pub estrategia_busca: EstrategiaBusca, // Aplicada ao iniciar a próxima busca
+    pub busca_bidirecional: bool,
+    pub rotas_pareto: Vec<InfoCaminho>, // Opções tempo × baldeações da última consulta
+    pub rota_pareto_selecionada: Option<usize>,
+    pub rotas_alternativas: Vec<RotaAlternativa>, // A melhor rota e as seguintes (Yen), sobrepostas no mapa
+    pub rota_alternativa_selecionada: Option<usize>,
+    pub quantidade_alternativas: usize,
+    pub quadro_horarios: Arc<QuadroHorarios>, // Vazio sem --horarios: a consulta com horário não tem esperas
+    pub usar_horario_partida: bool,
+    pub texto_horario_partida: String, // "HH:MM", editado no painel
+    pub texto_horario_chegada: String, // "HH:MM" da consulta "chegar até"
 }
 
 impl MinhaAplicacaoGUI {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let mut grafo = GrafoMetro::novo();
-        if let Err(e) = grafo.carregar_distancias_heuristicas("data/tabela1_distancias_diretas.csv") {
-            eprintln!("ERRO GUI: Falha ao carregar distâncias heurísticas: {}", e);
-        }
-        if let Err(e) = grafo.carregar_conexoes(
-            "data/tabela2_distancias_reais.csv",
-            "data/tabela_linhas_conexao.csv",
-        ) {
-            eprintln!("ERRO GUI: Falha ao carregar conexões: {}", e);
-        }
+    pub fn new(
+        _cc: &eframe::CreationContext<'_>,
+        fonte_rede: &FonteRede,
+        modo_carregamento: ModoCarregamento,
+        caminho_horarios: Option<&str>,
+    ) -> Self {
+        // Em caso de erro a janela abre com um grafo vazio e o motivo na barra de status,
+        // em vez de seguir com uma rede carregada pela metade
+        let (grafo, mut avisos_carregamento, erro_rede) = match fonte_rede.carregar(modo_carregamento) {
+            Ok((grafo, avisos)) => (grafo, avisos, None),
+            Err(e) => {
+                eprintln!("ERRO GUI: Falha ao carregar a rede: {}", e);
+                (GrafoMetro::novo(), Vec::new(), Some(format!("❌ Falha ao carregar a rede: {}", e)))
+            }
+        };
         
-        let mut posicoes = vec![egui::Pos2::ZERO; NUMERO_ESTACOES];
-        let offset_x = 200.0;
-        let offset_y = 150.0;
-        let fator_escala = 1.4;
+        // O quadro de horários é opcional: se falhar, a rede abre sem ele
+        let (quadro_horarios, erro_horarios) = match caminho_horarios.map(|caminho| QuadroHorarios::carregar(&grafo, caminho, modo_carregamento)) {
+            Some(Ok((quadro, avisos))) => {
+                avisos_carregamento.extend(avisos);
+                (quadro, None)
+            }
+            Some(Err(e)) => {
+                eprintln!("ERRO GUI: Falha ao carregar o quadro de horários: {}", e);
+                (QuadroHorarios::novo(), Some(format!("❌ Falha ao carregar o quadro de horários: {}", e)))
+            }
+            None => (QuadroHorarios::novo(), None),
+        };
         
-        if NUMERO_ESTACOES >= 14 {
-            posicoes[0] = egui::Pos2::new(offset_x + 80.0 * fator_escala, offset_y + 250.0 * fator_escala);
-            posicoes[1] = egui::Pos2::new(offset_x + 220.0 * fator_escala, offset_y + 240.0 * fator_escala);
-            posicoes[2] = egui::Pos2::new(offset_x + 360.0 * fator_escala, offset_y + 230.0 * fator_escala);
-            posicoes[3] = egui::Pos2::new(offset_x + 400.0 * fator_escala, offset_y + 280.0 * fator_escala);
-            posicoes[4] = egui::Pos2::new(offset_x + 580.0 * fator_escala, offset_y + 350.0 * fator_escala);
-            posicoes[5] = egui::Pos2::new(offset_x + 730.0 * fator_escala, offset_y + 320.0 * fator_escala);
-            posicoes[6] = egui::Pos2::new(offset_x + 680.0 * fator_escala, offset_y + 390.0 * fator_escala);
-            posicoes[7] = egui::Pos2::new(offset_x + 420.0 * fator_escala, offset_y + 150.0 * fator_escala);
-            posicoes[8] = egui::Pos2::new(offset_x + 300.0 * fator_escala, offset_y + 130.0 * fator_escala);
-            posicoes[9] = egui::Pos2::new(offset_x + 150.0 * fator_escala, offset_y + 210.0 * fator_escala);
-            posicoes[10] = egui::Pos2::new(offset_x + 200.0 * fator_escala, offset_y + 50.0 * fator_escala);
-            posicoes[11] = egui::Pos2::new(offset_x + 400.0 * fator_escala, offset_y + 50.0 * fator_escala);
-            posicoes[12] = egui::Pos2::new(offset_x + 400.0 * fator_escala, offset_y + 480.0 * fator_escala);
-            posicoes[13] = egui::Pos2::new(offset_x + 380.0 * fator_escala, offset_y + 580.0 * fator_escala);
-        }
+        // O status só é montado depois das duas cargas: a falha da rede tem precedência e os avisos
+        // contados são os da rede e os do quadro de horários
+        let mensagem_status = match (erro_rede, erro_horarios) {
+            (Some(erro), _) | (None, Some(erro)) => erro,
+            (None, None) if avisos_carregamento.is_empty() => "Selecione início/fim e inicie a busca.".to_string(),
+            (None, None) => format!("Dados carregados com {} aviso(s). Selecione início/fim e inicie a busca.", avisos_carregamento.len()),
+        };
+        
+        let posicoes = calcular_posicoes_estacoes(&grafo);
+        let id_inicio_padrao = grafo.obter_id_estacao("E6").unwrap_or(0);
+        let id_objetivo_padrao = grafo.obter_id_estacao("E13")
+            .unwrap_or(grafo.numero_estacoes().saturating_sub(1));
         
         Self {
             grafo_metro: Some(Arc::new(grafo)),
             posicoes_estacoes_tela: posicoes,
-            id_estacao_inicio_selecionada: 5,
-            id_estacao_objetivo_selecionada: 12,
+            id_estacao_inicio_selecionada: id_inicio_padrao,
+            id_estacao_objetivo_selecionada: id_objetivo_padrao,
             linha_inicio_opcional: None,
             resultado_caminho_ui: None,
-            mensagem_status_ui: "Selecione início/fim e inicie a busca.".to_string(),
+            mensagem_status_ui: mensagem_status,
             solucionador_a_estrela: None,
+            solucionador_bidirecional: None,
             estacao_sendo_expandida_ui: None,
             estacoes_exploradas_ui: HashSet::new(),
             detalhes_analise_ui: Vec::new(),
@@ -110,18 +130,137 @@ impl MinhaAplicacaoGUI {
             mostrar_marcadores_estacoes: true,
             mostrar_ids_estacoes: true,
             offset_rolagem: Vec2::new(0.0, 0.0),
-            arrastando: false,
             ultima_posicao_mouse: None,
             popups_info: HashMap::new(),
-            estacoes_com_popup_automatico: HashSet::new(),
-            offset_arrasto_popup_atual: None,
-            estacao_sendo_arrastada: None,
             ultimo_tempo_animacao: 0.0,
             ja_centralizou: false,
+            avisos_carregamento,
+            modo_micro_passo: false,
+            estrategia_busca: EstrategiaBusca::default(),
+            busca_bidirecional: false,
+            rotas_pareto: Vec::new(),
+            rota_pareto_selecionada: None,
+            rotas_alternativas: Vec::new(),
+            rota_alternativa_selecionada: None,
+            quantidade_alternativas: QUANTIDADE_ALTERNATIVAS_PADRAO,
+            usar_horario_partida: !quadro_horarios.esta_vazio(),
+            quadro_horarios: Arc::new(quadro_horarios),
+            texto_horario_partida: "08:00".to_string(),
+            texto_horario_chegada: "09:00".to_string(),
+        }
+    }
+}
+
+/// Layout desenhado à mão para a rede de exemplo (códigos E1..E14), em coordenadas de tela
+const LAYOUT_REDE_EXEMPLO: [(&str, f32, f32); 14] = [
+    ("E1", 80.0, 250.0),
+    ("E2", 220.0, 240.0),
+    ("E3", 360.0, 230.0),
+    ("E4", 400.0, 280.0),
+    ("E5", 580.0, 350.0),
+    ("E6", 730.0, 320.0),
+    ("E7", 680.0, 390.0),
+    ("E8", 420.0, 150.0),
+    ("E9", 300.0, 130.0),
+    ("E10", 150.0, 210.0),
+    ("E11", 200.0, 50.0),
+    ("E12", 400.0, 50.0),
+    ("E13", 400.0, 480.0),
+    ("E14", 380.0, 580.0),
+];
+
+/// Área (em coordenadas de tela, antes da escala) ocupada pelas estações posicionadas por coordenadas
+const LARGURA_LAYOUT_COORDENADAS: f32 = 700.0;
+const ALTURA_LAYOUT_COORDENADAS: f32 = 550.0;
+
+/// Projeta as coordenadas de uma estação num plano em km (x para leste, y para norte)
+fn projetar_coordenadas_km(coordenadas: &Coordenadas, latitude_referencia: f64) -> (f32, f32) {
+    match *coordenadas {
+        Coordenadas::Planas { x_km, y_km } => (x_km, y_km),
+        Coordenadas::Geograficas { latitude, longitude } => {
+            // Projeção equirretangular: suficiente na escala de uma cidade
+            let x = longitude * 111.32 * latitude_referencia.to_radians().cos();
+            let y = latitude * 110.57;
+            (x as f32, y as f32)
         }
     }
 }
 
+/// Calcula a posição de tela de cada estação do grafo carregado.
+/// A rede de exemplo usa o layout fixo; outras redes usam as coordenadas do arquivo de
+/// estações quando todas as têm, e as estações restantes são distribuídas em círculo.
+fn calcular_posicoes_estacoes(grafo: &GrafoMetro) -> Vec<egui::Pos2> {
+    let offset_x = 200.0;
+    let offset_y = 150.0;
+    let fator_escala = 1.4;
+
+    let layout_conhecido: HashMap<&str, (f32, f32)> = LAYOUT_REDE_EXEMPLO.iter()
+        .map(|&(codigo, x, y)| (codigo, (x, y)))
+        .collect();
+    let todas_no_layout = grafo.estacoes.iter()
+        .all(|estacao| layout_conhecido.contains_key(estacao.codigo.as_str()));
+
+    let numero_estacoes = grafo.numero_estacoes().max(1);
+    let raio = 60.0 * (numero_estacoes as f32).sqrt().max(3.0);
+    let posicao_em_circulo = |id: IdEstacao| {
+        let angulo = std::f32::consts::TAU * id as f32 / numero_estacoes as f32;
+        (400.0 + raio * angulo.cos(), 300.0 + raio * angulo.sin())
+    };
+
+    let posicoes_por_coordenadas = if todas_no_layout {
+        None
+    } else {
+        posicoes_por_coordenadas(grafo)
+    };
+
+    grafo.estacoes.iter()
+        .map(|estacao| {
+            let (x, y) = match &posicoes_por_coordenadas {
+                Some(posicoes) => posicoes[estacao.id],
+                None => layout_conhecido.get(estacao.codigo.as_str()).copied()
+                    .unwrap_or_else(|| posicao_em_circulo(estacao.id)),
+            };
+            egui::Pos2::new(offset_x + x * fator_escala, offset_y + y * fator_escala)
+        })
+        .collect()
+}
+
+/// Ajusta as coordenadas de todas as estações à área do layout, mantendo a proporção.
+/// Retorna `None` se alguma estação não tiver coordenadas.
+fn posicoes_por_coordenadas(grafo: &GrafoMetro) -> Option<Vec<(f32, f32)>> {
+    let coordenadas: Vec<&Coordenadas> = grafo.estacoes.iter()
+        .map(|estacao| estacao.coordenadas.as_ref())
+        .collect::<Option<_>>()?;
+    if coordenadas.is_empty() {
+        return None;
+    }
+
+    let latitudes: Vec<f64> = coordenadas.iter()
+        .filter_map(|c| match c {
+            Coordenadas::Geograficas { latitude, .. } => Some(*latitude),
+            Coordenadas::Planas { .. } => None,
+        })
+        .collect();
+    let latitude_referencia = if latitudes.is_empty() {
+        0.0
+    } else {
+        latitudes.iter().sum::<f64>() / latitudes.len() as f64
+    };
+
+    let pontos_km: Vec<(f32, f32)> = coordenadas.iter()
+        .map(|c| projetar_coordenadas_km(c, latitude_referencia))
+        .collect();
+    let (min_x, max_x) = pontos_km.iter().fold((f32::MAX, f32::MIN), |(a, b), p| (a.min(p.0), b.max(p.0)));
+    let (min_y, max_y) = pontos_km.iter().fold((f32::MAX, f32::MIN), |(a, b), p| (a.min(p.1), b.max(p.1)));
+    let escala = (LARGURA_LAYOUT_COORDENADAS / (max_x - min_x).max(f32::EPSILON))
+        .min(ALTURA_LAYOUT_COORDENADAS / (max_y - min_y).max(f32::EPSILON));
+
+    // y da tela cresce para baixo, o norte fica em cima
+    Some(pontos_km.iter()
+        .map(|&(x, y)| (50.0 + (x - min_x) * escala, 50.0 + (max_y - y) * escala))
+        .collect())
+}
+
 impl eframe::App for MinhaAplicacaoGUI {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         super::controls::mostrar_painel_controles(self, ctx);
@@ -153,6 +292,7 @@ impl eframe::App for MinhaAplicacaoGUI {
                 let grafo_ref = &*grafo_clone;
                 
                 super::drawing::desenhar_conexoes(self, &painter, rect_desenho, grafo_ref);
+                super::drawing::desenhar_destaques_evento(self, &painter, rect_desenho);
                 super::drawing::desenhar_estacoes(self, &painter, rect_desenho, grafo_ref, ui);
                 super::visual_effects::desenhar_marcadores_estacoes(self, &painter, rect_desenho, grafo_ref, ui);
                 
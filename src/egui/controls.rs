@@ -1,5 +1,7 @@
 use egui::{Color32, ComboBox};
-use crate::grafo_metro::NUMERO_ESTACOES;
+use crate::busca_bidirecional::SentidoBusca;
+use crate::estrategia_busca::{EstrategiaBusca, EPSILON_PADRAO};
+use crate::quadro_horarios::formatar_horario;
 use super::app::MinhaAplicacaoGUI;
 use super::state_manager;
 
@@ -11,6 +13,8 @@ pub fn mostrar_painel_controles(app: &mut MinhaAplicacaoGUI, ctx: &egui::Context
             ui.separator();
             
             mostrar_seletores_estacao(app, ui);
+            mostrar_seletor_estrategia(app, ui);
+            mostrar_horario_partida(app, ui);
             
             ui.separator();
             ui.label(egui::RichText::new("Controles de Busca")
@@ -20,12 +24,29 @@ pub fn mostrar_painel_controles(app: &mut MinhaAplicacaoGUI, ctx: &egui::Context
             
             mostrar_botoes_controle_principal(app, ui);
             
-            if app.solucionador_a_estrela.is_some() {
+            if app.solucionador_bidirecional.is_some() {
+                mostrar_controles_bidirecional(app, ui);
+            } else if app.solucionador_a_estrela.is_some() {
                 mostrar_controles_passo_a_passo(app, ui);
+                if app.modo_micro_passo {
+                    mostrar_explicacao_evento(app, ui);
+                }
             }
             
             ui.separator();
             ui.label(&app.mensagem_status_ui);
+
+            if !app.avisos_carregamento.is_empty() {
+                mostrar_avisos_carregamento(app, ui);
+            }
+            
+            if app.rotas_pareto.len() > 1 {
+                mostrar_opcoes_pareto(app, ui);
+            }
+            
+            if app.rotas_alternativas.len() > 1 {
+                mostrar_rotas_alternativas(app, ui);
+            }
             
             if let Some(info_caminho) = &app.resultado_caminho_ui {
                 mostrar_resumo_rota(app, ui, info_caminho);
@@ -37,7 +58,7 @@ pub fn mostrar_painel_controles(app: &mut MinhaAplicacaoGUI, ctx: &egui::Context
 }
 
 fn mostrar_seletores_estacao(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
-    if let Some(grafo) = &app.grafo_metro {
+    if let Some(grafo) = app.grafo_metro.as_ref().filter(|grafo| grafo.numero_estacoes() > 0) {
         let estacao_inicio_nome_atual = grafo.estacoes[app.id_estacao_inicio_selecionada].nome.clone();
         ComboBox::from_label("Estação de Início")
             .selected_text(estacao_inicio_nome_atual)
@@ -55,11 +76,50 @@ fn mostrar_seletores_estacao(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
                     ui_combo.selectable_value(&mut app.id_estacao_objetivo_selecionada, estacao.id, &estacao.nome);
                 }
             });
+    } else if app.grafo_metro.is_some() {
+        ui.label("Nenhuma estação carregada.");
     } else {
         ui.label("Aguardando carregamento do grafo...");
     }
 }
 
+/// Estratégia usada na próxima busca; o ε só aparece no A* ponderado
+fn mostrar_seletor_estrategia(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    let epsilon_atual = match app.estrategia_busca {
+        EstrategiaBusca::AEstrelaPonderado { epsilon } => epsilon,
+        _ => EPSILON_PADRAO,
+    };
+    ui.add_enabled_ui(!app.busca_bidirecional, |ui| {
+        ComboBox::from_label("Estratégia")
+            .selected_text(app.estrategia_busca.nome())
+            .show_ui(ui, |ui_combo| {
+                for estrategia in EstrategiaBusca::todas(epsilon_atual) {
+                    ui_combo.selectable_value(&mut app.estrategia_busca, estrategia, estrategia.nome());
+                }
+            });
+        if let EstrategiaBusca::AEstrelaPonderado { epsilon } = &mut app.estrategia_busca {
+            ui.add(egui::Slider::new(epsilon, 1.0..=5.0).step_by(0.1).text("ε (peso de h)"));
+        }
+    });
+    ui.checkbox(&mut app.busca_bidirecional, "Busca bidirecional (A* da origem e do objetivo)");
+}
+
+/// Horário de partida da próxima busca; com quadro de horários, a busca conta a espera pelos trens
+fn mostrar_horario_partida(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    ui.add_enabled_ui(!app.busca_bidirecional, |ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut app.usar_horario_partida, "Partir às");
+            ui.add_enabled(app.usar_horario_partida,
+                egui::TextEdit::singleline(&mut app.texto_horario_partida).desired_width(50.0).hint_text("HH:MM"));
+        });
+        if app.usar_horario_partida && app.quadro_horarios.esta_vazio() {
+            ui.label(egui::RichText::new("Sem quadro de horários (--horarios): só os horários, sem esperas.")
+                .size(11.0)
+                .color(Color32::LIGHT_GRAY));
+        }
+    });
+}
+
 fn mostrar_botoes_controle_principal(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
     let tamanho_botao_padrao = egui::Vec2::new(220.0, 32.0);
     
@@ -69,6 +129,30 @@ fn mostrar_botoes_controle_principal(app: &mut MinhaAplicacaoGUI, ui: &mut egui:
     
     ui.add_space(3.0);
     
+    if ui.add_sized(tamanho_botao_padrao, egui::Button::new("Opções Tempo × Baldeações")).clicked() {
+        state_manager::calcular_rotas_pareto(app);
+    }
+    
+    ui.add_space(3.0);
+    
+    ui.horizontal(|ui| {
+        if ui.add_sized(egui::Vec2::new(160.0, 32.0), egui::Button::new("Rotas Alternativas")).clicked() {
+            state_manager::calcular_rotas_alternativas(app);
+        }
+        ui.add(egui::DragValue::new(&mut app.quantidade_alternativas).range(1..=5).suffix(" alt."));
+    });
+    
+    ui.add_space(3.0);
+    
+    ui.horizontal(|ui| {
+        if ui.add_sized(egui::Vec2::new(160.0, 32.0), egui::Button::new("Chegar Até")).clicked() {
+            state_manager::calcular_partida_mais_tarde(app);
+        }
+        ui.add(egui::TextEdit::singleline(&mut app.texto_horario_chegada).desired_width(50.0).hint_text("HH:MM"));
+    });
+    
+    ui.add_space(3.0);
+    
     if ui.add_sized(tamanho_botao_padrao, egui::Button::new("Limpar Tudo")).clicked() {
         state_manager::limpar_estado_visual(app);
         app.mensagem_status_ui = "Estado limpo. Selecione início/fim e inicie nova busca.".to_string();
@@ -83,6 +167,15 @@ fn mostrar_controles_passo_a_passo(app: &mut MinhaAplicacaoGUI, ui: &mut egui::U
         .size(13.0)
         .strong());
     ui.add_space(5.0);
+    ui.checkbox(&mut app.modo_micro_passo, "Micro-passos (um evento por clique)");
+    if let Some(solucionador) = &app.solucionador_a_estrela {
+        let estatisticas = solucionador.estatisticas_fronteira();
+        ui.label(egui::RichText::new(format!(
+            "Fronteira: {} nó(s) | {} inserções, {} atualizações, {} remoções",
+            solucionador.fronteira.len(), estatisticas.insercoes, estatisticas.diminuicoes_de_chave, estatisticas.remocoes
+        )).size(10.5).color(Color32::LIGHT_GRAY));
+    }
+    ui.add_space(5.0);
     
     let (pode_voltar, num_passos_historico) = if let Some(ref solucionador) = app.solucionador_a_estrela {
         (solucionador.pode_voltar_passo(), solucionador.numero_passos_historico())
@@ -124,14 +217,19 @@ fn mostrar_controles_passo_a_passo(app: &mut MinhaAplicacaoGUI, ui: &mut egui::U
             .stroke(egui::Stroke::new(1.5, Color32::from_rgb(100, 120, 160)));
         
         if ui.add_sized(tamanho_nav, btn_prox).clicked() {
-            state_manager::executar_proximo_passo_a_estrela(app);
+            if app.modo_micro_passo {
+                state_manager::executar_proximo_micro_passo_a_estrela(app);
+            } else {
+                state_manager::executar_proximo_passo_a_estrela(app);
+            }
         }
     });
     
     ui.add_space(5.0);
     
     if ui.add_sized(tamanho_botao_padrao, egui::Button::new("Executar Tudo")).clicked() {
-        for _i in 0..NUMERO_ESTACOES * NUMERO_ESTACOES * 2 {
+        let numero_estacoes = app.grafo_metro.as_ref().map_or(0, |grafo| grafo.numero_estacoes());
+        for _i in 0..numero_estacoes * numero_estacoes * 2 {
             if app.solucionador_a_estrela.is_none() {
                 break;
             }
@@ -143,6 +241,108 @@ fn mostrar_controles_passo_a_passo(app: &mut MinhaAplicacaoGUI, ui: &mut egui::U
     }
 }
 
+/// Passos da busca bidirecional, com a legenda das cores de cada metade no mapa
+fn mostrar_controles_bidirecional(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    let tamanho_botao_padrao = egui::Vec2::new(220.0, 32.0);
+    let Some(solucionador) = &app.solucionador_bidirecional else { return };
+    let finalizado = solucionador.esta_finalizado();
+
+    ui.add_space(8.0);
+    ui.label(egui::RichText::new("Busca Bidirecional")
+        .size(13.0)
+        .strong());
+    for (sentido, texto) in [(SentidoBusca::Ida, "Ida (a partir da origem)"), (SentidoBusca::Volta, "Volta (a partir do objetivo)")] {
+        let meia_busca = solucionador.meia_busca(sentido);
+        ui.label(egui::RichText::new(format!(
+            "● {}: {} expandido(s), {} na fronteira",
+            texto, meia_busca.explorados.len(), meia_busca.fronteira.len()
+        )).size(11.0).color(super::drawing::cor_sentido_busca(sentido)));
+    }
+    let texto_encontro = solucionador.melhor_encontro.map_or_else(
+        || "● Encontro: ainda nenhum".to_string(),
+        |encontro| format!("● Encontro: {:.1} min", encontro.custo_total),
+    );
+    ui.label(egui::RichText::new(texto_encontro).size(11.0).color(super::drawing::COR_ENCONTRO_BIDIRECIONAL));
+    ui.add_space(5.0);
+
+    if ui.add_enabled(!finalizado, egui::Button::new("Próximo ▶").min_size(tamanho_botao_padrao)).clicked() {
+        state_manager::executar_proximo_passo_bidirecional(app);
+    }
+    ui.add_space(3.0);
+    if ui.add_enabled(!finalizado, egui::Button::new("Executar Tudo").min_size(tamanho_botao_padrao)).clicked() {
+        while app.solucionador_bidirecional.as_ref().is_some_and(|solucionador| !solucionador.esta_finalizado()) {
+            state_manager::executar_proximo_passo_bidirecional(app);
+        }
+    }
+}
+
+/// Lista as opções de Pareto; a escolhida fica destacada no mapa e no resumo da rota
+fn mostrar_opcoes_pareto(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    ui.separator();
+    ui.label(egui::RichText::new("Opções de Rota")
+        .size(13.0)
+        .strong());
+    let mut escolhida = None;
+    for (indice, rota) in app.rotas_pareto.iter().enumerate() {
+        let texto = format!("{}. {:.1} min · {} baldeação(ões)", indice + 1, rota.tempo_total_minutos, rota.baldeacoes);
+        if ui.selectable_label(app.rota_pareto_selecionada == Some(indice), texto).clicked() {
+            escolhida = Some(indice);
+        }
+    }
+    if let Some(indice) = escolhida {
+        state_manager::selecionar_rota_pareto(app, indice);
+    }
+}
+
+/// Lista a melhor rota e as alternativas com a diferença para ela, na cor usada no mapa
+fn mostrar_rotas_alternativas(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    ui.separator();
+    ui.label(egui::RichText::new("Rotas Alternativas")
+        .size(13.0)
+        .strong());
+    let mut escolhida = None;
+    for (indice, rota) in app.rotas_alternativas.iter().enumerate() {
+        let texto = if indice == 0 {
+            format!("● Melhor: {:.1} min · {} baldeação(ões)", rota.caminho.tempo_total_minutos, rota.caminho.baldeacoes)
+        } else {
+            format!("● {}: {:.1} min ({:+.1}) · {} baldeação(ões) ({:+})", indice, rota.caminho.tempo_total_minutos,
+                rota.diferenca_tempo_minutos, rota.caminho.baldeacoes, rota.diferenca_baldeacoes)
+        };
+        let texto = egui::RichText::new(texto).size(11.0).color(super::drawing::cor_rota_alternativa(indice));
+        if ui.selectable_label(app.rota_alternativa_selecionada == Some(indice), texto).clicked() {
+            escolhida = Some(indice);
+        }
+    }
+    if let Some(indice) = escolhida {
+        state_manager::selecionar_rota_alternativa(app, indice);
+    }
+}
+
+/// Explica o último micro-passo da busca, com as mesmas cores dos destaques no mapa
+fn mostrar_explicacao_evento(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    let (Some(grafo), Some(solucionador)) = (&app.grafo_metro, &app.solucionador_a_estrela) else { return };
+    
+    ui.add_space(8.0);
+    egui::Frame::group(ui.style())
+        .fill(Color32::from_rgb(40, 42, 54))
+        .stroke(egui::Stroke::new(1.0, Color32::from_rgb(80, 80, 100)))
+        .corner_radius(egui::CornerRadius::same(8))
+        .inner_margin(egui::Margin::same(8))
+        .show(ui, |ui| {
+            ui.set_width(220.0);
+            match &solucionador.ultimo_evento {
+                Some(evento) => {
+                    let (titulo, explicacao) = super::explicacao_evento::explicar_evento(grafo, evento, solucionador.estrategia());
+                    ui.label(egui::RichText::new(titulo).strong().color(Color32::from_rgb(255, 220, 150)));
+                    ui.label(egui::RichText::new(explicacao).size(11.5));
+                }
+                None => {
+                    ui.label(egui::RichText::new("Clique em \"Próximo\" para ver o primeiro evento da busca.").size(11.5));
+                }
+            }
+        });
+}
+
 fn mostrar_resumo_rota(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui, info_caminho: &crate::algoritmo_a_estrela::InfoCaminho) {
     ui.separator();
     ui.heading("Resumo da Rota");
@@ -187,6 +387,13 @@ fn mostrar_resumo_rota(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui, info_caminho:
             });
         });
     
+    if let Some(partida) = info_caminho.horario_partida {
+        ui.label(format!("Partida {} → chegada {} ({:.1} min de espera)",
+            formatar_horario(partida),
+            formatar_horario(partida + info_caminho.tempo_total_minutos),
+            info_caminho.espera_total_minutos()));
+    }
+    
     ui.add_space(8.0);
     ui.label(egui::RichText::new("Trajeto Completo:").strong());
     
@@ -198,17 +405,21 @@ fn mostrar_tabela_trajeto(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui, info_camin
         egui::ScrollArea::vertical()
             .max_height(200.0)
             .show(ui, |ui| {
+                let com_horarios = !info_caminho.horarios_trechos.is_empty();
                 egui::Grid::new("grid_trajeto")
-                    .num_columns(3)
+                    .num_columns(if com_horarios { 4 } else { 3 })
                     .striped(true)
                     .spacing([8.0, 4.0])
                     .show(ui, |ui| {
                         ui.add(egui::Label::new(egui::RichText::new("#").strong()));
                         ui.add(egui::Label::new(egui::RichText::new("Estação").strong()));
                         ui.add(egui::Label::new(egui::RichText::new("Linha").strong()));
+                        if com_horarios {
+                            ui.add(egui::Label::new(egui::RichText::new("Horário").strong()));
+                        }
                         ui.end_row();
                         
-                        let mut linha_anterior: Option<crate::grafo_metro::CorLinha> = None;
+                        let mut linha_anterior = info_caminho.linha_partida;
                         for (idx, (id_est, linha_chegada_op)) in info_caminho.estacoes_do_caminho.iter().enumerate() {
                             let nome_est = &grafo.estacoes[*id_est].nome;
                             
@@ -234,19 +445,14 @@ fn mostrar_tabela_trajeto(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui, info_camin
                             }
                             
                             let texto_linha = match linha_chegada_op {
-                                Some(cor) => {
-                                    let cor_linha = match cor {
-                                        crate::grafo_metro::CorLinha::Azul => egui::Color32::from_rgb(0, 120, 255),
-                                        crate::grafo_metro::CorLinha::Amarela => egui::Color32::from_rgb(255, 215, 0),
-                                        crate::grafo_metro::CorLinha::Vermelha => egui::Color32::RED,
-                                        crate::grafo_metro::CorLinha::Verde => egui::Color32::from_rgb(0, 180, 0),
-                                        _ => egui::Color32::GRAY,
-                                    };
-                                    egui::RichText::new(format!("{:?}", cor)).color(cor_linha)
-                                },
+                                Some(id_linha) => egui::RichText::new(grafo.nome_linha(*id_linha))
+                                    .color(super::drawing::obter_cor_linha(grafo, *id_linha)),
                                 None => egui::RichText::new("Partida").italics(),
                             };
                             ui.label(texto_linha);
+                            if com_horarios {
+                                ui.label(descrever_horario_parada(info_caminho, idx));
+                            }
                             ui.end_row();
                             
                             linha_anterior = *linha_chegada_op;
@@ -256,6 +462,38 @@ fn mostrar_tabela_trajeto(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui, info_camin
     }
 }
 
+/// Chegada na parada (na origem, a partida) e a espera pelo trem seguinte, se houver
+fn descrever_horario_parada(info_caminho: &crate::algoritmo_a_estrela::InfoCaminho, indice: usize) -> String {
+    let trechos = &info_caminho.horarios_trechos;
+    let horario = match indice.checked_sub(1) {
+        Some(anterior) => trechos.get(anterior).map(|trecho| trecho.chegada),
+        None => info_caminho.horario_partida,
+    };
+    let mut texto = horario.map(formatar_horario).unwrap_or_default();
+    if let Some(seguinte) = trechos.get(indice).filter(|trecho| trecho.espera_minutos > 0.0) {
+        texto.push_str(&format!(", espera {:.0} min", seguinte.espera_minutos));
+    }
+    texto
+}
+
+fn mostrar_avisos_carregamento(app: &MinhaAplicacaoGUI, ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new(
+        egui::RichText::new(format!("⚠ Avisos de carregamento ({})", app.avisos_carregamento.len()))
+            .color(Color32::from_rgb(255, 200, 80)),
+    )
+    .default_open(false)
+    .show(ui, |ui| {
+        egui::ScrollArea::vertical()
+            .id_salt("avisos_carregamento")
+            .max_height(150.0)
+            .show(ui, |ui| {
+                for aviso in &app.avisos_carregamento {
+                    ui.label(egui::RichText::new(aviso.to_string()).size(11.0));
+                }
+            });
+    });
+}
+
 fn mostrar_opcoes_visualizacao(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui) {
     ui.label(egui::RichText::new("Opções de Visualização")
         .size(14.0)
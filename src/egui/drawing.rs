@@ -1,19 +1,56 @@
 use egui::{Color32, Pos2, Stroke, Vec2};
-use crate::grafo_metro::{CorLinha, GrafoMetro, IdEstacao};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha, COR_LINHA_PADRAO};
+use crate::busca_bidirecional::{SentidoBusca, SolucionadorBidirecional};
 use super::app::MinhaAplicacaoGUI;
+use super::explicacao_evento;
 
 /// Desenha todas as conexões entre estações
 pub fn desenhar_conexoes(app: &MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect, grafo: &GrafoMetro) {
     desenhar_conexoes_normais(app, painter, rect_desenho, grafo);
+    desenhar_rotas_alternativas(app, painter, rect_desenho);
     desenhar_caminho_solucao(app, painter, rect_desenho, grafo);
 }
 
+/// Cor de cada uma das k rotas (a melhor usa a mesma cor do caminho da solução)
+pub fn cor_rota_alternativa(indice: usize) -> Color32 {
+    const CORES: [Color32; 6] = [
+        Color32::from_rgb(0, 150, 136),
+        Color32::from_rgb(255, 152, 0),
+        Color32::from_rgb(236, 64, 122),
+        Color32::from_rgb(41, 182, 246),
+        Color32::from_rgb(255, 235, 59),
+        Color32::from_rgb(171, 71, 188),
+    ];
+    CORES[indice % CORES.len()]
+}
+
+/// Rotas alternativas que não estão selecionadas: tracejadas, cada uma com sua cor, seu padrão
+/// de traço e um pequeno deslocamento lateral, para que trechos em comum não se escondam
+fn desenhar_rotas_alternativas(app: &MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect) {
+    let posicao = |id: IdEstacao| app.posicoes_estacoes_tela[id] * app.zoom_nivel + app.offset_rolagem + rect_desenho.min.to_vec2();
+
+    for (indice, rota) in app.rotas_alternativas.iter().enumerate() {
+        if app.rota_alternativa_selecionada == Some(indice) {
+            continue;
+        }
+        let stroke = Stroke::new(3.5 * app.zoom_nivel, cor_rota_alternativa(indice).gamma_multiply(0.9));
+        let deslocamento = (indice as f32 + 1.0) * 4.0 * app.zoom_nivel;
+        let (traco, intervalo) = ((8.0 + 4.0 * indice as f32) * app.zoom_nivel, 6.0 * app.zoom_nivel);
+
+        for par in rota.caminho.estacoes_do_caminho.windows(2) {
+            let (inicio, fim) = (posicao(par[0].0), posicao(par[1].0));
+            let normal = (fim - inicio).normalized().rot90() * deslocamento;
+            painter.extend(egui::Shape::dashed_line(&[inicio + normal, fim + normal], stroke, traco, intervalo));
+        }
+    }
+}
+
 fn desenhar_conexoes_normais(app: &MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect, grafo: &GrafoMetro) {
     for (id_origem, conexoes) in grafo.lista_adjacencia.iter().enumerate() {
         for conexao in conexoes {
             let id_destino = conexao.para_estacao;
             
-            let na_solucao = esta_na_solucao(app, id_origem, id_destino, conexao.cor_linha);
+            let na_solucao = esta_na_solucao(app, id_origem, id_destino, conexao.linha);
             if na_solucao {
                 continue;
             }
@@ -21,7 +58,8 @@ fn desenhar_conexoes_normais(app: &MinhaAplicacaoGUI, painter: &egui::Painter, r
             let pos_origem = app.posicoes_estacoes_tela[id_origem] * app.zoom_nivel + app.offset_rolagem + rect_desenho.min.to_vec2();
             let pos_destino = app.posicoes_estacoes_tela[id_destino] * app.zoom_nivel + app.offset_rolagem + rect_desenho.min.to_vec2();
             
-            let (cor_linha, espessura) = obter_cor_linha(conexao.cor_linha);
+            let cor_linha = obter_cor_linha(grafo, conexao.linha);
+            let espessura = 3.5;
             
             painter.line_segment(
                 [pos_origem, pos_destino], 
@@ -69,7 +107,7 @@ fn desenhar_caminho_solucao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, re
             );
             
             if app.mostrar_tempos_conexao {
-                let tempo = obter_tempo_conexao(grafo, id_origem, id_destino);
+                let tempo = caminho_info.tempos_trechos_minutos[i];
                 let meio = (pos_origem + pos_destino.to_vec2()) / 2.0;
                 let texto_tempo = format!("{:.1}", tempo);
                 let tamanho_texto = egui::FontId::proportional(12.0 * app.zoom_nivel);
@@ -84,26 +122,39 @@ fn desenhar_caminho_solucao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, re
                 );
             }
             
-            desenhar_icones_baldeacao(app, painter, rect_desenho, caminho_info, i);
+            desenhar_icones_baldeacao(app, painter, rect_desenho, grafo, caminho_info, i);
         }
     }
 }
 
+/// No modo micro-passo, realça as estações e os trechos envolvidos no último evento da busca
+pub fn desenhar_destaques_evento(app: &MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect) {
+    let Some(solucionador) = app.solucionador_a_estrela.as_ref().filter(|_| app.modo_micro_passo) else { return };
+    let Some(evento) = &solucionador.ultimo_evento else { return };
+    let destaques = explicacao_evento::destaques_do_evento(evento, solucionador.estacao_sendo_explorada_no_momento);
+    let posicao = |id: IdEstacao| app.posicoes_estacoes_tela[id] * app.zoom_nivel + app.offset_rolagem + rect_desenho.min.to_vec2();
+
+    for (id_origem, id_destino, cor) in destaques.conexoes {
+        painter.line_segment(
+            [posicao(id_origem), posicao(id_destino)],
+            Stroke::new(7.0 * app.zoom_nivel, cor.gamma_multiply(0.8))
+        );
+    }
+    for (id_estacao, cor) in destaques.estacoes {
+        painter.circle_stroke(posicao(id_estacao), 27.0 * app.zoom_nivel, Stroke::new(4.0 * app.zoom_nivel, cor));
+    }
+}
+
 /// Desenha as estações com seus status visuais
 pub fn desenhar_estacoes(app: &mut MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect, grafo: &GrafoMetro, ui: &mut egui::Ui) {
-    for (i, estacao) in grafo.estacoes.iter().enumerate() {
+    for i in 0..grafo.estacoes.len() {
         let pos = app.posicoes_estacoes_tela[i] * app.zoom_nivel + app.offset_rolagem + rect_desenho.min.to_vec2();
         
-        // Determinar status da estação
-        let esta_na_solucao = if let Some(ref info_caminho) = app.resultado_caminho_ui {
-            info_caminho.estacoes_do_caminho.iter().any(|(id, _)| *id == i)
-        } else {
-            false
-        };
-        
         let e_vizinho_sendo_analisado = app.vizinhos_sendo_analisados_ui.contains(&i);
         let e_sendo_explorada_agora = if let Some(ref solucionador) = app.solucionador_a_estrela {
             solucionador.estacao_sendo_explorada_no_momento == Some(i)
+        } else if let Some(ref solucionador) = app.solucionador_bidirecional {
+            !solucionador.esta_finalizado() && solucionador.ultima_expansao.map(|(_, id_estacao)| id_estacao) == Some(i)
         } else {
             false
         };
@@ -172,7 +223,7 @@ fn desenhar_circulo_estacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, po
         painter.text(
             pos,
             egui::Align2::CENTER_CENTER,
-            &format!("E{}", id_estacao + 1),
+            &grafo.estacoes[id_estacao].codigo,
             egui::FontId::proportional(12.5 * app.zoom_nivel),
             Color32::WHITE,
         );
@@ -227,37 +278,49 @@ fn processar_interacao_estacao(app: &mut MinhaAplicacaoGUI, ui: &mut egui::Ui, p
 
 // Funções auxiliares
 
-fn esta_na_solucao(app: &MinhaAplicacaoGUI, id_origem: IdEstacao, id_destino: IdEstacao, cor_linha: CorLinha) -> bool {
+fn esta_na_solucao(app: &MinhaAplicacaoGUI, id_origem: IdEstacao, id_destino: IdEstacao, id_linha: IdLinha) -> bool {
     if let Some(ref info_caminho) = app.resultado_caminho_ui {
         for i in 0..info_caminho.estacoes_do_caminho.len().saturating_sub(1) {
             let (id1, _linha1) = info_caminho.estacoes_do_caminho[i];
             let (id2, linha2) = info_caminho.estacoes_do_caminho[i+1];
             
             if id1 == id_origem && id2 == id_destino {
-                return linha2.map_or(false, |l| l == cor_linha);
+                return linha2 == Some(id_linha);
             }
         }
     }
     false
 }
 
-fn obter_cor_linha(cor_linha: CorLinha) -> (Color32, f32) {
-    match cor_linha {
-        CorLinha::Azul => (Color32::from_rgb(0, 120, 255), 3.5),
-        CorLinha::Amarela => (Color32::from_rgb(255, 215, 0), 3.5),
-        CorLinha::Vermelha => (Color32::RED, 3.5),
-        CorLinha::Verde => (Color32::from_rgb(0, 180, 0), 3.5),
-        _ => (Color32::GRAY, 3.0)
+/// Cor de exibição de uma linha, conforme definida nos dados carregados
+pub fn obter_cor_linha(grafo: &GrafoMetro, id_linha: IdLinha) -> Color32 {
+    let [r, g, b] = grafo.linhas.get(id_linha).map_or(COR_LINHA_PADRAO, |linha| linha.cor_rgb);
+    Color32::from_rgb(r, g, b)
+}
+
+/// Cor de cada metade da busca bidirecional (preenchimento escuro e borda clara usam o mesmo tom)
+pub fn cor_sentido_busca(sentido: SentidoBusca) -> Color32 {
+    match sentido {
+        SentidoBusca::Ida => Color32::from_rgb(0, 200, 255),
+        SentidoBusca::Volta => Color32::from_rgb(220, 90, 255),
     }
 }
 
-fn obter_tempo_conexao(grafo: &GrafoMetro, id_origem: IdEstacao, id_destino: IdEstacao) -> f32 {
-    if let Some(conexoes) = grafo.lista_adjacencia.get(id_origem) {
-        conexoes.iter()
-               .find(|con| con.para_estacao == id_destino)
-               .map_or(0.0, |con| con.tempo_minutos)
-    } else {
-        0.0
+pub const COR_ENCONTRO_BIDIRECIONAL: Color32 = Color32::from_rgb(255, 200, 0);
+
+/// Preenchimento e borda de uma estação na busca bidirecional: explorada pela ida, pela volta
+/// ou pelas duas; só na fronteira, apenas a borda ganha a cor da metade
+fn cores_estacao_bidirecional(solucionador: &SolucionadorBidirecional, id_estacao: IdEstacao) -> (Color32, (Color32, f32)) {
+    let (ida, volta) = (&solucionador.ida, &solucionador.volta);
+    let e_encontro = solucionador.melhor_encontro.is_some_and(|encontro| encontro.estado_ida.0 == id_estacao);
+    match (ida.estacoes_exploradas.contains(&id_estacao), volta.estacoes_exploradas.contains(&id_estacao)) {
+        _ if e_encontro => (Color32::from_rgb(80, 65, 0), (COR_ENCONTRO_BIDIRECIONAL, 4.5)),
+        (true, true) => (Color32::from_rgb(60, 40, 70), (COR_ENCONTRO_BIDIRECIONAL, 2.5)),
+        (true, false) => (Color32::from_rgb(0, 50, 70), (cor_sentido_busca(SentidoBusca::Ida), 3.0)),
+        (false, true) => (Color32::from_rgb(55, 20, 70), (cor_sentido_busca(SentidoBusca::Volta), 3.0)),
+        (false, false) if ida.estacao_na_fronteira(id_estacao) => (Color32::from_rgb(40, 42, 54), (cor_sentido_busca(SentidoBusca::Ida), 2.0)),
+        (false, false) if volta.estacao_na_fronteira(id_estacao) => (Color32::from_rgb(40, 42, 54), (cor_sentido_busca(SentidoBusca::Volta), 2.0)),
+        (false, false) => (Color32::from_rgb(40, 42, 54), (Color32::from_rgb(150, 150, 200), 2.0)),
     }
 }
 
@@ -266,6 +329,8 @@ fn obter_cor_preenchimento_estacao(app: &MinhaAplicacaoGUI, id_estacao: IdEstaca
         Color32::from_rgb(0, 60, 0)
     } else if id_estacao == app.id_estacao_objetivo_selecionada {
         Color32::from_rgb(60, 0, 0)
+    } else if let Some(ref solucionador) = app.solucionador_bidirecional {
+        cores_estacao_bidirecional(solucionador, id_estacao).0
     } else if let Some(ref solucionador) = app.solucionador_a_estrela {
         match solucionador.obter_status_estacao(id_estacao) {
             crate::algoritmo_a_estrela::StatusEstacao::Disponivel => Color32::from_rgb(40, 42, 54),
@@ -289,6 +354,8 @@ fn obter_cor_borda_estacao(app: &MinhaAplicacaoGUI, id_estacao: IdEstacao) -> (C
         (Color32::from_rgb(0, 220, 0), 4.0)
     } else if id_estacao == app.id_estacao_objetivo_selecionada {
         (Color32::from_rgb(220, 50, 50), 4.0)
+    } else if let Some(ref solucionador) = app.solucionador_bidirecional {
+        cores_estacao_bidirecional(solucionador, id_estacao).1
     } else if let Some(ref solucionador) = app.solucionador_a_estrela {
         match solucionador.obter_status_estacao(id_estacao) {
             crate::algoritmo_a_estrela::StatusEstacao::Disponivel => (Color32::from_rgb(150, 150, 200), 2.0),
@@ -356,12 +423,13 @@ fn desenhar_balao_tempo(app: &MinhaAplicacaoGUI, painter: &egui::Painter, posica
     painter.text(posicao, egui::Align2::CENTER_CENTER, texto, tamanho_fonte, cor_texto);
 }
 
-fn desenhar_icones_baldeacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect, caminho_info: &crate::algoritmo_a_estrela::InfoCaminho, i: usize) {
+fn desenhar_icones_baldeacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, rect_desenho: egui::Rect, grafo: &GrafoMetro, caminho_info: &crate::algoritmo_a_estrela::InfoCaminho, i: usize) {
     if i < caminho_info.estacoes_do_caminho.len().saturating_sub(2) {
         let (_, linha_atual) = caminho_info.estacoes_do_caminho[i+1];
         let (_, proxima_linha) = caminho_info.estacoes_do_caminho[i+2];
         
-        if linha_atual.is_some() && proxima_linha.is_some() && linha_atual != proxima_linha {
+        if let (Some(linha_atual), Some(proxima_linha)) = (linha_atual, proxima_linha)
+            && linha_atual != proxima_linha {
             let (id_destino, _) = caminho_info.estacoes_do_caminho[i+1];
             let pos_baldeacao = app.posicoes_estacoes_tela[id_destino] * app.zoom_nivel + 
                                 app.offset_rolagem + rect_desenho.min.to_vec2() + 
@@ -372,7 +440,8 @@ fn desenhar_icones_baldeacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, r
                 painter,
                 pos_baldeacao,
                 10.0 * app.zoom_nivel,
-                Some((linha_atual.unwrap(), proxima_linha.unwrap()))
+                Some((obter_cor_linha(grafo, linha_atual), obter_cor_linha(grafo, proxima_linha))),
+                grafo.tempo_baldeacao_em(id_destino),
             );
         }
     }
@@ -0,0 +1,133 @@
+use egui::Color32;
+use crate::algoritmo_a_estrela::EventoVisual;
+use crate::estrategia_busca::EstrategiaBusca;
+use crate::grafo_metro::{GrafoMetro, IdEstacao};
+
+const COR_SELECIONADA: Color32 = Color32::from_rgb(255, 220, 0);
+const COR_OBJETIVO: Color32 = Color32::from_rgb(0, 220, 120);
+const COR_AVALIANDO: Color32 = Color32::from_rgb(255, 140, 0);
+const COR_ADICIONADO: Color32 = Color32::from_rgb(80, 220, 80);
+const COR_DESCARTADO: Color32 = Color32::from_rgb(220, 60, 60);
+const COR_EXPANDINDO: Color32 = Color32::from_rgb(0, 150, 255);
+const COR_IGNORADO: Color32 = Color32::from_gray(140);
+
+/// O que destacar no mapa para um micro-passo: anéis em volta de estações e trechos realçados
+#[derive(Default)]
+pub struct DestaquesEvento {
+    pub estacoes: Vec<(IdEstacao, Color32)>,
+    pub conexoes: Vec<(IdEstacao, IdEstacao, Color32)>,
+}
+
+impl DestaquesEvento {
+    fn com_caminho(mut self, caminho: &[IdEstacao], cor: Color32) -> Self {
+        self.conexoes.extend(caminho.windows(2).map(|par| (par[0], par[1], cor)));
+        self
+    }
+}
+
+fn descrever_caminho(grafo: &GrafoMetro, caminho: &[IdEstacao]) -> String {
+    caminho.iter().map(|&id| grafo.estacoes[id].codigo.as_str()).collect::<Vec<_>>().join(" → ")
+}
+
+/// Título e explicação de um micro-passo, para o painel lateral. A conta de f segue a estratégia da busca.
+pub fn explicar_evento(grafo: &GrafoMetro, evento: &EventoVisual, estrategia: EstrategiaBusca) -> (&'static str, String) {
+    let nome = |id: IdEstacao| format!("{} ({})", grafo.estacoes[id].nome, grafo.estacoes[id].codigo);
+
+    match evento {
+        EventoVisual::AlgoritmoInicializado { id_inicio, id_objetivo, custo_h_inicial } => ("Início da busca", format!(
+            "Busca com {}. A fronteira começa só com {}: g = 0, h = {:.1}, {}. O objetivo é {}.",
+            estrategia, nome(*id_inicio), custo_h_inicial,
+            estrategia.descrever_prioridade(0.0, *custo_h_inicial, estrategia.prioridade(0.0, *custo_h_inicial, 0)),
+            nome(*id_objetivo)
+        )),
+        EventoVisual::NoEscolhidoDaFronteira { id_estacao, custo_f, custo_g, custo_h, caminho_ate_aqui } => ("Retirar da fronteira", format!(
+            "{} tem o menor f da fronteira: {}. Caminho até aqui: {}.",
+            nome(*id_estacao), estrategia.descrever_prioridade(*custo_g, *custo_h, *custo_f), descrever_caminho(grafo, caminho_ate_aqui)
+        )),
+        EventoVisual::VerificandoSeEhObjetivo { id_estacao, eh_objetivo: true } if estrategia.garante_rota_otima() => ("Teste de objetivo", format!(
+            "{} é o objetivo. Como nenhum nó da fronteira tem f menor, este caminho é o melhor encontrado.",
+            nome(*id_estacao)
+        )),
+        EventoVisual::VerificandoSeEhObjetivo { id_estacao, eh_objetivo: true } => ("Teste de objetivo", format!(
+            "{} é o objetivo e a busca para aqui. Com {}, f não é o tempo da rota, então pode haver uma rota mais rápida.",
+            nome(*id_estacao), estrategia
+        )),
+        EventoVisual::VerificandoSeEhObjetivo { id_estacao, eh_objetivo: false } => ("Teste de objetivo", format!(
+            "{} não é o objetivo; a busca continua.", nome(*id_estacao)
+        )),
+        EventoVisual::InicioDaExpansaoDeNo { id_estacao, numero_vizinhos } => ("Expansão", format!(
+            "{} entra no conjunto de explorados. Cada uma das suas {} conexões será avaliada.",
+            nome(*id_estacao), numero_vizinhos
+        )),
+        EventoVisual::AvaliandoVizinho {
+            id_estacao_atual, id_vizinho, linha_conexao, tempo_conexao, custo_baldeacao, custo_espera, custo_g_novo, custo_h, custo_f_novo,
+        } => {
+            let custo_g_atual = custo_g_novo - tempo_conexao - custo_baldeacao - custo_espera;
+            let mut acrescimos = if *custo_baldeacao > 0.0 {
+                format!(" + {:.1} de baldeação", custo_baldeacao)
+            } else {
+                String::new()
+            };
+            if *custo_espera > 0.0 {
+                acrescimos.push_str(&format!(" + {:.1} de espera pelo trem", custo_espera));
+            }
+            ("Cálculo de g, h e f", format!(
+                "{} → {} pela linha {}: g = {:.1} + {:.1} de viagem{} = {:.1}; h = {:.1}; {}.",
+                nome(*id_estacao_atual), nome(*id_vizinho), grafo.nome_linha(*linha_conexao),
+                custo_g_atual, tempo_conexao, acrescimos, custo_g_novo, custo_h,
+                estrategia.descrever_prioridade(*custo_g_novo, *custo_h, *custo_f_novo)
+            ))
+        }
+        EventoVisual::VizinhoJaExplorado { id_vizinho } => ("Vizinho já explorado", format!(
+            "{} já foi expandida chegando por esta linha; a conexão é ignorada.", nome(*id_vizinho)
+        )),
+        EventoVisual::VizinhoJaTemCaminhoMelhor { id_vizinho, custo_g_existente, custo_g_novo } => ("Descartado", format!(
+            "A fronteira já tem {} com f menor ou igual (g = {:.1}); o novo caminho, com g = {:.1}, é descartado.",
+            nome(*id_vizinho), custo_g_existente, custo_g_novo
+        )),
+        EventoVisual::VizinhoAdicionadoNaFronteira { id_vizinho, custo_f, custo_g, custo_h, novo_caminho } => ("Adicionado na fronteira", format!(
+            "{} entra na fronteira com f = {:.1} (g = {:.1}, h = {:.1}). Caminho: {}.",
+            nome(*id_vizinho), custo_f, custo_g, custo_h, descrever_caminho(grafo, novo_caminho)
+        )),
+        EventoVisual::FimDaExpansaoDeNo { id_estacao, vizinhos_adicionados, tamanho_fronteira } => ("Fim da expansão", format!(
+            "{}: {} vizinho(s) adicionado(s); a fronteira tem {} nó(s). O próximo passo retira o de menor f.",
+            nome(*id_estacao), vizinhos_adicionados, tamanho_fronteira
+        )),
+        EventoVisual::CaminhoEncontrado { info_caminho } => ("Caminho encontrado", format!(
+            "Tempo total de {:.1} min com {} baldeação(ões).", info_caminho.tempo_total_minutos, info_caminho.baldeacoes
+        )),
+        EventoVisual::NenhumCaminho => ("Sem caminho", "A fronteira esvaziou sem chegar ao objetivo.".to_string()),
+    }
+}
+
+/// Destaques de um micro-passo. `id_estacao_expandida` é o nó em expansão, origem das
+/// conexões nos eventos de vizinho que não a trazem.
+pub fn destaques_do_evento(evento: &EventoVisual, id_estacao_expandida: Option<IdEstacao>) -> DestaquesEvento {
+    let estacao = |id: IdEstacao, cor: Color32| DestaquesEvento { estacoes: vec![(id, cor)], conexoes: Vec::new() };
+    let conexao_do_expandido = |id_vizinho: IdEstacao, cor: Color32| DestaquesEvento {
+        estacoes: vec![(id_vizinho, cor)],
+        conexoes: id_estacao_expandida.map(|id| (id, id_vizinho, cor)).into_iter().collect(),
+    };
+
+    match evento {
+        EventoVisual::AlgoritmoInicializado { id_inicio, id_objetivo, .. } => DestaquesEvento {
+            estacoes: vec![(*id_inicio, COR_SELECIONADA), (*id_objetivo, COR_OBJETIVO)],
+            conexoes: Vec::new(),
+        },
+        EventoVisual::NoEscolhidoDaFronteira { id_estacao, caminho_ate_aqui, .. } =>
+            estacao(*id_estacao, COR_SELECIONADA).com_caminho(caminho_ate_aqui, COR_SELECIONADA),
+        EventoVisual::VerificandoSeEhObjetivo { id_estacao, eh_objetivo } =>
+            estacao(*id_estacao, if *eh_objetivo { COR_OBJETIVO } else { COR_IGNORADO }),
+        EventoVisual::InicioDaExpansaoDeNo { id_estacao, .. } | EventoVisual::FimDaExpansaoDeNo { id_estacao, .. } =>
+            estacao(*id_estacao, COR_EXPANDINDO),
+        EventoVisual::AvaliandoVizinho { id_estacao_atual, id_vizinho, .. } => DestaquesEvento {
+            estacoes: vec![(*id_estacao_atual, COR_EXPANDINDO), (*id_vizinho, COR_AVALIANDO)],
+            conexoes: vec![(*id_estacao_atual, *id_vizinho, COR_AVALIANDO)],
+        },
+        EventoVisual::VizinhoJaExplorado { id_vizinho } => conexao_do_expandido(*id_vizinho, COR_IGNORADO),
+        EventoVisual::VizinhoJaTemCaminhoMelhor { id_vizinho, .. } => conexao_do_expandido(*id_vizinho, COR_DESCARTADO),
+        EventoVisual::VizinhoAdicionadoNaFronteira { id_vizinho, novo_caminho, .. } =>
+            estacao(*id_vizinho, COR_ADICIONADO).com_caminho(novo_caminho, COR_ADICIONADO),
+        EventoVisual::CaminhoEncontrado { .. } | EventoVisual::NenhumCaminho => DestaquesEvento::default(),
+    }
+}
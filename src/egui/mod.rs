@@ -1,6 +1,7 @@
 pub mod app;
 pub mod controls;
 pub mod drawing;
+pub mod explicacao_evento;
 pub mod popups;
 pub mod visual_effects;
 pub mod navigation;
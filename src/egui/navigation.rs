@@ -3,6 +3,9 @@ use super::app::MinhaAplicacaoGUI;
 
 /// Centraliza a visualização do grafo na tela
 pub fn centralizar_visualizacao(app: &mut MinhaAplicacaoGUI, tamanho_disponivel: Vec2) {
+    if app.posicoes_estacoes_tela.is_empty() {
+        return;
+    }
     let centro = tamanho_disponivel / 2.0;
     let centro_grafo = app.posicoes_estacoes_tela.iter()
         .fold(Vec2::ZERO, |acc, p| acc + p.to_vec2()) / (app.posicoes_estacoes_tela.len() as f32);
@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 use std::cell::RefCell;
 use egui::{Color32, Id, Pos2, Vec2};
-use crate::grafo_metro::{CorLinha, GrafoMetro, IdEstacao};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha, Linha};
+use super::drawing::obter_cor_linha;
 use super::app::{MinhaAplicacaoGUI, PopupInfo, AcaoPopup, TipoAcaoPopup};
 
 /// Desenha popups persistentes com informações das estações
@@ -9,27 +10,26 @@ pub fn desenhar_popups(
     app: &mut MinhaAplicacaoGUI, 
     ui: &mut egui::Ui, 
     rect_desenho: egui::Rect, 
-    _grafo: &GrafoMetro
+    grafo: &GrafoMetro
 ) -> Vec<AcaoPopup> {
     let mut acoes = Vec::new();
     
     let posicoes_estacoes = app.posicoes_estacoes_tela.clone();
     let zoom_nivel = app.zoom_nivel;
     let offset_rolagem = app.offset_rolagem;
-    let grafo = app.grafo_metro.as_ref().map(|g| g.as_ref());
     
     let popup_data: Vec<_> = app.popups_info.iter()
         .filter(|(_, popup)| popup.visivel)
         .map(|(id, popup)| (*id, popup.clone()))
         .collect();
     
-    if let Some(grafo) = grafo {
+    if app.grafo_metro.is_some() {
         for (id, popup) in popup_data {
             let pos_estacao = posicoes_estacoes[id] * zoom_nivel + offset_rolagem + rect_desenho.min.to_vec2();
             let offset_popup = *popup.posicao.borrow();
             let pos_popup = pos_estacao + offset_popup;
             
-            desenhar_popup_persistente(grafo, ui, id, &popup, pos_popup, &mut acoes);
+            desenhar_popup_persistente(ui, grafo, id, &popup, pos_popup, &mut acoes);
         }
     }
     
@@ -37,8 +37,8 @@ pub fn desenhar_popups(
 }
 
 fn desenhar_popup_persistente(
-    grafo: &crate::grafo_metro::GrafoMetro,
     ui: &mut egui::Ui,
+    grafo: &GrafoMetro,
     id_estacao: IdEstacao,
     popup: &PopupInfo,
     pos_popup: Pos2,
@@ -62,7 +62,7 @@ fn desenhar_popup_persistente(
                     
                     ui.separator();
                     
-                    desenhar_conteudo_popup(ui, popup);
+                    desenhar_conteudo_popup(ui, grafo, popup);
                     
                     ui.add_space(6.0);
                     ui.separator();
@@ -127,17 +127,17 @@ fn desenhar_cabecalho_popup(ui: &mut egui::Ui, id_estacao: IdEstacao, acoes: &mu
         .italics());
 }
 
-fn desenhar_conteudo_popup(ui: &mut egui::Ui, popup: &PopupInfo) {
+fn desenhar_conteudo_popup(ui: &mut egui::Ui, grafo: &GrafoMetro, popup: &PopupInfo) {
     egui::ScrollArea::vertical()
         .max_height(300.0)
         .show(ui, |ui| {
             for linha in popup.conteudo.lines() {
-                formatar_linha_popup(ui, linha);
+                formatar_linha_popup(ui, grafo, linha);
             }
         });
 }
 
-fn formatar_linha_popup(ui: &mut egui::Ui, linha: &str) {
+fn formatar_linha_popup(ui: &mut egui::Ui, grafo: &GrafoMetro, linha: &str) {
     if linha.trim().is_empty() {
         ui.add_space(4.0);
     } else if linha.starts_with("Estação:") || linha.starts_with("ID:") {
@@ -158,7 +158,7 @@ fn formatar_linha_popup(ui: &mut egui::Ui, linha: &str) {
             .color(egui::Color32::from_rgb(150, 200, 255))
             .strong());
     } else if linha.starts_with("• Linha") {
-        let cor = determinar_cor_linha_popup(linha);
+        let cor = determinar_cor_linha_popup(grafo, linha);
         ui.label(egui::RichText::new(linha)
             .size(11.0)
             .color(cor));
@@ -191,18 +191,25 @@ fn determinar_cor_status(linha: &str) -> Color32 {
     }
 }
 
-fn determinar_cor_linha_popup(linha: &str) -> Color32 {
-    if linha.contains("Azul") {
-        egui::Color32::from_rgb(0, 120, 255)
-    } else if linha.contains("Amarela") {
-        egui::Color32::from_rgb(255, 215, 0)
-    } else if linha.contains("Vermelha") {
-        egui::Color32::RED
-    } else if linha.contains("Verde") {
-        egui::Color32::from_rgb(0, 180, 0)
-    } else {
-        egui::Color32::GRAY
-    }
+/// Texto de uma linha na lista de conexões do popup ("• Linha 14 (Metrô)")
+fn descrever_linha_popup(linha: &Linha) -> String {
+    format!("• Linha {} ({})", linha.nome, linha.modo.nome())
+}
+
+fn determinar_cor_linha_popup(grafo: &GrafoMetro, linha: &str) -> Color32 {
+    grafo.linhas.iter()
+        .find(|definicao| linha == descrever_linha_popup(definicao))
+        .map_or(egui::Color32::GRAY, |definicao| obter_cor_linha(grafo, definicao.id))
+}
+
+/// Linhas distintas que passam por uma estação, na ordem em que foram definidas
+fn linhas_da_estacao(grafo: &GrafoMetro, id_estacao: IdEstacao) -> Vec<IdLinha> {
+    let linhas_conectadas: HashSet<IdLinha> = grafo.lista_adjacencia.get(id_estacao)
+        .map(|conexoes| conexoes.iter().map(|conexao| conexao.linha).collect())
+        .unwrap_or_default();
+    let mut linhas: Vec<IdLinha> = linhas_conectadas.into_iter().collect();
+    linhas.sort_unstable();
+    linhas
 }
 
 fn desenhar_rodape_popup(ui: &mut egui::Ui) {
@@ -226,24 +233,13 @@ pub fn processar_acoes_popup(app: &mut MinhaAplicacaoGUI, acoes: Vec<AcaoPopup>)
                 if let Some(popup) = app.popups_info.get_mut(&acao.id_estacao) {
                     popup.visivel = false;
                 }
-            },
-            TipoAcaoPopup::Iniciar => {
-                
             },
             TipoAcaoPopup::MoverDelta => {
-                if let Some(delta) = acao.delta {
-                    if let Some(popup) = app.popups_info.get_mut(&acao.id_estacao) {
-                        let mut pos = popup.posicao.borrow().clone();
-                        pos += delta;
-                        *popup.posicao.borrow_mut() = pos;
-                    }
+                if let Some(delta) = acao.delta
+                    && let Some(popup) = app.popups_info.get_mut(&acao.id_estacao) {
+                    *popup.posicao.borrow_mut() += delta;
                 }
             },
-            TipoAcaoPopup::Soltar => {
-                if let Some(popup) = app.popups_info.get_mut(&acao.id_estacao) {
-                    popup.esta_sendo_arrastado = false;
-                }
-            }
         }
     }
 }
@@ -252,7 +248,9 @@ pub fn processar_acoes_popup(app: &mut MinhaAplicacaoGUI, acoes: Vec<AcaoPopup>)
 pub fn abrir_popup_estacao(app: &mut MinhaAplicacaoGUI, id_estacao: IdEstacao, grafo: &GrafoMetro) {
     let estacao = &grafo.estacoes[id_estacao];
     
-    let mut conteudo = format!("Estação: {}\nID: E{}\n\n", estacao.nome, id_estacao + 1);
+    let mut conteudo = format!("Estação: {}\nID: {}\n", estacao.nome, estacao.codigo);
+    conteudo.push_str(&descrever_metadados_estacao(estacao));
+    conteudo.push('\n');
     
     conteudo.push_str(&determinar_status_estacao(app, id_estacao));
     
@@ -261,17 +259,26 @@ pub fn abrir_popup_estacao(app: &mut MinhaAplicacaoGUI, id_estacao: IdEstacao, g
     conteudo.push_str("\nUse os controles do painel lateral para\n   selecionar início e destino");
     
     let popup = PopupInfo {
-        id_estacao,
         conteudo,
         posicao: RefCell::new(Vec2::new(50.0, -40.0)),
         visivel: true,
-        esta_sendo_arrastado: false,
-        tamanho: Vec2::new(300.0, 200.0),
     };
     
     app.popups_info.insert(id_estacao, popup);
 }
 
+/// Zona tarifária e acessibilidade, uma informação por linha (vazio se o arquivo de estações não as define)
+fn descrever_metadados_estacao(estacao: &crate::grafo_metro::Estacao) -> String {
+    let mut texto = String::new();
+    if let Some(zona) = estacao.zona {
+        texto.push_str(&format!("Zona: {}\n", zona));
+    }
+    if estacao.acessivel {
+        texto.push_str("Acessível para cadeirantes\n");
+    }
+    texto
+}
+
 fn determinar_status_estacao(app: &MinhaAplicacaoGUI, id_estacao: IdEstacao) -> String {
     if id_estacao == app.id_estacao_inicio_selecionada {
         "Status: ESTAÇÃO DE INÍCIO\n\n".to_string()
@@ -290,30 +297,17 @@ fn adicionar_informacoes_conectividade(conteudo: &mut String, grafo: &GrafoMetro
     if let Some(conexoes) = grafo.lista_adjacencia.get(id_estacao) {
         conteudo.push_str("CONEXÕES DISPONÍVEIS:\n");
         
-        let mut linhas_conectadas: HashSet<CorLinha> = HashSet::new();
-        for conexao in conexoes {
-            linhas_conectadas.insert(conexao.cor_linha);
-        }
-        
-        for linha in &linhas_conectadas {
-            let nome_linha = match linha {
-                CorLinha::Azul => "Linha Azul",
-                CorLinha::Amarela => "Linha Amarela", 
-                CorLinha::Vermelha => "Linha Vermelha",
-                CorLinha::Verde => "Linha Verde",
-                _ => "Linha Desconhecida",
-            };
-            conteudo.push_str(&format!("• {}\n", nome_linha));
+        for id_linha in linhas_da_estacao(grafo, id_estacao) {
+            conteudo.push_str(&descrever_linha_popup(&grafo.linhas[id_linha]));
+            conteudo.push('\n');
         }
         
         conteudo.push_str(&format!("\nTotal de conexões diretas: {}\n\n", conexoes.len()));
         
         conteudo.push_str("ESTAÇÕES CONECTADAS:\n");
-        let mut conexoes_mostradas = 0;
         for conexao in conexoes.iter().take(5) {
             let estacao_destino = &grafo.estacoes[conexao.para_estacao];
             conteudo.push_str(&format!("• {} ({:.1} min)\n", estacao_destino.nome, conexao.tempo_minutos));
-            conexoes_mostradas += 1;
         }
         
         if conexoes.len() > 5 {
@@ -330,14 +324,11 @@ pub fn mostrar_popup_vizinho_hover(
     id_estacao: IdEstacao,
     grafo: &GrafoMetro
 ) {
-    if let Some(ref solucionador) = app.solucionador_a_estrela {
-        if let Some(ref analise) = solucionador.ultima_analise {
-            if let Some(vizinho_info) = analise.vizinhos_analisados.iter()
-                .find(|v| v.starts_with(&format!("E{}", id_estacao + 1))) {
-                
-                mostrar_popup_analise_astar(app, ui, pos_estacao, id_estacao, grafo, vizinho_info);
-            }
-        }
+    if let Some(ref solucionador) = app.solucionador_a_estrela
+        && let Some(ref analise) = solucionador.ultima_analise
+        && let Some(indice) = analise.ids_vizinhos_analisados.iter().position(|&id| id == id_estacao)
+        && let Some(vizinho_info) = analise.vizinhos_analisados.get(indice) {
+        mostrar_popup_analise_astar(app, ui, pos_estacao, id_estacao, grafo, vizinho_info);
     }
 }
 
@@ -367,7 +358,7 @@ pub fn mostrar_popup_estacao_hover(
                     ui.set_max_width(280.0);
                     
                     desenhar_cabecalho_hover(ui);
-                    desenhar_info_basica_estacao(ui, estacao, id_estacao);
+                    desenhar_info_basica_estacao(ui, estacao);
                     desenhar_status_estacao_hover(app, ui, id_estacao);
                     desenhar_conectividade_hover(ui, grafo, id_estacao);
                     desenhar_dica_interacao(ui);
@@ -402,7 +393,7 @@ fn mostrar_popup_analise_astar(
                     ui.set_max_width(320.0);
                     
                     desenhar_cabecalho_astar(ui);
-                    desenhar_info_estacao_astar(ui, estacao, id_estacao);
+                    desenhar_info_estacao_astar(ui, estacao);
                     desenhar_valores_astar(ui, valor_f, valor_g, valor_h);
                     desenhar_conectividade_astar(ui, grafo, id_estacao);
                     desenhar_dica_detalhes(ui);
@@ -440,26 +431,33 @@ fn desenhar_cabecalho_astar(ui: &mut egui::Ui) {
     ui.separator();
 }
 
-fn desenhar_info_basica_estacao(ui: &mut egui::Ui, estacao: &crate::grafo_metro::Estacao, id_estacao: IdEstacao) {
-    ui.label(egui::RichText::new(format!("{}", estacao.nome))
+fn desenhar_info_basica_estacao(ui: &mut egui::Ui, estacao: &crate::grafo_metro::Estacao) {
+    ui.label(egui::RichText::new(&estacao.nome)
         .size(14.0)
         .color(egui::Color32::WHITE)
         .strong());
     
-    ui.label(egui::RichText::new(format!("Identificador: E{}", id_estacao + 1))
+    ui.label(egui::RichText::new(format!("Identificador: {}", estacao.codigo))
         .size(11.0)
         .color(egui::Color32::from_rgb(200, 200, 200)));
     
+    let metadados = descrever_metadados_estacao(estacao);
+    if !metadados.is_empty() {
+        ui.label(egui::RichText::new(metadados.trim_end())
+            .size(10.0)
+            .color(egui::Color32::from_rgb(180, 180, 180)));
+    }
+    
     ui.add_space(6.0);
 }
 
-fn desenhar_info_estacao_astar(ui: &mut egui::Ui, estacao: &crate::grafo_metro::Estacao, id_estacao: IdEstacao) {
+fn desenhar_info_estacao_astar(ui: &mut egui::Ui, estacao: &crate::grafo_metro::Estacao) {
     ui.label(egui::RichText::new(format!("Estação: {}", estacao.nome))
         .size(13.0)
         .color(egui::Color32::WHITE)
         .strong());
     
-    ui.label(egui::RichText::new(format!("ID: E{}", id_estacao + 1))
+    ui.label(egui::RichText::new(format!("ID: {}", estacao.codigo))
         .size(11.0)
         .color(egui::Color32::from_rgb(200, 200, 200)));
     
@@ -511,12 +509,12 @@ fn desenhar_valores_astar(ui: &mut egui::Ui, valor_f: Option<f32>, valor_g: Opti
             }
             
             // Mostrar fórmula visual
-            if valor_f.is_some() && valor_g.is_some() && valor_h.is_some() {
+            if let (Some(f), Some(g), Some(h)) = (valor_f, valor_g, valor_h) {
                 ui.add_space(8.0);
                 ui.separator();
                 ui.add_space(4.0);
                 
-                desenhar_formula_astar(ui, valor_f.unwrap(), valor_g.unwrap(), valor_h.unwrap());
+                desenhar_formula_astar(ui, f, g, h);
             }
         });
     
@@ -577,24 +575,11 @@ fn desenhar_conectividade_hover(ui: &mut egui::Ui, grafo: &GrafoMetro, id_estaca
             .color(egui::Color32::from_rgb(150, 200, 255))
             .strong());
         
-        let mut linhas_conectadas: HashSet<CorLinha> = HashSet::new();
-        for conexao in conexoes {
-            linhas_conectadas.insert(conexao.cor_linha);
-        }
-        
         ui.horizontal_wrapped(|ui| {
-            for linha in &linhas_conectadas {
-                let (cor_linha, nome_linha) = match linha {
-                    CorLinha::Azul => (egui::Color32::from_rgb(0, 120, 255), "Azul"),
-                    CorLinha::Amarela => (egui::Color32::from_rgb(255, 215, 0), "Amarela"),
-                    CorLinha::Vermelha => (egui::Color32::RED, "Vermelha"),
-                    CorLinha::Verde => (egui::Color32::from_rgb(0, 180, 0), "Verde"),
-                    _ => (egui::Color32::GRAY, "Outra"),
-                };
-                
-                ui.label(egui::RichText::new(format!("● {}", nome_linha))
+            for id_linha in linhas_da_estacao(grafo, id_estacao) {
+                ui.label(egui::RichText::new(format!("● {}", grafo.nome_linha(id_linha)))
                     .size(10.0)
-                    .color(cor_linha));
+                    .color(obter_cor_linha(grafo, id_linha)));
             }
         });
         
@@ -608,30 +593,17 @@ fn desenhar_conectividade_hover(ui: &mut egui::Ui, grafo: &GrafoMetro, id_estaca
 }
 
 fn desenhar_conectividade_astar(ui: &mut egui::Ui, grafo: &GrafoMetro, id_estacao: IdEstacao) {
-    if let Some(conexoes) = grafo.lista_adjacencia.get(id_estacao) {
+    if grafo.lista_adjacencia.get(id_estacao).is_some() {
         ui.label(egui::RichText::new("Conexões Disponíveis:")
             .size(12.0)
             .color(egui::Color32::from_rgb(150, 255, 150))
             .strong());
         
-        let mut linhas_conectadas: HashSet<CorLinha> = HashSet::new();
-        for conexao in conexoes {
-            linhas_conectadas.insert(conexao.cor_linha);
-        }
-        
         ui.horizontal_wrapped(|ui| {
-            for linha in &linhas_conectadas {
-                let (cor_linha, nome_linha) = match linha {
-                    CorLinha::Azul => (egui::Color32::from_rgb(0, 120, 255), "Azul"),
-                    CorLinha::Amarela => (egui::Color32::from_rgb(255, 215, 0), "Amarela"),
-                    CorLinha::Vermelha => (egui::Color32::RED, "Vermelha"),
-                    CorLinha::Verde => (egui::Color32::from_rgb(0, 180, 0), "Verde"),
-                    _ => (egui::Color32::GRAY, "Outra"),
-                };
-                
-                ui.label(egui::RichText::new(format!("● {}", nome_linha))
+            for id_linha in linhas_da_estacao(grafo, id_estacao) {
+                ui.label(egui::RichText::new(format!("● {}", grafo.nome_linha(id_linha)))
                     .size(10.0)
-                    .color(cor_linha));
+                    .color(obter_cor_linha(grafo, id_linha)));
             }
         });
     }
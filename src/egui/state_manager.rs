@@ -1,5 +1,10 @@
 use std::sync::Arc;
-use crate::algoritmo_a_estrela::SolucionadorAEstrela;
+use crate::algoritmo_a_estrela::{EventoVisual, ResultadoPassoAEstrela, SolucionadorAEstrela};
+use crate::busca_bidirecional::{SentidoBusca, SolucionadorBidirecional};
+use crate::busca_chegada::buscar_partida_mais_tarde;
+use crate::busca_pareto::buscar_rotas_pareto;
+use crate::quadro_horarios::{formatar_horario, interpretar_horario, ConsultaHorarios};
+use crate::rotas_alternativas::buscar_rotas_alternativas;
 use super::app::MinhaAplicacaoGUI;
 
 /// Limpa todos os estados visuais do algoritmo
@@ -10,11 +15,16 @@ pub fn limpar_estado_visual(app: &mut MinhaAplicacaoGUI) {
     app.detalhes_analise_ui.clear();
     app.vizinhos_sendo_analisados_ui.clear();
     app.solucionador_a_estrela = None;
+    app.solucionador_bidirecional = None;
+    app.rotas_pareto.clear();
+    app.rota_pareto_selecionada = None;
+    app.rotas_alternativas.clear();
+    app.rota_alternativa_selecionada = None;
 }
 
 /// Inicia uma nova busca A*
 pub fn iniciar_busca_a_estrela(app: &mut MinhaAplicacaoGUI) {
-    if let Some(ref grafo) = app.grafo_metro {
+    if let Some(grafo) = app.grafo_metro.as_ref().filter(|grafo| grafo.numero_estacoes() > 0) {
         let grafo_arco = Arc::clone(grafo);
         let id_inicio = app.id_estacao_inicio_selecionada;
         let id_objetivo = app.id_estacao_objetivo_selecionada;
@@ -22,18 +32,44 @@ pub fn iniciar_busca_a_estrela(app: &mut MinhaAplicacaoGUI) {
         let nome_inicio = grafo.estacoes[id_inicio].nome.clone();
         let nome_objetivo = grafo.estacoes[id_objetivo].nome.clone();
         
+        // O horário de partida só vale para a busca de um sentido
+        let horarios = if app.usar_horario_partida && !app.busca_bidirecional {
+            let Some(horario_partida) = interpretar_horario(&app.texto_horario_partida) else {
+                app.mensagem_status_ui = format!("Erro: horário de partida inválido '{}' (use HH:MM).", app.texto_horario_partida);
+                return;
+            };
+            Some(ConsultaHorarios { quadro: Arc::clone(&app.quadro_horarios), horario_partida })
+        } else {
+            None
+        };
+        
         limpar_estado_visual(app);
         
-        let solucionador = SolucionadorAEstrela::novo(
+        if app.busca_bidirecional {
+            app.solucionador_bidirecional = Some(SolucionadorBidirecional::novo(
+                grafo_arco,
+                id_inicio,
+                app.linha_inicio_opcional,
+                id_objetivo
+            ));
+            app.mensagem_status_ui = format!("Busca bidirecional iniciada: De {} para {}", nome_inicio, nome_objetivo);
+            return;
+        }
+        
+        let mut solucionador = SolucionadorAEstrela::novo(
             grafo_arco,
             id_inicio,
             app.linha_inicio_opcional,
             id_objetivo
-        );
+        ).com_estrategia(app.estrategia_busca);
+        if let Some(horarios) = horarios {
+            solucionador = solucionador.com_horarios(horarios);
+        }
         
         app.solucionador_a_estrela = Some(solucionador);
         app.mensagem_status_ui = format!(
-            "Busca iniciada: De {} para {}", 
+            "Busca iniciada ({}): De {} para {}", 
+            app.estrategia_busca,
             nome_inicio, 
             nome_objetivo
         );
@@ -42,13 +78,84 @@ pub fn iniciar_busca_a_estrela(app: &mut MinhaAplicacaoGUI) {
     }
 }
 
+/// Calcula de uma vez todas as rotas que trocam tempo por menos baldeações e destaca a mais rápida
+pub fn calcular_rotas_pareto(app: &mut MinhaAplicacaoGUI) {
+    let Some(grafo) = app.grafo_metro.clone().filter(|grafo| grafo.numero_estacoes() > 0) else {
+        app.mensagem_status_ui = "Erro: Grafo não carregado.".to_string();
+        return;
+    };
+    limpar_estado_visual(app);
+
+    let rotas_pareto = buscar_rotas_pareto(&grafo, app.id_estacao_inicio_selecionada, app.linha_inicio_opcional, app.id_estacao_objetivo_selecionada);
+    app.mensagem_status_ui = match rotas_pareto.rotas.len() {
+        0 => "❌ Nenhum caminho possível encontrado!".to_string(),
+        1 => "Só uma opção: a rota mais rápida é também a de menos baldeações.".to_string(),
+        quantidade => format!("{} opções entre tempo e baldeações. Clique em uma para vê-la no mapa.", quantidade),
+    };
+    app.rotas_pareto = rotas_pareto.rotas;
+    selecionar_rota_pareto(app, 0);
+}
+
+/// Destaca no mapa uma das opções de Pareto
+pub fn selecionar_rota_pareto(app: &mut MinhaAplicacaoGUI, indice: usize) {
+    if let Some(rota) = app.rotas_pareto.get(indice) {
+        app.rota_pareto_selecionada = Some(indice);
+        app.resultado_caminho_ui = Some(rota.clone());
+    }
+}
+
+/// Calcula a melhor rota e as seguintes (Yen); todas ficam sobrepostas no mapa, a selecionada cheia
+pub fn calcular_rotas_alternativas(app: &mut MinhaAplicacaoGUI) {
+    let Some(grafo) = app.grafo_metro.clone().filter(|grafo| grafo.numero_estacoes() > 0) else {
+        app.mensagem_status_ui = "Erro: Grafo não carregado.".to_string();
+        return;
+    };
+    limpar_estado_visual(app);
+
+    app.rotas_alternativas = buscar_rotas_alternativas(&grafo, app.id_estacao_inicio_selecionada, app.linha_inicio_opcional,
+        app.id_estacao_objetivo_selecionada, app.quantidade_alternativas);
+    app.mensagem_status_ui = match app.rotas_alternativas.len() {
+        0 => "❌ Nenhum caminho possível encontrado!".to_string(),
+        1 => "Não há rota alternativa sem repetir estações.".to_string(),
+        quantidade => format!("Melhor rota e {} alternativa(s). Clique em uma para destacá-la.", quantidade - 1),
+    };
+    selecionar_rota_alternativa(app, 0);
+}
+
+/// Consulta "chegar até": o horário mais tarde para sair do início e chegar ao objetivo a tempo
+pub fn calcular_partida_mais_tarde(app: &mut MinhaAplicacaoGUI) {
+    let Some(grafo) = app.grafo_metro.clone().filter(|grafo| grafo.numero_estacoes() > 0) else {
+        app.mensagem_status_ui = "Erro: Grafo não carregado.".to_string();
+        return;
+    };
+    let Some(horario_chegada) = interpretar_horario(&app.texto_horario_chegada) else {
+        app.mensagem_status_ui = format!("Erro: horário de chegada inválido '{}' (use HH:MM).", app.texto_horario_chegada);
+        return;
+    };
+    limpar_estado_visual(app);
+
+    let resultado = buscar_partida_mais_tarde(&grafo, &app.quadro_horarios, app.id_estacao_inicio_selecionada,
+        app.linha_inicio_opcional, app.id_estacao_objetivo_selecionada, horario_chegada);
+    app.mensagem_status_ui = match resultado.caminho.as_ref().and_then(|caminho| caminho.horario_partida) {
+        Some(partida) => format!("Para chegar até {}, parta às {} de {}.", formatar_horario(horario_chegada), formatar_horario(partida),
+            grafo.estacoes[app.id_estacao_inicio_selecionada].nome),
+        None => "❌ Nenhum caminho possível encontrado!".to_string(),
+    };
+    app.resultado_caminho_ui = resultado.caminho;
+}
+
+/// Destaca uma das k rotas; as outras continuam tracejadas no mapa
+pub fn selecionar_rota_alternativa(app: &mut MinhaAplicacaoGUI, indice: usize) {
+    if let Some(rota) = app.rotas_alternativas.get(indice) {
+        app.rota_alternativa_selecionada = Some(indice);
+        app.resultado_caminho_ui = Some(rota.caminho.clone());
+    }
+}
+
 /// Executa o próximo passo do algoritmo A*
 pub fn executar_proximo_passo_a_estrela(app: &mut MinhaAplicacaoGUI) {
-    let resultado = if let Some(ref mut solucionador) = app.solucionador_a_estrela {
-        Some(solucionador.proximo_passo())
-    } else {
-        None
-    };
+    let resultado = app.solucionador_a_estrela.as_mut()
+        .map(|solucionador| solucionador.proximo_passo());
     
     if let Some(resultado) = resultado {
         match resultado {
@@ -78,32 +185,85 @@ pub fn executar_proximo_passo_a_estrela(app: &mut MinhaAplicacaoGUI) {
     }
 }
 
+/// Avança um único evento da busca (modo micro-passo)
+pub fn executar_proximo_micro_passo_a_estrela(app: &mut MinhaAplicacaoGUI) {
+    let Some(evento) = app.solucionador_a_estrela.as_mut().map(|solucionador| solucionador.proximo_micro_passo()) else {
+        app.mensagem_status_ui = "Erro: Nenhuma busca em andamento.".to_string();
+        return;
+    };
+
+    match evento {
+        EventoVisual::CaminhoEncontrado { info_caminho } => processar_caminho_encontrado(app, info_caminho),
+        EventoVisual::NenhumCaminho => processar_nenhum_caminho(app),
+        evento => {
+            atualizar_estado_visual_do_solucionador(app);
+            if let (Some(grafo), Some(solucionador)) = (&app.grafo_metro, &app.solucionador_a_estrela) {
+                let (titulo, _) = super::explicacao_evento::explicar_evento(grafo, &evento, solucionador.estrategia());
+                app.mensagem_status_ui = format!("Micro-passo: {}", titulo);
+            }
+        }
+    }
+}
+
+/// Um passo da busca bidirecional: expande um nó da ida ou da volta
+pub fn executar_proximo_passo_bidirecional(app: &mut MinhaAplicacaoGUI) {
+    let Some(solucionador) = app.solucionador_bidirecional.as_mut().filter(|solucionador| !solucionador.esta_finalizado()) else {
+        return;
+    };
+
+    match solucionador.proximo_passo() {
+        ResultadoPassoAEstrela::EmProgresso => {
+            let sentido = match solucionador.ultima_expansao {
+                Some((SentidoBusca::Ida, _)) => "ida",
+                _ => "volta",
+            };
+            let encontro = solucionador.melhor_encontro
+                .map_or_else(|| "ainda sem encontro".to_string(), |encontro| format!("melhor encontro: {:.1} min", encontro.custo_total));
+            app.estacao_sendo_expandida_ui = solucionador.ultima_expansao.map(|(_, id_estacao)| id_estacao);
+            app.mensagem_status_ui = format!("Passo {} ({}): {}", solucionador.passo_atual, sentido, encontro);
+        }
+        ResultadoPassoAEstrela::CaminhoEncontrado(caminho_info) => {
+            let expandidos = solucionador.estados_expandidos();
+            app.estacao_sendo_expandida_ui = None;
+            app.mensagem_status_ui = format!(
+                "✅ Caminho encontrado! Tempo: {:.1} min, Baldeações: {}, {} estados expandidos",
+                caminho_info.tempo_total_minutos, caminho_info.baldeacoes, expandidos
+            );
+            app.resultado_caminho_ui = Some(caminho_info);
+        }
+        ResultadoPassoAEstrela::NenhumCaminhoPossivel => {
+            app.estacao_sendo_expandida_ui = None;
+            app.mensagem_status_ui = "❌ Não foi possível encontrar um caminho.".to_string();
+        }
+        ResultadoPassoAEstrela::Erro(msg) => processar_erro(app, msg),
+    }
+}
+
 fn processar_passo_em_progresso_dados(app: &mut MinhaAplicacaoGUI, analise: &crate::algoritmo_a_estrela::DetalhesAnalise) {
         app.estacao_sendo_expandida_ui = Some(analise.estacao_expandida);
         
         app.estacoes_exploradas_ui.insert(analise.estacao_expandida);
         
         app.vizinhos_sendo_analisados_ui.clear();
-        for vizinho_info in &analise.vizinhos_analisados {
-            if let Some(id_estacao) = extrair_id_estacao_de_info(vizinho_info) {
-                if !app.estacoes_exploradas_ui.contains(&id_estacao) {
-                    app.vizinhos_sendo_analisados_ui.insert(id_estacao);
-                }
+        for id_estacao in &analise.ids_vizinhos_analisados {
+            if !app.estacoes_exploradas_ui.contains(id_estacao) {
+                app.vizinhos_sendo_analisados_ui.insert(*id_estacao);
             }
         }
         
         app.detalhes_analise_ui = analise.vizinhos_analisados.clone();
         
-        let nome_estacao = if let Some(ref grafo) = app.grafo_metro {
-            &grafo.estacoes[analise.estacao_expandida].nome
+        let (nome_estacao, codigo_estacao) = if let Some(ref grafo) = app.grafo_metro {
+            let estacao = &grafo.estacoes[analise.estacao_expandida];
+            (estacao.nome.as_str(), estacao.codigo.as_str())
         } else {
-            "Desconhecida"
+            ("Desconhecida", "?")
         };
         
         app.mensagem_status_ui = format!(
-            "Expandindo {} (E{}) - Analisando vizinhos",
+            "Expandindo {} ({}) - Analisando vizinhos",
             nome_estacao,
-            analise.estacao_expandida + 1
+            codigo_estacao
         );
 }
 
@@ -140,33 +300,13 @@ fn processar_erro(app: &mut MinhaAplicacaoGUI, msg: String) {
     app.solucionador_a_estrela = None;
 }
 
-/// Extrai o ID da estação de uma string de informação
-fn extrair_id_estacao_de_info(vizinho_info: &str) -> Option<usize> {
-    if let Some(inicio_e) = vizinho_info.find('E') {
-        if let Some(pos_dois_pontos) = vizinho_info.find(':') {
-            if pos_dois_pontos > inicio_e + 1 {
-                let numero_str = &vizinho_info[inicio_e + 1..pos_dois_pontos];
-                if let Ok(id_estacao_um_baseado) = numero_str.parse::<usize>() {
-                    if id_estacao_um_baseado > 0 {
-                        return Some(id_estacao_um_baseado - 1);
-                    }
-                }
-            }
-        }
-    }
-    None
-}
-
 /// Atualiza o estado visual da GUI com base no solucionador atual
 pub fn atualizar_estado_visual_do_solucionador(app: &mut MinhaAplicacaoGUI) {
     if let Some(ref solucionador) = app.solucionador_a_estrela {
         app.estacoes_exploradas_ui.clear();
         for (id_estacao, status) in &solucionador.status_estacoes {
-            match status {
-                crate::algoritmo_a_estrela::StatusEstacao::Explorada => {
-                    app.estacoes_exploradas_ui.insert(*id_estacao);
-                },
-                _ => {}
+            if *status == crate::algoritmo_a_estrela::StatusEstacao::Explorada {
+                app.estacoes_exploradas_ui.insert(*id_estacao);
             }
         }
         
@@ -1,5 +1,6 @@
+use std::f32::consts::FRAC_1_SQRT_2;
 use egui::{Color32, Pos2, Stroke, Vec2};
-use crate::grafo_metro::{CorLinha, GrafoMetro, IdEstacao};
+use crate::grafo_metro::{GrafoMetro, IdEstacao};
 use super::app::MinhaAplicacaoGUI;
 
 /// Desenha marcadores visuais acima das estações
@@ -110,33 +111,21 @@ pub fn desenhar_icone_baldeacao(
     painter: &egui::Painter,
     posicao: Pos2,
     tamanho: f32,
-    linhas: Option<(CorLinha, CorLinha)>
+    cores_linhas: Option<(Color32, Color32)>,
+    tempo_baldeacao_minutos: f32,
 ) {
-    if let Some((de_linha, para_linha)) = linhas {
-        let cor1 = obter_cor_linha_baldeacao(de_linha);
-        let cor2 = obter_cor_linha_baldeacao(para_linha);
-        
+    if let Some((cor1, cor2)) = cores_linhas {
         // Desenhar base da baldeação
         desenhar_base_baldeacao(app, painter, posicao, tamanho);
         
         // Desenhar semi-círculos de cores diferentes
-        desenhar_semicirculos_baldeacao(app, painter, posicao, tamanho, cor1, cor2);
+        desenhar_semicirculos_baldeacao(painter, posicao, tamanho, cor1, cor2);
         
         // Desenhar símbolo de transferência
         desenhar_simbolo_transferencia(app, painter, posicao, tamanho);
         
         // Desenhar balão de tempo
-        desenhar_balao_tempo_baldeacao(app, painter, posicao, tamanho);
-    }
-}
-
-fn obter_cor_linha_baldeacao(linha: CorLinha) -> Color32 {
-    match linha {
-        CorLinha::Azul => Color32::from_rgb(0, 120, 255),
-        CorLinha::Amarela => Color32::from_rgb(255, 215, 0),
-        CorLinha::Vermelha => Color32::RED,
-        CorLinha::Verde => Color32::from_rgb(0, 180, 0),
-        _ => Color32::GRAY,
+        desenhar_balao_tempo_baldeacao(app, painter, posicao, tamanho, tempo_baldeacao_minutos);
     }
 }
 
@@ -164,7 +153,6 @@ fn desenhar_base_baldeacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, pos
 }
 
 fn desenhar_semicirculos_baldeacao(
-    app: &MinhaAplicacaoGUI,
     painter: &egui::Painter,
     posicao: Pos2,
     tamanho: f32,
@@ -178,9 +166,9 @@ fn desenhar_semicirculos_baldeacao(
         vec![
             posicao,
             posicao + Vec2::new(-raio, 0.0),
-            posicao + Vec2::new(-raio * 0.7071, -raio * 0.7071),
+            posicao + Vec2::new(-raio * FRAC_1_SQRT_2, -raio * FRAC_1_SQRT_2),
             posicao + Vec2::new(0.0, -raio),
-            posicao + Vec2::new(raio * 0.7071, -raio * 0.7071),
+            posicao + Vec2::new(raio * FRAC_1_SQRT_2, -raio * FRAC_1_SQRT_2),
             posicao + Vec2::new(raio, 0.0),
         ],
         cor1,
@@ -192,9 +180,9 @@ fn desenhar_semicirculos_baldeacao(
         vec![
             posicao,
             posicao + Vec2::new(raio, 0.0),
-            posicao + Vec2::new(raio * 0.7071, raio * 0.7071),
+            posicao + Vec2::new(raio * FRAC_1_SQRT_2, raio * FRAC_1_SQRT_2),
             posicao + Vec2::new(0.0, raio),
-            posicao + Vec2::new(-raio * 0.7071, raio * 0.7071),
+            posicao + Vec2::new(-raio * FRAC_1_SQRT_2, raio * FRAC_1_SQRT_2),
             posicao + Vec2::new(-raio, 0.0),
         ],
         cor2,
@@ -275,11 +263,11 @@ fn desenhar_seta_circular(
     );
 }
 
-fn desenhar_balao_tempo_baldeacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, posicao: Pos2, tamanho: f32) {
-    let texto_tempo = "+4.0min"; // Tempo fixo de baldeação
+fn desenhar_balao_tempo_baldeacao(app: &MinhaAplicacaoGUI, painter: &egui::Painter, posicao: Pos2, tamanho: f32, tempo_baldeacao_minutos: f32) {
+    let texto_tempo = format!("+{:.1}min", tempo_baldeacao_minutos);
     
     let texto_galley = painter.layout_no_wrap(
-        texto_tempo.to_string(),
+        texto_tempo.clone(),
         egui::FontId::proportional(11.0 * app.zoom_nivel),
         Color32::WHITE,
     );
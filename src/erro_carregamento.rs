@@ -0,0 +1,189 @@
+use std::fmt;
+
+/// Como os carregadores tratam dados inconsistentes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModoCarregamento {
+    /// Qualquer inconsistência interrompe o carregamento com erro
+    Estrito,
+    /// Inconsistências são ignoradas e devolvidas como avisos
+    #[default]
+    Tolerante,
+}
+
+/// Posição de um problema nos dados de origem
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalDados {
+    pub arquivo: String,
+    pub linha: Option<u64>,     // Linha física no arquivo (o cabeçalho é a linha 1)
+    pub coluna: Option<String>, // Nome da coluna (nas matrizes, o código da estação de destino)
+}
+
+impl LocalDados {
+    pub fn celula(arquivo: &str, linha: Option<u64>, coluna: &str) -> Self {
+        LocalDados { arquivo: arquivo.to_string(), linha, coluna: Some(coluna.to_string()) }
+    }
+}
+
+impl fmt::Display for LocalDados {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.arquivo)?;
+        if let Some(linha) = self.linha {
+            write!(f, ", linha {}", linha)?;
+        }
+        if let Some(coluna) = &self.coluna {
+            write!(f, ", coluna {}", coluna)?;
+        }
+        Ok(())
+    }
+}
+
+/// Inconsistência encontrada nos dados. No modo tolerante o dado é ignorado e o aviso
+/// devolvido ao chamador; no modo estrito vira `ErroCarregamento::Inconsistencia`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AvisoCarregamento {
+    /// Célula que não pôde ser interpretada (número, cor, modo...)
+    ValorInvalido {
+        local: LocalDados,
+        valor: String,
+        esperado: &'static str,
+    },
+    /// Distância heurística negativa entre duas estações
+    DistanciaNegativa {
+        local: LocalDados,
+        estacao_origem: String,
+        estacao_destino: String,
+        valor: f32,
+    },
+    /// Código de linha usado numa conexão mas não definido nos dados de linhas
+    LinhaDesconhecida {
+        local: LocalDados,
+        codigo_linha: String,
+        estacao_origem: String,
+        estacao_destino: String,
+    },
+    /// Conexão com linha definida, mas sem distância real correspondente
+    ConexaoSemDistancia {
+        local: LocalDados,
+        codigo_linha: String,
+        estacao_origem: String,
+        estacao_destino: String,
+    },
+    /// Distância real definida para um par de estações sem linha correspondente
+    DistanciaSemLinha {
+        local: LocalDados,
+        estacao_origem: String,
+        estacao_destino: String,
+        distancia_km: f32,
+    },
+    /// Referência a uma estação que não existe nos dados
+    EstacaoDesconhecida {
+        local: LocalDados,
+        codigo: String,
+    },
+}
+
+impl fmt::Display for AvisoCarregamento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AvisoCarregamento::ValorInvalido { local, valor, esperado } => {
+                write!(f, "{}: valor '{}' inválido (esperado {})", local, valor, esperado)
+            }
+            AvisoCarregamento::DistanciaNegativa { local, estacao_origem, estacao_destino, valor } => {
+                write!(f, "{}: distância negativa ({}) entre {} e {}", local, valor, estacao_origem, estacao_destino)
+            }
+            AvisoCarregamento::LinhaDesconhecida { local, codigo_linha, estacao_origem, estacao_destino } => {
+                write!(f, "{}: linha '{}' entre {} e {} não está definida", local, codigo_linha, estacao_origem, estacao_destino)
+            }
+            AvisoCarregamento::ConexaoSemDistancia { local, codigo_linha, estacao_origem, estacao_destino } => {
+                write!(f, "{}: conexão {} -> {} na linha '{}' sem distância real", local, estacao_origem, estacao_destino, codigo_linha)
+            }
+            AvisoCarregamento::DistanciaSemLinha { local, estacao_origem, estacao_destino, distancia_km } => {
+                write!(f, "{}: distância {} km entre {} e {} sem linha de conexão", local, distancia_km, estacao_origem, estacao_destino)
+            }
+            AvisoCarregamento::EstacaoDesconhecida { local, codigo } => {
+                write!(f, "{}: estação '{}' não existe", local, codigo)
+            }
+        }
+    }
+}
+
+/// Falha que impede o carregamento de uma rede
+#[derive(Debug)]
+pub enum ErroCarregamento {
+    Io { arquivo: String, erro: std::io::Error },
+    Csv { arquivo: String, erro: csv::Error },
+    /// Documento que não pôde ser lido no seu formato (JSON, TOML, zip, versão)
+    Formato { arquivo: String, mensagem: String },
+    ArquivoAusente { arquivo: String },
+    ColunaAusente { arquivo: String, coluna: String },
+    /// Inconsistência nos dados, no modo estrito
+    Inconsistencia(Box<AvisoCarregamento>),
+}
+
+impl ErroCarregamento {
+    pub(crate) fn io(arquivo: &str) -> impl FnOnce(std::io::Error) -> Self + '_ {
+        move |erro| ErroCarregamento::Io { arquivo: arquivo.to_string(), erro }
+    }
+
+    pub(crate) fn csv(arquivo: &str) -> impl FnOnce(csv::Error) -> Self + '_ {
+        move |erro| ErroCarregamento::Csv { arquivo: arquivo.to_string(), erro }
+    }
+
+    pub(crate) fn formato(arquivo: &str, mensagem: impl fmt::Display) -> Self {
+        ErroCarregamento::Formato { arquivo: arquivo.to_string(), mensagem: mensagem.to_string() }
+    }
+
+    pub(crate) fn coluna_ausente(arquivo: &str, coluna: &str) -> Self {
+        ErroCarregamento::ColunaAusente { arquivo: arquivo.to_string(), coluna: coluna.to_string() }
+    }
+}
+
+impl fmt::Display for ErroCarregamento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErroCarregamento::Io { arquivo, erro } => write!(f, "{}: {}", arquivo, erro),
+            ErroCarregamento::Csv { arquivo, erro } => write!(f, "{}: {}", arquivo, erro),
+            ErroCarregamento::Formato { arquivo, mensagem } => write!(f, "{}: {}", arquivo, mensagem),
+            ErroCarregamento::ArquivoAusente { arquivo } => write!(f, "arquivo obrigatório ausente: {}", arquivo),
+            ErroCarregamento::ColunaAusente { arquivo, coluna } => write!(f, "{}: coluna obrigatória '{}' ausente", arquivo, coluna),
+            ErroCarregamento::Inconsistencia(aviso) => write!(f, "{}", aviso),
+        }
+    }
+}
+
+impl std::error::Error for ErroCarregamento {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ErroCarregamento::Io { erro, .. } => Some(erro),
+            ErroCarregamento::Csv { erro, .. } => Some(erro),
+            _ => None,
+        }
+    }
+}
+
+/// Junta os avisos de um carregamento, ou interrompe no primeiro se o modo for estrito
+#[derive(Debug)]
+pub(crate) struct ColetorAvisos {
+    modo: ModoCarregamento,
+    avisos: Vec<AvisoCarregamento>,
+}
+
+impl ColetorAvisos {
+    pub(crate) fn novo(modo: ModoCarregamento) -> Self {
+        ColetorAvisos { modo, avisos: Vec::new() }
+    }
+
+    pub(crate) fn registrar(&mut self, aviso: AvisoCarregamento) -> Result<(), ErroCarregamento> {
+        match self.modo {
+            ModoCarregamento::Estrito => Err(ErroCarregamento::Inconsistencia(Box::new(aviso))),
+            ModoCarregamento::Tolerante => {
+                self.avisos.push(aviso);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn concluir(self) -> Vec<AvisoCarregamento> {
+        self.avisos
+    }
+}
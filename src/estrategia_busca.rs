@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Peso da heurística no A* ponderado quando nenhum é informado
+pub const EPSILON_PADRAO: f32 = 1.5;
+
+/// Como a fronteira é ordenada. Todas as estratégias usam o mesmo motor de eventos, o mesmo
+/// estado (estação, linha de chegada) e o mesmo custo g; muda só a prioridade de cada nó.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub enum EstrategiaBusca {
+    /// f = g + h: ótimo com heurística admissível
+    #[default]
+    AEstrela,
+    /// f = g (h = 0): ótimo, expande tudo que custa menos que a rota
+    Dijkstra,
+    /// f = h (g ignorado): rápido, sem garantia de rota ótima
+    GulosaMelhorPrimeiro,
+    /// f = número de trechos: menor quantidade de trechos, não de tempo
+    Largura,
+    /// f = g + ε·h: no máximo ε vezes o custo ótimo, em geral com menos expansões
+    AEstrelaPonderado { epsilon: f32 },
+}
+
+impl EstrategiaBusca {
+    /// As estratégias na ordem em que aparecem nas listas de escolha
+    pub fn todas(epsilon: f32) -> [EstrategiaBusca; 5] {
+        [
+            EstrategiaBusca::AEstrela,
+            EstrategiaBusca::Dijkstra,
+            EstrategiaBusca::GulosaMelhorPrimeiro,
+            EstrategiaBusca::Largura,
+            EstrategiaBusca::AEstrelaPonderado { epsilon },
+        ]
+    }
+
+    /// Interpreta o nome usado na linha de comando ("a-estrela", "dijkstra", "gulosa", "largura", "ponderado")
+    pub fn de_texto(texto: &str, epsilon: f32) -> Option<Self> {
+        match texto.trim().to_lowercase().as_str() {
+            "a-estrela" | "a*" | "astar" => Some(EstrategiaBusca::AEstrela),
+            "dijkstra" => Some(EstrategiaBusca::Dijkstra),
+            "gulosa" | "greedy" => Some(EstrategiaBusca::GulosaMelhorPrimeiro),
+            "largura" | "bfs" => Some(EstrategiaBusca::Largura),
+            "ponderado" | "a-estrela-ponderado" | "weighted" => Some(EstrategiaBusca::AEstrelaPonderado { epsilon }),
+            _ => None,
+        }
+    }
+
+    pub fn nome(self) -> &'static str {
+        match self {
+            EstrategiaBusca::AEstrela => "A*",
+            EstrategiaBusca::Dijkstra => "Dijkstra",
+            EstrategiaBusca::GulosaMelhorPrimeiro => "Gulosa (melhor primeiro)",
+            EstrategiaBusca::Largura => "Busca em largura",
+            EstrategiaBusca::AEstrelaPonderado { .. } => "A* ponderado",
+        }
+    }
+
+    /// Se a primeira rota que chega ao objetivo é a de menor tempo (com heurística admissível)
+    pub fn garante_rota_otima(self) -> bool {
+        matches!(self, EstrategiaBusca::AEstrela | EstrategiaBusca::Dijkstra)
+    }
+
+    /// Prioridade de um nó na fronteira (menor sai primeiro)
+    pub fn prioridade(self, custo_g: f32, custo_h: f32, trechos: usize) -> f32 {
+        match self {
+            EstrategiaBusca::AEstrela => custo_g + custo_h,
+            EstrategiaBusca::Dijkstra => custo_g,
+            EstrategiaBusca::GulosaMelhorPrimeiro => custo_h,
+            EstrategiaBusca::Largura => trechos as f32,
+            EstrategiaBusca::AEstrelaPonderado { epsilon } => custo_g + epsilon * custo_h,
+        }
+    }
+
+    /// Conta de f com os valores de um nó, para as explicações passo a passo
+    pub fn descrever_prioridade(self, custo_g: f32, custo_h: f32, custo_f: f32) -> String {
+        match self {
+            EstrategiaBusca::AEstrela => format!("f = g + h = {:.1} + {:.1} = {:.1}", custo_g, custo_h, custo_f),
+            EstrategiaBusca::Dijkstra => format!("f = g = {:.1}", custo_f),
+            EstrategiaBusca::GulosaMelhorPrimeiro => format!("f = h = {:.1}", custo_f),
+            EstrategiaBusca::Largura => format!("f = nº de trechos = {:.0}", custo_f),
+            EstrategiaBusca::AEstrelaPonderado { epsilon } =>
+                format!("f = g + {:.1}·h = {:.1} + {:.1}·{:.1} = {:.1}", epsilon, custo_g, epsilon, custo_h, custo_f),
+        }
+    }
+}
+
+impl fmt::Display for EstrategiaBusca {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EstrategiaBusca::AEstrelaPonderado { epsilon } => write!(f, "{} (ε = {:.1})", self.nome(), epsilon),
+            _ => f.write_str(self.nome()),
+        }
+    }
+}
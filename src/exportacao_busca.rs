@@ -0,0 +1,103 @@
+use crate::algoritmo_a_estrela::{EventoVisual, InfoCaminho, SolucionadorAEstrela};
+use crate::grafo_metro::{GrafoMetro, IdEstacao};
+use crate::quadro_horarios::formatar_horario;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// Estação do itinerário com os códigos e nomes já resolvidos, para quem lê o JSON sem o grafo
+#[derive(Debug, Clone, Serialize)]
+pub struct ParadaRota {
+    pub id: IdEstacao,
+    pub codigo: String,
+    pub nome: String,
+    pub codigo_linha: Option<String>, // Linha usada para chegar nesta estação (None na origem)
+    pub nome_linha: Option<String>,
+    pub tempo_trecho_minutos: Option<f32>, // Tempo de viagem desde a estação anterior, sem baldeação
+    // Nas consultas com horário: chegada pelo trecho anterior, espera e partida do trecho seguinte ("HH:MM")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horario_chegada: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub espera_minutos: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horario_partida: Option<String>,
+}
+
+/// `InfoCaminho` com as estações e linhas descritas pelo código e pelo nome
+#[derive(Debug, Clone, Serialize)]
+pub struct RotaDetalhada {
+    pub origem: String,
+    pub destino: String,
+    pub codigo_linha_partida: Option<String>,
+    pub estacoes: Vec<ParadaRota>,
+    pub baldeacoes: u32,
+    pub tempo_total_minutos: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horario_partida: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horario_chegada: Option<String>,
+}
+
+impl RotaDetalhada {
+    pub fn do_caminho(grafo: &GrafoMetro, caminho: &InfoCaminho) -> Self {
+        let estacoes: Vec<ParadaRota> = caminho.estacoes_do_caminho.iter().enumerate()
+            .map(|(indice, &(id, linha))| {
+                let linha = linha.and_then(|id_linha| grafo.linhas.get(id_linha));
+                let trecho_anterior = indice.checked_sub(1).and_then(|trecho| caminho.horarios_trechos.get(trecho));
+                let trecho_seguinte = caminho.horarios_trechos.get(indice);
+                ParadaRota {
+                    id,
+                    codigo: grafo.estacoes[id].codigo.clone(),
+                    nome: grafo.estacoes[id].nome.clone(),
+                    codigo_linha: linha.map(|linha| linha.codigo.clone()),
+                    nome_linha: linha.map(|linha| linha.nome.clone()),
+                    tempo_trecho_minutos: indice.checked_sub(1).and_then(|trecho| caminho.tempos_trechos_minutos.get(trecho).copied()),
+                    horario_chegada: trecho_anterior.map(|horario| formatar_horario(horario.chegada)),
+                    espera_minutos: trecho_seguinte.map(|horario| horario.espera_minutos),
+                    horario_partida: trecho_seguinte.map(|horario| formatar_horario(horario.partida)),
+                }
+            })
+            .collect();
+
+        RotaDetalhada {
+            origem: estacoes.first().map(|parada| parada.codigo.clone()).unwrap_or_default(),
+            destino: estacoes.last().map(|parada| parada.codigo.clone()).unwrap_or_default(),
+            codigo_linha_partida: caminho.linha_partida
+                .and_then(|id_linha| grafo.linhas.get(id_linha))
+                .map(|linha| linha.codigo.clone()),
+            estacoes,
+            baldeacoes: caminho.baldeacoes,
+            tempo_total_minutos: caminho.tempo_total_minutos,
+            horario_partida: caminho.horario_partida.map(formatar_horario),
+            horario_chegada: caminho.horario_partida.map(|partida| formatar_horario(partida + caminho.tempo_total_minutos)),
+        }
+    }
+}
+
+/// Roda a busca evento a evento até o fim e devolve todos os eventos emitidos,
+/// terminando em `CaminhoEncontrado` ou `NenhumCaminho`
+pub fn coletar_eventos(solucionador: &mut SolucionadorAEstrela) -> Vec<EventoVisual> {
+    let mut eventos = Vec::new();
+    loop {
+        let evento = solucionador.proximo_evento();
+        let terminou = matches!(evento, EventoVisual::CaminhoEncontrado { .. } | EventoVisual::NenhumCaminho);
+        eventos.push(evento);
+        if terminou {
+            return eventos;
+        }
+    }
+}
+
+/// Escreve um valor como JSON formatado, seguido de quebra de linha
+pub fn escrever_json<T: Serialize>(valor: &T, mut saida: impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut saida, valor)?;
+    writeln!(saida)
+}
+
+/// Escreve um item por linha (JSON Lines), o formato usado para a sequência de eventos da busca
+pub fn escrever_jsonl<'a, T: Serialize + 'a>(itens: impl IntoIterator<Item = &'a T>, mut saida: impl Write) -> io::Result<()> {
+    for item in itens {
+        serde_json::to_writer(&mut saida, item)?;
+        writeln!(saida)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+/// Item guardado na fila: a chave identifica o estado (no A*, estação e linha de chegada)
+/// e a prioridade ordena a fila, menor primeiro
+pub trait ItemFila {
+    type Chave: Hash + Eq + Clone + Debug;
+
+    fn chave(&self) -> Self::Chave;
+    fn prioridade(&self) -> f32;
+}
+
+/// O que `inserir_ou_diminuir` fez com o item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultadoInsercao {
+    Inserido,
+    PrioridadeDiminuida,
+    /// Já havia um item com a mesma chave e prioridade menor ou igual
+    Ignorado,
+}
+
+/// Contadores de operações da fila. A comparação é com o `BinaryHeap` sem índice que a fronteira
+/// do A* usava antes, onde cada diminuição de chave era uma inserção duplicada e, mais tarde, uma
+/// remoção obsoleta: aqui a chave é diminuída no lugar, e `diminuicoes_de_chave` conta as inserções
+/// (e no máximo as remoções obsoletas) que aquele heap teria a mais. O A* não retira estados
+/// obsoletos; `remocoes_obsoletas` só conta os rótulos que a busca de Pareto descarta.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EstatisticasFila {
+    pub insercoes: usize,
+    pub diminuicoes_de_chave: usize,
+    pub remocoes: usize,
+    /// Itens retirados que o chamador descartou (na busca de Pareto, rótulos dominados depois de inseridos)
+    pub remocoes_obsoletas: usize,
+    pub maior_tamanho: usize,
+}
+
+impl fmt::Display for EstatisticasFila {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} inserções, {} diminuições de chave, {} remoções", self.insercoes, self.diminuicoes_de_chave, self.remocoes)?;
+        if self.remocoes_obsoletas > 0 {
+            write!(f, " ({} obsoletas)", self.remocoes_obsoletas)?;
+        }
+        write!(f, ", maior tamanho {}", self.maior_tamanho)
+    }
+}
+
+/// Heap binário de mínimo com um índice chave -> posição, que permite consultar
+/// e diminuir a prioridade de um item em O(log n) sem deixar duplicatas na fila
+#[derive(Debug, Clone)]
+pub struct FilaPrioridadeIndexada<T: ItemFila> {
+    heap: Vec<T>,
+    posicoes: HashMap<T::Chave, usize>,
+    estatisticas: EstatisticasFila,
+}
+
+impl<T: ItemFila> Default for FilaPrioridadeIndexada<T> {
+    fn default() -> Self {
+        Self { heap: Vec::new(), posicoes: HashMap::new(), estatisticas: EstatisticasFila::default() }
+    }
+}
+
+impl<T: ItemFila> FilaPrioridadeIndexada<T> {
+    pub fn nova() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn estatisticas(&self) -> EstatisticasFila {
+        self.estatisticas
+    }
+
+    /// Item que sairia primeiro, sem retirá-lo
+    pub fn minimo(&self) -> Option<&T> {
+        self.heap.first()
+    }
+
+    /// Item na fila com a chave dada, se houver
+    pub fn obter(&self, chave: &T::Chave) -> Option<&T> {
+        self.posicoes.get(chave).map(|&posicao| &self.heap[posicao])
+    }
+
+    /// Itens em ordem de armazenamento (não de prioridade)
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// Itens na ordem em que seriam retirados
+    pub fn ordenados(&self) -> Vec<&T> {
+        let mut itens: Vec<&T> = self.heap.iter().collect();
+        itens.sort_by(|a, b| a.prioridade().total_cmp(&b.prioridade()));
+        itens
+    }
+
+    /// Insere o item ou, se já houver um com a mesma chave e prioridade maior, substitui-o
+    pub fn inserir_ou_diminuir(&mut self, item: T) -> ResultadoInsercao {
+        let chave = item.chave();
+        let resultado = match self.posicoes.get(&chave) {
+            Some(&posicao) if item.prioridade() < self.heap[posicao].prioridade() => {
+                self.heap[posicao] = item;
+                self.subir(posicao);
+                self.estatisticas.diminuicoes_de_chave += 1;
+                ResultadoInsercao::PrioridadeDiminuida
+            }
+            Some(_) => ResultadoInsercao::Ignorado,
+            None => {
+                self.heap.push(item);
+                self.posicoes.insert(chave, self.heap.len() - 1);
+                self.subir(self.heap.len() - 1);
+                self.estatisticas.insercoes += 1;
+                ResultadoInsercao::Inserido
+            }
+        };
+        self.estatisticas.maior_tamanho = self.estatisticas.maior_tamanho.max(self.heap.len());
+        resultado
+    }
+
+    /// Retira o item de menor prioridade
+    pub fn remover_minimo(&mut self) -> Option<T> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let ultima = self.heap.len() - 1;
+        self.trocar(0, ultima);
+        let item = self.heap.pop()?;
+        self.posicoes.remove(&item.chave());
+        if !self.heap.is_empty() {
+            self.descer(0);
+        }
+        self.estatisticas.remocoes += 1;
+        Some(item)
+    }
+
+    /// Conta um item retirado que não precisou ser processado
+    pub fn registrar_remocao_obsoleta(&mut self) {
+        self.estatisticas.remocoes_obsoletas += 1;
+    }
+
+    /// Esvazia a fila e zera os contadores
+    pub fn limpar(&mut self) {
+        *self = Self::default();
+    }
+
+    fn trocar(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.posicoes.insert(self.heap[a].chave(), a);
+        self.posicoes.insert(self.heap[b].chave(), b);
+    }
+
+    fn subir(&mut self, mut posicao: usize) {
+        while posicao > 0 {
+            let pai = (posicao - 1) / 2;
+            if self.heap[posicao].prioridade() >= self.heap[pai].prioridade() {
+                break;
+            }
+            self.trocar(posicao, pai);
+            posicao = pai;
+        }
+    }
+
+    fn descer(&mut self, mut posicao: usize) {
+        loop {
+            let (esquerda, direita) = (2 * posicao + 1, 2 * posicao + 2);
+            let mut menor = posicao;
+            if esquerda < self.heap.len() && self.heap[esquerda].prioridade() < self.heap[menor].prioridade() {
+                menor = esquerda;
+            }
+            if direita < self.heap.len() && self.heap[direita].prioridade() < self.heap[menor].prioridade() {
+                menor = direita;
+            }
+            if menor == posicao {
+                break;
+            }
+            self.trocar(posicao, menor);
+            posicao = menor;
+        }
+    }
+}
+
+/// Serializa os itens na ordem em que seriam retirados
+impl<T: ItemFila + Serialize> Serialize for FilaPrioridadeIndexada<T> {
+    fn serialize<S: Serializer>(&self, serializador: S) -> Result<S::Ok, S::Error> {
+        serializador.collect_seq(self.ordenados())
+    }
+}
+
+#[cfg(test)]
+mod testes {
+    use super::*;
+    use std::collections::{BinaryHeap, HashSet};
+    use std::cmp::Reverse;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Item(char, f32);
+
+    impl ItemFila for Item {
+        type Chave = char;
+
+        fn chave(&self) -> char {
+            self.0
+        }
+
+        fn prioridade(&self) -> f32 {
+            self.1
+        }
+    }
+
+    fn verificar_indice(fila: &FilaPrioridadeIndexada<Item>) {
+        assert_eq!(fila.posicoes.len(), fila.heap.len());
+        for (posicao, item) in fila.heap.iter().enumerate() {
+            assert_eq!(fila.posicoes[&item.0], posicao, "{:?}", item);
+            if posicao > 0 {
+                assert!(fila.heap[(posicao - 1) / 2].1 <= item.1, "{:?}", fila.heap);
+            }
+        }
+    }
+
+    fn fila_com(itens: &[(char, f32)]) -> FilaPrioridadeIndexada<Item> {
+        let mut fila = FilaPrioridadeIndexada::nova();
+        for &(chave, prioridade) in itens {
+            fila.inserir_ou_diminuir(Item(chave, prioridade));
+        }
+        fila
+    }
+
+    #[test]
+    fn inserir_diminuir_e_ignorar() {
+        let mut fila = fila_com(&[('a', 3.0), ('b', 5.0), ('c', 4.0)]);
+        assert_eq!(fila.inserir_ou_diminuir(Item('d', 6.0)), ResultadoInsercao::Inserido);
+        assert_eq!(fila.minimo(), Some(&Item('a', 3.0)));
+
+        // 'd' estava numa folha e sobe até a raiz
+        assert_eq!(fila.inserir_ou_diminuir(Item('d', 1.0)), ResultadoInsercao::PrioridadeDiminuida);
+        assert_eq!(fila.minimo(), Some(&Item('d', 1.0)));
+        verificar_indice(&fila);
+
+        // Prioridade maior ou igual à que já está na fila não muda nada
+        assert_eq!(fila.inserir_ou_diminuir(Item('d', 2.0)), ResultadoInsercao::Ignorado);
+        assert_eq!(fila.inserir_ou_diminuir(Item('b', 5.0)), ResultadoInsercao::Ignorado);
+        assert_eq!(fila.obter(&'d'), Some(&Item('d', 1.0)));
+        assert_eq!(fila.len(), 4);
+        verificar_indice(&fila);
+
+        let estatisticas = fila.estatisticas();
+        assert_eq!((estatisticas.insercoes, estatisticas.diminuicoes_de_chave, estatisticas.maior_tamanho), (4, 1, 4));
+    }
+
+    #[test]
+    fn indice_acompanha_as_remocoes() {
+        let mut fila = fila_com(&[('a', 7.0), ('b', 2.0), ('c', 9.0), ('d', 4.0), ('e', 1.0), ('f', 8.0), ('g', 3.0)]);
+        fila.inserir_ou_diminuir(Item('c', 0.5));
+        verificar_indice(&fila);
+
+        let mut retirados = Vec::new();
+        while let Some(item) = fila.remover_minimo() {
+            assert_eq!(fila.obter(&item.0), None);
+            verificar_indice(&fila);
+            retirados.push(item.0);
+        }
+        assert_eq!(retirados, ['c', 'e', 'b', 'g', 'd', 'a', 'f']);
+        assert_eq!(fila.remover_minimo(), None);
+        assert_eq!(fila.estatisticas().remocoes, 7);
+    }
+
+    #[test]
+    fn limpar_esvazia_fila_e_contadores() {
+        let mut fila = fila_com(&[('a', 1.0), ('b', 2.0)]);
+        fila.remover_minimo();
+        fila.registrar_remocao_obsoleta();
+
+        fila.limpar();
+        assert!(fila.is_empty() && fila.posicoes.is_empty());
+        assert_eq!(fila.estatisticas(), EstatisticasFila::default());
+        assert_eq!(fila.inserir_ou_diminuir(Item('a', 5.0)), ResultadoInsercao::Inserido);
+    }
+
+    /// A mesma sequência num `BinaryHeap` sem índice, como o A* fazia: cada melhoria vira uma
+    /// entrada duplicada que depois sai da fila e é descartada
+    #[test]
+    fn sem_indice_diminuicoes_viram_remocoes_obsoletas() {
+        let operacoes = [('a', 5.0), ('b', 6.0), ('c', 7.0), ('b', 4.0), ('c', 3.0), ('c', 2.0), ('a', 9.0)];
+
+        let mut fila = FilaPrioridadeIndexada::nova();
+        let mut heap = BinaryHeap::new();
+        let mut melhores: HashMap<char, f32> = HashMap::new();
+        for (chave, prioridade) in operacoes {
+            fila.inserir_ou_diminuir(Item(chave, prioridade));
+            if melhores.get(&chave).is_none_or(|&melhor| prioridade < melhor) {
+                melhores.insert(chave, prioridade);
+                heap.push(Reverse(((prioridade * 10.0) as u32, chave)));
+            }
+        }
+
+        let mut retirados_do_heap = Vec::new();
+        let mut processados = HashSet::new();
+        let mut obsoletas = 0;
+        while let Some(Reverse((_, chave))) = heap.pop() {
+            if processados.insert(chave) {
+                retirados_do_heap.push(chave);
+            } else {
+                obsoletas += 1;
+            }
+        }
+        let retirados_da_fila: Vec<char> = std::iter::from_fn(|| fila.remover_minimo()).map(|item| item.0).collect();
+
+        assert_eq!(retirados_da_fila, retirados_do_heap);
+        assert_eq!(obsoletas, 3);
+        let estatisticas = fila.estatisticas();
+        assert_eq!((estatisticas.remocoes, estatisticas.remocoes_obsoletas, estatisticas.maior_tamanho), (3, 0, 3));
+    }
+}
@@ -1,84 +1,305 @@
 use std::collections::HashMap;
+use serde::Serialize;
 
-pub const NUMERO_ESTACOES: usize = 14;
-pub const VELOCIDADE_TREM_KMH: f32 = 30.0 / 2.0; // Ajustado para corresponder ao tempo esperado (em C++ tempo = distância * 2)
-pub const TEMPO_BALDEACAO_MINUTOS: f32 = 4.0; // Esta constante será usada no algoritmo_a_estrela.rs
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum CorLinha {
-    Azul = 1,
-    Amarela = 2,
-    Vermelha = 3,
-    Verde = 4,
-    Nenhuma = 0,
+/// Velocidade das linhas que não definem a própria (2 min por km, como nas tabelas originais)
+pub const VELOCIDADE_TREM_KMH: f32 = 30.0;
+/// Penalidade padrão por troca de linha; cada grafo pode definir a sua (e cada estação sobrescrevê-la)
+pub const TEMPO_BALDEACAO_MINUTOS: f32 = 4.0;
+
+/// Cor usada para linhas cujos dados não definem uma
+pub const COR_LINHA_PADRAO: [u8; 3] = [128, 128, 128];
+
+pub type IdEstacao = usize;
+pub type IdLinha = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ModoTransporte {
+    #[default]
+    Metro,
+    Rer,
+    Tram,
+    Onibus,
+    APe, // Transferências a pé entre estações próximas
 }
 
-impl CorLinha {
-    pub fn de_inteiro(valor: u8) -> Self {
-        match valor {
-            1 => CorLinha::Azul,
-            2 => CorLinha::Amarela,
-            3 => CorLinha::Vermelha,
-            4 => CorLinha::Verde,
-            _ => CorLinha::Nenhuma,
+impl ModoTransporte {
+    /// Interpreta o modo escrito nos arquivos de dados ("metro", "RER", "tram", "onibus"...)
+    pub fn de_texto(texto: &str) -> Option<Self> {
+        match texto.trim().to_lowercase().as_str() {
+            "metro" | "metrô" | "metropolitano" => Some(ModoTransporte::Metro),
+            "rer" | "trem" => Some(ModoTransporte::Rer),
+            "tram" | "bonde" | "vlt" => Some(ModoTransporte::Tram),
+            "onibus" | "ônibus" | "bus" => Some(ModoTransporte::Onibus),
+            "a_pe" | "a pé" | "a pe" | "caminhada" => Some(ModoTransporte::APe),
+            _ => None,
+        }
+    }
+
+    /// Código usado nos arquivos de dados; aceito de volta por `de_texto`
+    pub fn codigo(self) -> &'static str {
+        match self {
+            ModoTransporte::Metro => "metro",
+            ModoTransporte::Rer => "rer",
+            ModoTransporte::Tram => "tram",
+            ModoTransporte::Onibus => "onibus",
+            ModoTransporte::APe => "a_pe",
+        }
+    }
+
+    pub fn nome(self) -> &'static str {
+        match self {
+            ModoTransporte::Metro => "Metrô",
+            ModoTransporte::Rer => "RER",
+            ModoTransporte::Tram => "Tram",
+            ModoTransporte::Onibus => "Ônibus",
+            ModoTransporte::APe => "A pé",
         }
     }
 }
 
-pub type IdEstacao = usize;
+/// Linha de transporte; as conexões do grafo referenciam linhas pelo índice em `GrafoMetro::linhas`
+#[derive(Debug, Clone)]
+pub struct Linha {
+    pub id: IdLinha,
+    pub codigo: String, // Identificador nos dados de origem ("1" nas matrizes CSV, route_id no GTFS)
+    pub nome: String,
+    pub cor_rgb: [u8; 3],
+    pub modo: ModoTransporte,
+    pub velocidade_kmh: Option<f32>, // Usada para calcular o tempo das conexões a partir da distância
+}
+
+/// Posição geográfica ou planar de uma estação
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordenadas {
+    /// Latitude e longitude em graus decimais (WGS84)
+    Geograficas { latitude: f64, longitude: f64 },
+    /// Posição em um plano cartesiano, em quilômetros
+    Planas { x_km: f32, y_km: f32 },
+}
 
 #[derive(Debug, Clone)]
 pub struct Estacao {
     pub id: IdEstacao, // Este campo será lido/usado ao exibir informações ou na lógica da GUI
-    pub nome: String,
+    pub codigo: String, // Código curto usado nas tabelas CSV (ex.: "E7")
+    pub nome: String,   // Nome de exibição; igual ao código enquanto não houver arquivo de estações
+    pub coordenadas: Option<Coordenadas>,
+    pub zona: Option<u8>,
+    pub acessivel: bool,
+    pub tempo_baldeacao_minutos: Option<f32>, // Sobrescreve a penalidade do grafo nesta estação
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Conexao {
     pub para_estacao: IdEstacao,
-    pub cor_linha: CorLinha,
+    pub linha: IdLinha,
     pub distancia_km: f32, // Este campo pode ser usado para exibir informações detalhadas do trajeto
     pub tempo_minutos: f32,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct GrafoMetro {
     pub estacoes: Vec<Estacao>,
     pub lista_adjacencia: Vec<Vec<Conexao>>,
-    pub distancias_heuristicas_km: Vec<Vec<Option<f32>>>,
-    // Este campo nome_para_id será usado se implementarmos seleção de estação por nome na GUI
-    // ou em outras funcionalidades de busca por nome.
+    // Tabela N×N de distâncias em linha reta, só quando os dados trazem uma (tabela1 das matrizes CSV);
+    // sem ela a heurística é calculada das coordenadas das estações
+    pub distancias_heuristicas_km: Option<Vec<Vec<Option<f32>>>>,
+    // Índice de busca por código ou nome (normalizados com normalizar_nome_estacao)
     pub nome_para_id: HashMap<String, IdEstacao>,
+    pub linhas: Vec<Linha>,
+    pub tempo_baldeacao_minutos: f32,
+}
+
+impl Default for GrafoMetro {
+    fn default() -> Self {
+        Self {
+            estacoes: Vec::new(),
+            lista_adjacencia: Vec::new(),
+            distancias_heuristicas_km: None,
+            nome_para_id: HashMap::new(),
+            linhas: Vec::new(),
+            tempo_baldeacao_minutos: TEMPO_BALDEACAO_MINUTOS,
+        }
+    }
 }
 
 impl GrafoMetro {
+    /// Cria um grafo vazio; as estações são criadas pelos carregadores a partir dos dados lidos
     pub fn novo() -> Self {
-        let mut estacoes_vec = Vec::with_capacity(NUMERO_ESTACOES);
-        let mut nome_para_id_map = HashMap::new();
-
-        for i in 0..NUMERO_ESTACOES {
-            let nome_estacao = format!("E{}", i + 1);
-            estacoes_vec.push(Estacao {
-                id: i,
-                nome: nome_estacao.clone(),
-            });
-            nome_para_id_map.insert(nome_estacao, i);
+        Self::default()
+    }
+
+    pub fn numero_estacoes(&self) -> usize {
+        self.estacoes.len()
+    }
+
+    /// Adiciona uma estação ao grafo (ou devolve a existente com o mesmo código)
+    /// e redimensiona a lista de adjacência e, se existir, a matriz heurística
+    pub fn adicionar_estacao(&mut self, codigo: &str) -> IdEstacao {
+        if let Some(id_existente) = self.obter_id_estacao(codigo) {
+            return id_existente;
         }
 
-        Self {
-            estacoes: estacoes_vec,
-            lista_adjacencia: vec![Vec::new(); NUMERO_ESTACOES],
-            distancias_heuristicas_km: vec![vec![None; NUMERO_ESTACOES]; NUMERO_ESTACOES],
-            nome_para_id: nome_para_id_map,
+        let id = self.estacoes.len();
+        self.estacoes.push(Estacao {
+            id,
+            codigo: codigo.to_string(),
+            nome: codigo.to_string(),
+            coordenadas: None,
+            zona: None,
+            acessivel: false,
+            tempo_baldeacao_minutos: None,
+        });
+        self.nome_para_id.insert(normalizar_nome_estacao(codigo), id);
+        self.lista_adjacencia.push(Vec::new());
+
+        if let Some(matriz) = &mut self.distancias_heuristicas_km {
+            for linha_matriz in matriz.iter_mut() {
+                linha_matriz.push(None);
+            }
+            matriz.push(vec![None; id + 1]);
+        }
+
+        id
+    }
+
+    /// Define o nome de exibição de uma estação, mantendo o código como apelido de busca
+    pub fn renomear_estacao(&mut self, id: IdEstacao, nome: &str) {
+        let estacao = &mut self.estacoes[id];
+        if estacao.nome != estacao.codigo {
+            self.nome_para_id.remove(&normalizar_nome_estacao(&estacao.nome));
         }
+        estacao.nome = nome.to_string();
+        self.nome_para_id.insert(normalizar_nome_estacao(nome), id);
     }
 
+    /// Procura uma estação pelo código ("E7") ou pelo nome, sem diferenciar maiúsculas
     pub fn obter_id_estacao(&self, nome: &str) -> Option<IdEstacao> {
-        self.nome_para_id.get(nome).copied()
+        self.nome_para_id.get(&normalizar_nome_estacao(nome)).copied()
     }
 
+    /// Penalidade de troca de linha em uma estação
+    pub fn tempo_baldeacao_em(&self, id_estacao: IdEstacao) -> f32 {
+        self.estacoes.get(id_estacao)
+            .and_then(|estacao| estacao.tempo_baldeacao_minutos)
+            .unwrap_or(self.tempo_baldeacao_minutos)
+    }
+
+    /// Adjacência invertida: em cada estação, as conexões que chegam nela, com `para_estacao`
+    /// apontando para a estação de onde vêm (linha, distância e tempo são os da conexão original)
+    pub fn lista_adjacencia_reversa(&self) -> Vec<Vec<Conexao>> {
+        let mut reversa = vec![Vec::new(); self.lista_adjacencia.len()];
+        for (id_origem, conexoes) in self.lista_adjacencia.iter().enumerate() {
+            for conexao in conexoes {
+                reversa[conexao.para_estacao].push(Conexao { para_estacao: id_origem, ..conexao.clone() });
+            }
+        }
+        reversa
+    }
+
+    /// Registra uma linha (ou devolve a existente com o mesmo código)
+    pub fn adicionar_linha(&mut self, codigo: &str, nome: &str, cor_rgb: [u8; 3]) -> IdLinha {
+        if let Some(linha) = self.linhas.iter().find(|linha| linha.codigo == codigo) {
+            return linha.id;
+        }
+
+        let id = self.linhas.len();
+        self.linhas.push(Linha {
+            id,
+            codigo: codigo.to_string(),
+            nome: nome.to_string(),
+            cor_rgb,
+            modo: ModoTransporte::default(),
+            velocidade_kmh: None,
+        });
+        id
+    }
+
+    /// Procura uma linha pelo código ou pelo nome, sem diferenciar maiúsculas
+    pub fn obter_id_linha(&self, codigo_ou_nome: &str) -> Option<IdLinha> {
+        let procurado = normalizar_nome_estacao(codigo_ou_nome);
+        self.linhas.iter()
+            .find(|linha| normalizar_nome_estacao(&linha.codigo) == procurado)
+            .or_else(|| self.linhas.iter().find(|linha| normalizar_nome_estacao(&linha.nome) == procurado))
+            .map(|linha| linha.id)
+    }
+
+    /// Nome de exibição de uma linha ("?" se o id não existir)
+    pub fn nome_linha(&self, id_linha: IdLinha) -> &str {
+        self.linhas.get(id_linha).map_or("?", |linha| linha.nome.as_str())
+    }
+
+    /// Velocidade de uma linha, ou `VELOCIDADE_TREM_KMH` se ela não define a própria
+    pub fn velocidade_linha_kmh(&self, id_linha: IdLinha) -> f32 {
+        self.linhas.get(id_linha)
+            .and_then(|linha| linha.velocidade_kmh)
+            .unwrap_or(VELOCIDADE_TREM_KMH)
+    }
+
+    /// Maior velocidade entre as linhas do grafo; a heurística divide por ela para nunca superestimar
+    pub fn velocidade_maxima_kmh(&self) -> f32 {
+        self.linhas.iter()
+            .map(|linha| self.velocidade_linha_kmh(linha.id))
+            .fold(VELOCIDADE_TREM_KMH, f32::max)
+    }
+
+    /// Tempo para percorrer uma distância na velocidade da linha
+    pub fn tempo_viagem_minutos(&self, id_linha: IdLinha, distancia_km: f32) -> f32 {
+        distancia_km / self.velocidade_linha_kmh(id_linha) * 60.0
+    }
+
+    /// Grava uma distância na tabela heurística, criando a tabela na primeira vez
+    pub fn definir_distancia_heuristica(&mut self, de_estacao: IdEstacao, para_estacao: IdEstacao, distancia_km: f32) {
+        let numero_estacoes = self.numero_estacoes();
+        let matriz = self.distancias_heuristicas_km
+            .get_or_insert_with(|| vec![vec![None; numero_estacoes]; numero_estacoes]);
+        matriz[de_estacao][para_estacao] = Some(distancia_km);
+    }
+
+    /// Valor da tabela heurística, se a rede tiver uma
+    pub fn distancia_heuristica_tabela_km(&self, de_estacao: IdEstacao, para_estacao: IdEstacao) -> Option<f32> {
+        *self.distancias_heuristicas_km.as_ref()?.get(de_estacao)?.get(para_estacao)?
+    }
+
+    /// Distância em linha reta entre duas estações: da tabela heurística quando a rede tem uma,
+    /// senão calculada das coordenadas (`None` se faltarem coordenadas compatíveis)
+    pub fn distancia_direta_km(&self, de_estacao: IdEstacao, para_estacao: IdEstacao) -> Option<f32> {
+        if self.distancias_heuristicas_km.is_some() {
+            return self.distancia_heuristica_tabela_km(de_estacao, para_estacao);
+        }
+        distancia_entre_coordenadas_km(
+            self.estacoes.get(de_estacao)?.coordenadas?,
+            self.estacoes.get(para_estacao)?.coordenadas?,
+        )
+    }
+
+    /// Tempo mínimo para cobrir a distância em linha reta na velocidade da linha mais rápida.
+    /// Nunca superestima o tempo real desde que nenhuma conexão seja mais rápida que essa velocidade.
     pub fn obter_tempo_heuristico_minutos(&self, de_estacao: IdEstacao, para_estacao: IdEstacao) -> Option<f32> {
-        self.distancias_heuristicas_km[de_estacao][para_estacao]
-            .map(|dist_km| dist_km * 2.0) // Direto para minutos, conforme o código
+        self.distancia_direta_km(de_estacao, para_estacao)
+            .map(|dist_km| dist_km / self.velocidade_maxima_kmh() * 60.0)
     }
-}
\ No newline at end of file
+}
+
+/// Distância em linha reta entre duas posições: haversine para coordenadas geográficas,
+/// euclidiana para planas. `None` se uma for geográfica e a outra planar.
+pub fn distancia_entre_coordenadas_km(a: Coordenadas, b: Coordenadas) -> Option<f32> {
+    const RAIO_TERRA_KM: f64 = 6371.0;
+    match (a, b) {
+        (Coordenadas::Geograficas { latitude: lat_a, longitude: lon_a },
+         Coordenadas::Geograficas { latitude: lat_b, longitude: lon_b }) => {
+            let delta_lat = (lat_b - lat_a).to_radians();
+            let delta_lon = (lon_b - lon_a).to_radians();
+            let h = (delta_lat / 2.0).sin().powi(2)
+                + lat_a.to_radians().cos() * lat_b.to_radians().cos() * (delta_lon / 2.0).sin().powi(2);
+            Some((2.0 * RAIO_TERRA_KM * h.sqrt().asin()) as f32)
+        }
+        (Coordenadas::Planas { x_km: x_a, y_km: y_a }, Coordenadas::Planas { x_km: x_b, y_km: y_b }) => {
+            Some((x_b - x_a).hypot(y_b - y_a))
+        }
+        _ => None,
+    }
+}
+
+pub fn normalizar_nome_estacao(nome: &str) -> String {
+    nome.trim().to_lowercase()
+}
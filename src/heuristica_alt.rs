@@ -0,0 +1,209 @@
+use crate::grafo_metro::*;
+use crate::validacao_rede::{tempos_minimos_a_partir, tempos_minimos_ate};
+use std::fmt;
+use std::sync::Arc;
+
+/// Quantidade de marcos usada quando nenhuma é informada
+pub const QUANTIDADE_MARCOS_PADRAO: usize = 4;
+
+/// Como escolher as estações-marco da heurística ALT
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelecaoMarcos {
+    /// Cada novo marco é a estação mais distante (em tempo) dos marcos já escolhidos;
+    /// marcos na periferia da rede dão limites melhores
+    MaisDistantes { quantidade: usize },
+    /// Sorteio reprodutível: a mesma semente escolhe sempre as mesmas estações
+    Aleatorios { quantidade: usize, semente: u64 },
+    /// Estações escolhidas pelo usuário
+    Manuais(Vec<IdEstacao>),
+}
+
+impl Default for SelecaoMarcos {
+    fn default() -> Self {
+        SelecaoMarcos::MaisDistantes { quantidade: QUANTIDADE_MARCOS_PADRAO }
+    }
+}
+
+impl SelecaoMarcos {
+    /// Interpreta a seleção da linha de comando: "mais-distantes[:N]", "aleatorios[:N[:semente]]"
+    /// ou uma lista de códigos ou nomes de estação separados por vírgula ("E1,E7,E14")
+    pub fn de_texto(grafo: &GrafoMetro, texto: &str) -> Result<Self, String> {
+        let mut partes = texto.trim().split(':');
+        let tipo = partes.next().unwrap_or_default().to_lowercase();
+        let numero = |parte: Option<&str>, padrao: u64| -> Result<u64, String> {
+            parte.map_or(Ok(padrao), |valor| valor.trim().parse().map_err(|_| format!("número inválido em --marcos: '{}'", valor)))
+        };
+
+        match tipo.as_str() {
+            "mais-distantes" | "distantes" | "farthest" => {
+                let quantidade = numero(partes.next(), QUANTIDADE_MARCOS_PADRAO as u64)? as usize;
+                Ok(SelecaoMarcos::MaisDistantes { quantidade })
+            }
+            "aleatorios" | "aleatórios" | "random" => {
+                let quantidade = numero(partes.next(), QUANTIDADE_MARCOS_PADRAO as u64)? as usize;
+                let semente = numero(partes.next(), 1)?;
+                Ok(SelecaoMarcos::Aleatorios { quantidade, semente })
+            }
+            _ => texto.split(',')
+                .map(|nome| {
+                    let nome = nome.trim();
+                    grafo.obter_id_estacao(nome).ok_or_else(|| format!("estação-marco desconhecida: '{}'", nome))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(SelecaoMarcos::Manuais),
+        }
+    }
+
+    /// Estações escolhidas, sem repetição e na ordem de escolha
+    pub fn escolher(&self, grafo: &GrafoMetro) -> Vec<IdEstacao> {
+        let numero_estacoes = grafo.numero_estacoes();
+        match self {
+            SelecaoMarcos::MaisDistantes { quantidade } => marcos_mais_distantes(grafo, (*quantidade).min(numero_estacoes)),
+            SelecaoMarcos::Aleatorios { quantidade, semente } => {
+                let mut candidatas: Vec<IdEstacao> = (0..numero_estacoes).collect();
+                let mut gerador = GeradorXorShift::novo(*semente);
+                // Fisher-Yates parcial: só as primeiras posições interessam
+                let quantidade = (*quantidade).min(numero_estacoes);
+                for posicao in 0..quantidade {
+                    let sorteada = posicao + (gerador.proximo() % (numero_estacoes - posicao) as u64) as usize;
+                    candidatas.swap(posicao, sorteada);
+                }
+                candidatas.truncate(quantidade);
+                candidatas
+            }
+            SelecaoMarcos::Manuais(estacoes) => {
+                let mut marcos = Vec::new();
+                for &id in estacoes {
+                    if id < numero_estacoes && !marcos.contains(&id) {
+                        marcos.push(id);
+                    }
+                }
+                marcos
+            }
+        }
+    }
+}
+
+impl fmt::Display for SelecaoMarcos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelecaoMarcos::MaisDistantes { quantidade } => write!(f, "{} mais distantes", quantidade),
+            SelecaoMarcos::Aleatorios { quantidade, semente } => write!(f, "{} aleatórios (semente {})", quantidade, semente),
+            SelecaoMarcos::Manuais(estacoes) => write!(f, "{} escolhidos", estacoes.len()),
+        }
+    }
+}
+
+/// Seleção gulosa "farthest": começa pela estação mais distante da primeira e depois sempre
+/// pega a que está mais longe do marco mais próximo. Estações inalcançáveis contam como infinitas,
+/// para que cada parte desconexa da rede ganhe um marco.
+fn marcos_mais_distantes(grafo: &GrafoMetro, quantidade: usize) -> Vec<IdEstacao> {
+    if quantidade == 0 {
+        return Vec::new();
+    }
+    let distancia = |tempo: Option<f32>| tempo.unwrap_or(f32::INFINITY);
+    let mais_distante = |distancias: &[f32], marcos: &[IdEstacao]| {
+        (0..distancias.len())
+            .filter(|id| !marcos.contains(id))
+            .max_by(|&a, &b| distancias[a].total_cmp(&distancias[b]).then(b.cmp(&a)))
+    };
+
+    let mut menor_distancia: Vec<f32> = tempos_minimos_a_partir(grafo, 0, false).into_iter().map(distancia).collect();
+    let mut marcos = Vec::new();
+    while marcos.len() < quantidade && let Some(proximo) = mais_distante(&menor_distancia, &marcos) {
+        marcos.push(proximo);
+        for (atual, tempo) in menor_distancia.iter_mut().zip(tempos_minimos_a_partir(grafo, proximo, false)) {
+            *atual = if marcos.len() == 1 { distancia(tempo) } else { atual.min(distancia(tempo)) };
+        }
+    }
+    marcos
+}
+
+/// Gerador pseudoaleatório mínimo (xorshift64*), suficiente para sortear marcos
+struct GeradorXorShift(u64);
+
+impl GeradorXorShift {
+    fn novo(semente: u64) -> Self {
+        // O estado nunca pode ser zero
+        GeradorXorShift(semente ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn proximo(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+/// Heurística ALT (A*, landmarks, triangle inequality): guarda os menores tempos de e para cada
+/// marco L e estima h(v) = max sobre L de d(L, t) − d(L, v) e d(v, L) − d(t, L).
+///
+/// Os tempos ignoram as penalidades de baldeação, que só aumentam o custo real, então a
+/// estimativa é admissível e consistente para qualquer rede e qualquer estação-marco.
+#[derive(Debug, Clone)]
+pub struct HeuristicaAlt {
+    pub marcos: Vec<IdEstacao>,
+    /// [marco][estação]: menor tempo do marco até a estação
+    tempos_do_marco: Vec<Vec<Option<f32>>>,
+    /// [marco][estação]: menor tempo da estação até o marco
+    tempos_ate_marco: Vec<Vec<Option<f32>>>,
+}
+
+impl HeuristicaAlt {
+    /// Escolhe os marcos e pré-calcula as duas tabelas (dois Dijkstra por marco)
+    pub fn calcular(grafo: &GrafoMetro, selecao: &SelecaoMarcos) -> Self {
+        let marcos = selecao.escolher(grafo);
+        let tempos_do_marco = marcos.iter().map(|&marco| tempos_minimos_a_partir(grafo, marco, false)).collect();
+        let tempos_ate_marco = marcos.iter().map(|&marco| tempos_minimos_ate(grafo, marco, false)).collect();
+        HeuristicaAlt { marcos, tempos_do_marco, tempos_ate_marco }
+    }
+
+    /// Limite inferior do tempo de `de_estacao` a `para_estacao` (0 se nenhum marco alcança as duas)
+    pub fn estimar_minutos(&self, de_estacao: IdEstacao, para_estacao: IdEstacao) -> f32 {
+        let mut limite: f32 = 0.0;
+        for (do_marco, ate_marco) in self.tempos_do_marco.iter().zip(&self.tempos_ate_marco) {
+            // d(L, t) <= d(L, v) + d(v, t)
+            if let (Some(marco_ao_objetivo), Some(marco_a_estacao)) = (do_marco[para_estacao], do_marco[de_estacao]) {
+                limite = limite.max(marco_ao_objetivo - marco_a_estacao);
+            }
+            // d(v, L) <= d(v, t) + d(t, L)
+            if let (Some(estacao_ao_marco), Some(objetivo_ao_marco)) = (ate_marco[de_estacao], ate_marco[para_estacao]) {
+                limite = limite.max(estacao_ao_marco - objetivo_ao_marco);
+            }
+        }
+        limite
+    }
+
+    /// Códigos das estações-marco, para mensagens
+    pub fn descrever_marcos(&self, grafo: &GrafoMetro) -> String {
+        self.marcos.iter().map(|&id| grafo.estacoes[id].codigo.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// De onde o A* tira a estimativa h
+#[derive(Debug, Clone, Default)]
+pub enum FonteHeuristica {
+    /// Distância em linha reta (tabela heurística ou coordenadas) na velocidade máxima
+    #[default]
+    LinhaReta,
+    /// Limites pela desigualdade triangular em relação às estações-marco
+    Alt(Arc<HeuristicaAlt>),
+}
+
+impl FonteHeuristica {
+    /// Estimativa de tempo entre duas estações; 0 quando a fonte não consegue calculá-la
+    pub fn estimar_minutos(&self, grafo: &GrafoMetro, de_estacao: IdEstacao, para_estacao: IdEstacao) -> f32 {
+        match self {
+            FonteHeuristica::LinhaReta => grafo.obter_tempo_heuristico_minutos(de_estacao, para_estacao).unwrap_or(0.0),
+            FonteHeuristica::Alt(alt) => alt.estimar_minutos(de_estacao, para_estacao),
+        }
+    }
+
+    pub fn nome(&self) -> &'static str {
+        match self {
+            FonteHeuristica::LinhaReta => "linha reta",
+            FonteHeuristica::Alt(_) => "ALT",
+        }
+    }
+}
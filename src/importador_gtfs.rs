@@ -0,0 +1,505 @@
+use crate::grafo_metro::*;
+use crate::dados_metro::parsear_cor_hex;
+use crate::erro_carregamento::{AvisoCarregamento, ColetorAvisos, ErroCarregamento, LocalDados, ModoCarregamento};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use csv::{ReaderBuilder, StringRecord};
+
+/// Código da linha fictícia usada para as transferências a pé entre estações diferentes (transfers.txt)
+pub const CODIGO_LINHA_A_PE: &str = "a_pe";
+
+/// Tempo mínimo de um trecho. Muitos feeds arredondam os horários ao minuto, e paradas próximas
+/// aparecem com tempo zero; um trecho instantâneo tornaria inadmissível qualquer heurística por distância.
+const TEMPO_MINIMO_TRECHO_MINUTOS: f32 = 0.5;
+
+/// Origem dos arquivos de um feed GTFS estático: um diretório ou um arquivo .zip
+enum FonteGtfs {
+    Diretorio(PathBuf),
+    Zip(zip::ZipArchive<File>),
+}
+
+impl FonteGtfs {
+    fn abrir(caminho: &str) -> Result<Self, ErroCarregamento> {
+        if Path::new(caminho).is_dir() {
+            Ok(FonteGtfs::Diretorio(PathBuf::from(caminho)))
+        } else {
+            let arquivo = File::open(caminho).map_err(ErroCarregamento::io(caminho))?;
+            let arquivo_zip = zip::ZipArchive::new(arquivo).map_err(|e| ErroCarregamento::formato(caminho, e))?;
+            Ok(FonteGtfs::Zip(arquivo_zip))
+        }
+    }
+
+    /// Abre um arquivo do feed pelo nome (ex.: "stops.txt"); `None` se o feed não o contém
+    fn ler_arquivo(&mut self, nome: &str) -> Result<Option<Box<dyn Read + '_>>, ErroCarregamento> {
+        match self {
+            FonteGtfs::Diretorio(diretorio) => {
+                let caminho = diretorio.join(nome);
+                if !caminho.exists() {
+                    return Ok(None);
+                }
+                let arquivo = File::open(&caminho).map_err(ErroCarregamento::io(&caminho.to_string_lossy()))?;
+                Ok(Some(Box::new(arquivo)))
+            }
+            FonteGtfs::Zip(arquivo_zip) => {
+                // Alguns feeds guardam os arquivos dentro de uma pasta no zip
+                let sufixo = format!("/{}", nome);
+                let Some(indice) = (0..arquivo_zip.len()).find(|&i| {
+                    arquivo_zip.name_for_index(i)
+                        .is_some_and(|nome_entrada| nome_entrada == nome || nome_entrada.ends_with(&sufixo))
+                }) else {
+                    return Ok(None);
+                };
+                let entrada = arquivo_zip.by_index(indice).map_err(|e| ErroCarregamento::formato(nome, e))?;
+                Ok(Some(Box::new(entrada)))
+            }
+        }
+    }
+}
+
+/// Tabela CSV de um arquivo GTFS, com acesso às colunas pelo nome
+struct TabelaGtfs {
+    nome_arquivo: String,
+    colunas: HashMap<String, usize>,
+    registros: Vec<StringRecord>,
+}
+
+impl TabelaGtfs {
+    fn ler(leitor: impl Read, nome_arquivo: &str) -> Result<Self, ErroCarregamento> {
+        let mut leitor_csv = ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .from_reader(leitor);
+
+        // O cabeçalho pode vir com BOM UTF-8 na primeira coluna
+        let colunas = leitor_csv.headers().map_err(ErroCarregamento::csv(nome_arquivo))?
+            .iter()
+            .enumerate()
+            .map(|(indice, nome)| (nome.trim().trim_start_matches('\u{feff}').to_string(), indice))
+            .collect();
+        let registros = leitor_csv.records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ErroCarregamento::csv(nome_arquivo))?;
+
+        Ok(TabelaGtfs { nome_arquivo: nome_arquivo.to_string(), colunas, registros })
+    }
+
+    fn coluna_obrigatoria(&self, nome: &str) -> Result<usize, ErroCarregamento> {
+        self.colunas.get(nome).copied()
+            .ok_or_else(|| ErroCarregamento::coluna_ausente(&self.nome_arquivo, nome))
+    }
+
+    fn coluna(&self, nome: &str) -> Option<usize> {
+        self.colunas.get(nome).copied()
+    }
+
+    /// Posição de uma célula para os avisos
+    fn local(&self, registro: &StringRecord, coluna: &str) -> LocalDados {
+        LocalDados::celula(&self.nome_arquivo, registro.position().map(|p| p.line()), coluna)
+    }
+
+    fn valor_invalido(&self, registro: &StringRecord, coluna: &str, valor: &str, esperado: &'static str) -> AvisoCarregamento {
+        AvisoCarregamento::ValorInvalido { local: self.local(registro, coluna), valor: valor.to_string(), esperado }
+    }
+}
+
+/// Valor de uma célula sem espaços; `None` se a coluna não existe ou a célula está vazia
+fn celula(registro: &StringRecord, coluna: Option<usize>) -> Option<&str> {
+    coluna.and_then(|c| registro.get(c)).map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Converte um horário GTFS ("HH:MM:SS", com horas podendo passar de 24) em segundos
+fn parsear_horario_gtfs(valor: &str) -> Option<u32> {
+    let mut partes = valor.trim().split(':').map(|parte| parte.parse::<u32>().ok());
+    let (Some(Some(horas)), Some(Some(minutos)), Some(Some(segundos)), None) =
+        (partes.next(), partes.next(), partes.next(), partes.next()) else {
+        return None;
+    };
+    Some(horas * 3600 + minutos * 60 + segundos)
+}
+
+/// Modo de transporte correspondente ao route_type do GTFS (tipos básicos e estendidos)
+fn modo_do_route_type(route_type: u16) -> ModoTransporte {
+    match route_type {
+        0 | 900..=999 => ModoTransporte::Tram,
+        2 | 100..=199 => ModoTransporte::Rer,
+        3 | 11 | 700..=899 => ModoTransporte::Onibus,
+        _ => ModoTransporte::Metro,
+    }
+}
+
+/// Soma dos tempos observados de um trecho (estação, estação, linha) em todas as viagens
+#[derive(Default)]
+struct TempoAcumulado {
+    soma_segundos: f64, // Fracionária quando o tempo vem da interpolação entre paradas sem horário
+    viagens: u32,
+}
+
+type TemposPorTrecho = HashMap<(IdEstacao, IdEstacao, IdLinha), TempoAcumulado>;
+
+/// Uma linha de stop_times.txt já resolvida; os horários podem faltar em paradas intermediárias
+struct ParadaDaViagem<'a> {
+    registro: &'a StringRecord,
+    id_viagem: &'a str,
+    sequencia: u32,
+    id_estacao: IdEstacao,
+    chegada: Option<u32>,
+    partida: Option<u32>,
+}
+
+impl GrafoMetro {
+    /// Importa um feed GTFS estático (diretório ou .zip) para o grafo.
+    ///
+    /// - stops.txt: plataformas com `parent_station` são agrupadas na estação-mãe,
+    ///   de modo que a troca de plataforma dentro da estação conta como baldeação;
+    /// - routes.txt: cada rota vira uma `Linha`, com nome e cor da própria rota;
+    /// - trips.txt + stop_times.txt: paradas consecutivas de uma viagem viram uma `Conexao`,
+    ///   com o tempo médio observado entre a partida de uma e a chegada na outra. Paradas sem
+    ///   horário (que não são pontos de controle) dividem o tempo entre as paradas com horário
+    ///   vizinhas, proporcionalmente à distância em linha reta;
+    /// - transfers.txt (opcional): transferências entre estações diferentes viram conexões
+    ///   a pé, usando `min_transfer_time` (ou a penalidade de baldeação do grafo se ausente).
+    ///
+    /// Não há tabela de distâncias heurísticas: a heurística vem das coordenadas das paradas,
+    /// e a velocidade de cada linha é a mais alta observada nos horários, para que nunca superestime.
+    /// Referências a viagens, rotas ou paradas que não existem e valores ilegíveis são ignorados
+    /// com aviso, como nas tabelas CSV (erro no modo estrito).
+    pub fn carregar_gtfs(
+        &mut self,
+        caminho: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<Vec<AvisoCarregamento>, ErroCarregamento> {
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        let mut fonte = FonteGtfs::abrir(caminho)?;
+
+        let mut ler_tabela = |nome: &str| -> Result<Option<TabelaGtfs>, ErroCarregamento> {
+            match fonte.ler_arquivo(nome)? {
+                Some(leitor) => Ok(Some(TabelaGtfs::ler(leitor, nome)?)),
+                None => Ok(None),
+            }
+        };
+        let arquivo_obrigatorio = |nome: &str| ErroCarregamento::ArquivoAusente {
+            arquivo: Path::new(caminho).join(nome).to_string_lossy().into_owned(),
+        };
+
+        let paradas = ler_tabela("stops.txt")?.ok_or_else(|| arquivo_obrigatorio("stops.txt"))?;
+        let rotas = ler_tabela("routes.txt")?.ok_or_else(|| arquivo_obrigatorio("routes.txt"))?;
+        let viagens = ler_tabela("trips.txt")?.ok_or_else(|| arquivo_obrigatorio("trips.txt"))?;
+        let horarios = ler_tabela("stop_times.txt")?.ok_or_else(|| arquivo_obrigatorio("stop_times.txt"))?;
+        let transferencias = ler_tabela("transfers.txt")?;
+
+        let estacao_da_parada = self.importar_paradas_gtfs(&paradas, &mut coletor)?;
+        let linha_da_rota = self.importar_rotas_gtfs(&rotas, &mut coletor)?;
+
+        let col_trip = viagens.coluna_obrigatoria("trip_id")?;
+        let col_rota = viagens.coluna_obrigatoria("route_id")?;
+        let mut linha_da_viagem: HashMap<&str, IdLinha> = HashMap::new();
+        // Viagens já avisadas em trips.txt: os horários delas são ignorados sem repetir o aviso
+        let mut viagens_descartadas: HashSet<&str> = HashSet::new();
+        for registro in &viagens.registros {
+            let Some(id_viagem) = celula(registro, Some(col_trip)) else {
+                coletor.registrar(viagens.valor_invalido(registro, "trip_id", "", "identificador da viagem"))?;
+                continue;
+            };
+            let id_rota = celula(registro, Some(col_rota)).unwrap_or_default();
+            match linha_da_rota.get(id_rota) {
+                Some(&id_linha) => {
+                    linha_da_viagem.insert(id_viagem, id_linha);
+                }
+                None => {
+                    viagens_descartadas.insert(id_viagem);
+                    coletor.registrar(viagens.valor_invalido(registro, "route_id", id_rota, "route_id definido em routes.txt"))?;
+                }
+            }
+        }
+
+        let tempos = importar_horarios_gtfs(self, &horarios, &linha_da_viagem, &viagens_descartadas, &estacao_da_parada, &mut coletor)?;
+        let mut trechos: Vec<_> = tempos.into_iter().collect();
+        trechos.sort_unstable_by_key(|&(trecho, _)| trecho);
+        for ((id_origem, id_destino, id_linha), tempo) in trechos {
+            let tempo_minutos = (tempo.soma_segundos / f64::from(tempo.viagens) / 60.0).max(f64::from(TEMPO_MINIMO_TRECHO_MINUTOS)) as f32;
+            let distancia_km = self.distancia_direta_km(id_origem, id_destino).unwrap_or(0.0);
+            self.lista_adjacencia[id_origem].push(Conexao {
+                para_estacao: id_destino,
+                linha: id_linha,
+                distancia_km,
+                tempo_minutos,
+            });
+        }
+
+        if let Some(tabela) = transferencias {
+            self.importar_transferencias_gtfs(&tabela, &estacao_da_parada, &mut coletor)?;
+        }
+        self.definir_velocidades_observadas();
+        Ok(coletor.concluir())
+    }
+
+    /// Cria as estações do feed e devolve, para cada stop_id, a estação em que ele foi agrupado
+    fn importar_paradas_gtfs(
+        &mut self,
+        paradas: &TabelaGtfs,
+        coletor: &mut ColetorAvisos,
+    ) -> Result<HashMap<String, IdEstacao>, ErroCarregamento> {
+        let col_id = paradas.coluna_obrigatoria("stop_id")?;
+        let col_nome = paradas.coluna("stop_name");
+        let col_lat = paradas.coluna("stop_lat");
+        let col_lon = paradas.coluna("stop_lon");
+        let col_tipo = paradas.coluna("location_type");
+        let col_pai = paradas.coluna("parent_station");
+        let col_zona = paradas.coluna("zone_id");
+        let col_acessivel = paradas.coluna("wheelchair_boarding");
+
+        // Entradas, nós de circulação e áreas de embarque (location_type >= 2) não são paradas de viagem
+        let eh_parada_ou_estacao = |registro: &StringRecord| {
+            matches!(celula(registro, col_tipo).unwrap_or("0"), "0" | "1")
+        };
+
+        let mut estacao_da_parada = HashMap::new();
+
+        // Primeiro as estações: paradas sem estação-mãe
+        for registro in paradas.registros.iter().filter(|r| eh_parada_ou_estacao(r) && celula(r, col_pai).is_none()) {
+            let Some(id_parada) = celula(registro, Some(col_id)) else { continue };
+            let id = self.adicionar_estacao(id_parada);
+            if let Some(nome) = celula(registro, col_nome) {
+                self.renomear_estacao(id, nome);
+            }
+
+            let mut coordenada = |coluna, nome_coluna| -> Result<Option<f64>, ErroCarregamento> {
+                let Some(valor) = celula(registro, coluna) else { return Ok(None) };
+                let numero = valor.parse::<f64>().ok();
+                if numero.is_none() {
+                    coletor.registrar(paradas.valor_invalido(registro, nome_coluna, valor, "número"))?;
+                }
+                Ok(numero)
+            };
+            let latitude = coordenada(col_lat, "stop_lat")?;
+            let longitude = coordenada(col_lon, "stop_lon")?;
+            let estacao = &mut self.estacoes[id];
+            if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+                estacao.coordenadas = Some(Coordenadas::Geograficas { latitude, longitude });
+            }
+            estacao.zona = celula(registro, col_zona).and_then(|v| v.parse::<u8>().ok());
+            estacao.acessivel = celula(registro, col_acessivel) == Some("1");
+
+            estacao_da_parada.insert(id_parada.to_string(), id);
+        }
+
+        // Depois as plataformas, agrupadas na estação-mãe
+        for registro in paradas.registros.iter().filter(|r| eh_parada_ou_estacao(r)) {
+            let (Some(id_parada), Some(id_pai)) = (celula(registro, Some(col_id)), celula(registro, col_pai)) else {
+                continue;
+            };
+            match estacao_da_parada.get(id_pai).copied() {
+                Some(id_estacao) => {
+                    estacao_da_parada.insert(id_parada.to_string(), id_estacao);
+                }
+                None => coletor.registrar(AvisoCarregamento::EstacaoDesconhecida {
+                    local: paradas.local(registro, "parent_station"),
+                    codigo: id_pai.to_string(),
+                })?,
+            }
+        }
+
+        Ok(estacao_da_parada)
+    }
+
+    /// Cria uma linha para cada rota e devolve o mapa route_id -> linha
+    fn importar_rotas_gtfs(&mut self, rotas: &TabelaGtfs, coletor: &mut ColetorAvisos) -> Result<HashMap<String, IdLinha>, ErroCarregamento> {
+        let col_id = rotas.coluna_obrigatoria("route_id")?;
+        let col_nome_curto = rotas.coluna("route_short_name");
+        let col_nome_longo = rotas.coluna("route_long_name");
+        let col_cor = rotas.coluna("route_color");
+        let col_tipo = rotas.coluna("route_type");
+
+        let mut linha_da_rota = HashMap::new();
+        for registro in &rotas.registros {
+            let Some(id_rota) = celula(registro, Some(col_id)) else { continue };
+            let nome = celula(registro, col_nome_curto)
+                .or_else(|| celula(registro, col_nome_longo))
+                .unwrap_or(id_rota);
+            let cor_rgb = celula(registro, col_cor).and_then(parsear_cor_hex).unwrap_or(COR_LINHA_PADRAO);
+            let id_linha = self.adicionar_linha(id_rota, nome, cor_rgb);
+            if let Some(valor) = celula(registro, col_tipo) {
+                match valor.parse::<u16>() {
+                    Ok(route_type) => self.linhas[id_linha].modo = modo_do_route_type(route_type),
+                    Err(_) => coletor.registrar(rotas.valor_invalido(registro, "route_type", valor, "route_type numérico"))?,
+                }
+            }
+            linha_da_rota.insert(id_rota.to_string(), id_linha);
+        }
+        Ok(linha_da_rota)
+    }
+
+    /// Define a velocidade de cada linha como a maior observada nas suas conexões (distância em
+    /// linha reta / tempo). A heurística divide pela maior delas, então nenhuma conexão pode ser mais rápida.
+    fn definir_velocidades_observadas(&mut self) {
+        for conexao in self.lista_adjacencia.iter().flatten() {
+            if conexao.tempo_minutos <= 0.0 || conexao.distancia_km <= 0.0 {
+                continue;
+            }
+            let velocidade_kmh = conexao.distancia_km / conexao.tempo_minutos * 60.0;
+            let linha = &mut self.linhas[conexao.linha];
+            linha.velocidade_kmh = Some(linha.velocidade_kmh.map_or(velocidade_kmh, |v| v.max(velocidade_kmh)));
+        }
+    }
+
+    /// Adiciona conexões a pé para as transferências entre estações diferentes
+    fn importar_transferencias_gtfs(
+        &mut self,
+        transferencias: &TabelaGtfs,
+        estacao_da_parada: &HashMap<String, IdEstacao>,
+        coletor: &mut ColetorAvisos,
+    ) -> Result<(), ErroCarregamento> {
+        let col_de = transferencias.coluna_obrigatoria("from_stop_id")?;
+        let col_para = transferencias.coluna_obrigatoria("to_stop_id")?;
+        let col_tipo = transferencias.coluna("transfer_type");
+        let col_tempo = transferencias.coluna("min_transfer_time");
+
+        let id_linha_a_pe = self.adicionar_linha(CODIGO_LINHA_A_PE, "A pé", COR_LINHA_PADRAO);
+        self.linhas[id_linha_a_pe].modo = ModoTransporte::APe;
+        for registro in &transferencias.registros {
+            // transfer_type 3: transferência impossível
+            if celula(registro, col_tipo) == Some("3") {
+                continue;
+            }
+            let mut estacao = |coluna, nome_coluna| -> Result<Option<IdEstacao>, ErroCarregamento> {
+                let codigo = celula(registro, Some(coluna)).unwrap_or_default();
+                let id_estacao = estacao_da_parada.get(codigo).copied();
+                if id_estacao.is_none() {
+                    coletor.registrar(AvisoCarregamento::EstacaoDesconhecida {
+                        local: transferencias.local(registro, nome_coluna),
+                        codigo: codigo.to_string(),
+                    })?;
+                }
+                Ok(id_estacao)
+            };
+            let (Some(id_origem), Some(id_destino)) = (estacao(col_de, "from_stop_id")?, estacao(col_para, "to_stop_id")?) else {
+                continue;
+            };
+            // Transferências dentro da mesma estação já são cobertas pelo custo de baldeação
+            if id_origem == id_destino
+                || self.lista_adjacencia[id_origem].iter().any(|c| c.para_estacao == id_destino && c.linha == id_linha_a_pe) {
+                continue;
+            }
+
+            let tempo_minutos = match celula(registro, col_tempo).map(|valor| (valor, valor.parse::<f32>().ok().filter(|s| *s >= 0.0))) {
+                Some((_, Some(segundos))) => segundos / 60.0,
+                Some((valor, None)) => {
+                    coletor.registrar(transferencias.valor_invalido(registro, "min_transfer_time", valor, "segundos (número não negativo)"))?;
+                    self.tempo_baldeacao_minutos
+                }
+                None => self.tempo_baldeacao_minutos,
+            };
+            let distancia_km = self.distancia_direta_km(id_origem, id_destino).unwrap_or(0.0);
+            self.lista_adjacencia[id_origem].push(Conexao {
+                para_estacao: id_destino,
+                linha: id_linha_a_pe,
+                distancia_km,
+                tempo_minutos,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Percorre stop_times.txt e acumula o tempo de cada trecho entre paradas consecutivas de uma viagem
+fn importar_horarios_gtfs<'a>(
+    grafo: &GrafoMetro,
+    horarios: &'a TabelaGtfs,
+    linha_da_viagem: &HashMap<&str, IdLinha>,
+    viagens_descartadas: &HashSet<&str>,
+    estacao_da_parada: &HashMap<String, IdEstacao>,
+    coletor: &mut ColetorAvisos,
+) -> Result<TemposPorTrecho, ErroCarregamento> {
+    let col_trip = horarios.coluna_obrigatoria("trip_id")?;
+    let col_parada = horarios.coluna_obrigatoria("stop_id")?;
+    let col_sequencia = horarios.coluna_obrigatoria("stop_sequence")?;
+    let col_chegada = horarios.coluna("arrival_time");
+    let col_partida = horarios.coluna("departure_time");
+
+    let mut paradas_das_viagens: Vec<ParadaDaViagem<'a>> = Vec::new();
+    for registro in &horarios.registros {
+        let id_viagem = celula(registro, Some(col_trip)).unwrap_or_default();
+        if viagens_descartadas.contains(id_viagem) {
+            continue;
+        }
+        if !linha_da_viagem.contains_key(id_viagem) {
+            coletor.registrar(horarios.valor_invalido(registro, "trip_id", id_viagem, "trip_id definido em trips.txt"))?;
+            continue;
+        }
+        let codigo_parada = celula(registro, Some(col_parada)).unwrap_or_default();
+        let Some(&id_estacao) = estacao_da_parada.get(codigo_parada) else {
+            coletor.registrar(AvisoCarregamento::EstacaoDesconhecida {
+                local: horarios.local(registro, "stop_id"),
+                codigo: codigo_parada.to_string(),
+            })?;
+            continue;
+        };
+        let texto_sequencia = celula(registro, Some(col_sequencia)).unwrap_or_default();
+        let Ok(sequencia) = texto_sequencia.parse::<u32>() else {
+            coletor.registrar(horarios.valor_invalido(registro, "stop_sequence", texto_sequencia, "número inteiro não negativo"))?;
+            continue;
+        };
+        // Horário em branco é permitido (a parada é interpolada); ilegível vira aviso e conta como em branco
+        let mut horario = |coluna, nome_coluna| -> Result<Option<u32>, ErroCarregamento> {
+            let Some(valor) = celula(registro, coluna) else { return Ok(None) };
+            let segundos = parsear_horario_gtfs(valor);
+            if segundos.is_none() {
+                coletor.registrar(horarios.valor_invalido(registro, nome_coluna, valor, "horário HH:MM:SS"))?;
+            }
+            Ok(segundos)
+        };
+        let chegada = horario(col_chegada, "arrival_time")?;
+        let partida = horario(col_partida, "departure_time")?.or(chegada);
+        paradas_das_viagens.push(ParadaDaViagem {
+            registro,
+            id_viagem,
+            sequencia,
+            id_estacao,
+            chegada: chegada.or(partida),
+            partida,
+        });
+    }
+    paradas_das_viagens.sort_unstable_by_key(|parada| (parada.id_viagem, parada.sequencia));
+
+    let mut tempos = TemposPorTrecho::new();
+    for viagem in paradas_das_viagens.chunk_by(|a, b| a.id_viagem == b.id_viagem) {
+        let id_linha = linha_da_viagem[viagem[0].id_viagem];
+        let com_horario: Vec<usize> = (0..viagem.len()).filter(|&indice| viagem[indice].partida.is_some()).collect();
+        // A primeira e a última parada precisam de horário; sem ele, os trechos além delas ficam de fora
+        let mut extremos = vec![0, viagem.len() - 1];
+        extremos.dedup();
+        for parada in extremos.into_iter().map(|indice| &viagem[indice]).filter(|parada| parada.partida.is_none()) {
+            coletor.registrar(horarios.valor_invalido(parada.registro, "arrival_time", "",
+                "horário na primeira e na última parada da viagem"))?;
+        }
+        for par in com_horario.windows(2) {
+            let trechos = &viagem[par[0]..=par[1]];
+            let (Some(partida), Some(chegada)) = (trechos[0].partida, trechos[trechos.len() - 1].chegada) else { continue };
+            for (trecho, segundos) in trechos.windows(2).zip(dividir_tempo(grafo, trechos, chegada.saturating_sub(partida))) {
+                let (a, b) = (&trecho[0], &trecho[1]);
+                if a.id_estacao == b.id_estacao {
+                    continue;
+                }
+                let tempo = tempos.entry((a.id_estacao, b.id_estacao, id_linha)).or_default();
+                tempo.soma_segundos += segundos;
+                tempo.viagens += 1;
+            }
+        }
+    }
+    Ok(tempos)
+}
+
+/// Divide o tempo entre duas paradas com horário pelos trechos até lá, proporcionalmente à
+/// distância em linha reta de cada um; em partes iguais se falta alguma coordenada
+fn dividir_tempo(grafo: &GrafoMetro, paradas: &[ParadaDaViagem], segundos: u32) -> Vec<f64> {
+    let distancias: Option<Vec<f64>> = paradas.windows(2)
+        .map(|par| grafo.distancia_direta_km(par[0].id_estacao, par[1].id_estacao).map(f64::from))
+        .collect();
+    let pesos = distancias
+        .filter(|distancias| distancias.iter().sum::<f64>() > 0.0)
+        .unwrap_or_else(|| vec![1.0; paradas.len() - 1]);
+    let total: f64 = pesos.iter().sum();
+    pesos.iter().map(|peso| f64::from(segundos) * peso / total).collect()
+}
@@ -0,0 +1,23 @@
+//! Núcleo do planejador de rotas: grafo do metrô, carregadores de dados e busca A*.
+//! A interface gráfica fica atrás da feature `gui` (ativa por padrão).
+
+pub mod grafo_metro;
+pub mod erro_carregamento;
+pub mod dados_metro;
+pub mod importador_gtfs;
+pub mod arquivo_rede;
+pub mod validacao_rede;
+pub mod analise_heuristica;
+pub mod heuristica_alt;
+pub mod quadro_horarios;
+pub mod fila_prioridade;
+pub mod estrategia_busca;
+pub mod algoritmo_a_estrela;
+pub mod busca_bidirecional;
+pub mod busca_pareto;
+pub mod rotas_alternativas;
+pub mod busca_chegada;
+pub mod observador_busca;
+pub mod exportacao_busca;
+#[cfg(feature = "gui")]
+pub mod egui;
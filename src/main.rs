@@ -1,12 +1,34 @@
-mod grafo_metro;
-mod dados_metro;
-mod algoritmo_a_estrela;
-mod egui;
-
-use egui::MinhaAplicacaoGUI; // Importa nossa struct da aplicação
+use metro_paris_astar::dados_metro::FonteRede;
+use metro_paris_astar::erro_carregamento::ModoCarregamento;
+use metro_paris_astar::egui::MinhaAplicacaoGUI; // Importa nossa struct da aplicação
 
 fn main() -> Result<(), eframe::Error> {
+    // Opções de linha de comando (validar, analisar heurísticas e exportar a rede ficam no metro-route):
+    //   --gtfs <diretório ou .zip>   usa um feed GTFS em vez das tabelas CSV de data/
+    //   --rede <arquivo.json|.toml>  usa um arquivo de rede único
+    //   --estrito                    recusa dados inconsistentes em vez de ignorá-los com aviso
+    //   --horarios <arquivo>         quadro de horários (.json/.toml) para as consultas com horário de partida
+    let argumentos: Vec<String> = std::env::args().collect();
+    let valor_do_argumento = |nome: &str| argumentos.iter()
+        .position(|arg| arg == nome)
+        .and_then(|indice| argumentos.get(indice + 1))
+        .cloned();
+
+    let fonte_rede = if let Some(caminho) = valor_do_argumento("--gtfs") {
+        FonteRede::Gtfs(caminho)
+    } else if let Some(caminho) = valor_do_argumento("--rede") {
+        FonteRede::ArquivoRede(caminho)
+    } else {
+        FonteRede::TabelasCsv("data".to_string())
+    };
+    let modo_carregamento = if argumentos.iter().any(|arg| arg == "--estrito") {
+        ModoCarregamento::Estrito
+    } else {
+        ModoCarregamento::Tolerante
+    };
+
     println!("Iniciando aplicação GUI do Metrô de Paris A*...");
+    let caminho_horarios = valor_do_argumento("--horarios");
 
     let opcoes_nativas = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -19,6 +41,6 @@ fn main() -> Result<(), eframe::Error> {
     eframe::run_native(
         "Metrô de Paris - Planejador de Rotas A*", // Título da Janela
         opcoes_nativas,
-        Box::new(|cc| Ok(Box::new(MinhaAplicacaoGUI::new(cc)))), // Cria e passa nossa app
+        Box::new(|cc| Ok(Box::new(MinhaAplicacaoGUI::new(cc, &fonte_rede, modo_carregamento, caminho_horarios.as_deref())))), // Cria e passa nossa app
     )
-}
\ No newline at end of file
+}
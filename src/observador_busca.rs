@@ -0,0 +1,145 @@
+use crate::algoritmo_a_estrela::EventoVisual;
+use crate::grafo_metro::{GrafoMetro, IdEstacao};
+use std::fmt;
+use std::io::{self, Write};
+
+/// Recebe os eventos da busca à medida que acontecem. O solucionador notifica o observador
+/// tanto em `proximo_passo` quanto em `proximo_evento`; por padrão usa `ObservadorSilencioso`.
+pub trait ObservadorBusca: fmt::Debug {
+    fn notificar(&mut self, grafo: &GrafoMetro, evento: &EventoVisual);
+
+    /// Chamado ao fim da busca por quem grava em arquivo, para descarregar a saída
+    /// e devolver o primeiro erro de escrita
+    fn finalizar(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Ignora todos os eventos
+#[derive(Debug, Default)]
+pub struct ObservadorSilencioso;
+
+impl ObservadorBusca for ObservadorSilencioso {
+    fn notificar(&mut self, _grafo: &GrafoMetro, _evento: &EventoVisual) {}
+}
+
+/// Escreve cada evento como uma linha legível na saída padrão, com as estações pelo código
+#[derive(Debug, Default)]
+pub struct ObservadorConsole {
+    passo: usize,
+}
+
+impl ObservadorConsole {
+    pub fn novo() -> Self {
+        Self::default()
+    }
+}
+
+impl ObservadorBusca for ObservadorConsole {
+    fn notificar(&mut self, grafo: &GrafoMetro, evento: &EventoVisual) {
+        let codigo = |id: IdEstacao| grafo.estacoes.get(id).map_or("?", |estacao| estacao.codigo.as_str());
+        let caminho = |ids: &[IdEstacao]| ids.iter().map(|&id| codigo(id)).collect::<Vec<_>>().join(" -> ");
+
+        match evento {
+            EventoVisual::AlgoritmoInicializado { id_inicio, id_objetivo, custo_h_inicial } => {
+                println!("Busca de {} para {} (h inicial={:.1})", codigo(*id_inicio), codigo(*id_objetivo), custo_h_inicial);
+            }
+            EventoVisual::NoEscolhidoDaFronteira { id_estacao, custo_f, custo_g, custo_h, caminho_ate_aqui } => {
+                self.passo += 1;
+                println!("\n=== PASSO {} ===", self.passo);
+                println!("SELECIONANDO: Estação {} (f={:.1}, g={:.1}, h={:.1}) | {}",
+                    codigo(*id_estacao), custo_f, custo_g, custo_h, caminho(caminho_ate_aqui));
+            }
+            EventoVisual::VerificandoSeEhObjetivo { id_estacao, eh_objetivo } => {
+                if *eh_objetivo {
+                    println!("  {} é o objetivo", codigo(*id_estacao));
+                }
+            }
+            EventoVisual::InicioDaExpansaoDeNo { id_estacao, numero_vizinhos } => {
+                println!("  Expandindo {}: {} conexão(ões)", codigo(*id_estacao), numero_vizinhos);
+            }
+            EventoVisual::AvaliandoVizinho { id_vizinho, linha_conexao, tempo_conexao, custo_baldeacao, custo_espera, custo_g_novo, custo_h, custo_f_novo, .. } => {
+                println!("    Analisando {} via linha {} ({:.1} min): g={:.1}, h={:.1}, f={:.1}",
+                    codigo(*id_vizinho), grafo.nome_linha(*linha_conexao), tempo_conexao, custo_g_novo, custo_h, custo_f_novo);
+                if *custo_baldeacao > 0.0 {
+                    println!("      inclui baldeação: +{:.1} min", custo_baldeacao);
+                }
+                if *custo_espera > 0.0 {
+                    println!("      inclui espera pelo trem: +{:.1} min", custo_espera);
+                }
+            }
+            EventoVisual::VizinhoJaExplorado { id_vizinho } => {
+                println!("    Ignorando {}: já explorado", codigo(*id_vizinho));
+            }
+            EventoVisual::VizinhoJaTemCaminhoMelhor { id_vizinho, custo_g_existente, custo_g_novo } => {
+                println!("    {} já tem caminho melhor (g={:.1} <= {:.1})", codigo(*id_vizinho), custo_g_existente, custo_g_novo);
+            }
+            EventoVisual::VizinhoAdicionadoNaFronteira { id_vizinho, custo_f, novo_caminho, .. } => {
+                println!("    {} adicionado na fronteira (f={:.1}) | {}", codigo(*id_vizinho), custo_f, caminho(novo_caminho));
+            }
+            EventoVisual::FimDaExpansaoDeNo { id_estacao, vizinhos_adicionados, tamanho_fronteira } => {
+                println!("  Fim da expansão de {}: {} adicionado(s), fronteira com {} nó(s)",
+                    codigo(*id_estacao), vizinhos_adicionados, tamanho_fronteira);
+            }
+            EventoVisual::CaminhoEncontrado { info_caminho } => {
+                let ids: Vec<IdEstacao> = info_caminho.estacoes_do_caminho.iter().map(|&(id, _)| id).collect();
+                println!("\nCAMINHO ENCONTRADO: {}", caminho(&ids));
+                println!("Baldeações: {} | Tempo total: {:.1} min", info_caminho.baldeacoes, info_caminho.tempo_total_minutos);
+            }
+            EventoVisual::NenhumCaminho => println!("\nNenhum caminho possível"),
+        }
+    }
+}
+
+/// Grava cada evento como um objeto JSON por linha (JSON Lines), o mesmo formato de
+/// `exportacao_busca::escrever_jsonl`. Erros de escrita são guardados e devolvidos em `finalizar`.
+pub struct ObservadorJsonl<W: Write> {
+    saida: W,
+    erro: Option<io::Error>,
+}
+
+impl<W: Write> ObservadorJsonl<W> {
+    pub fn novo(saida: W) -> Self {
+        Self { saida, erro: None }
+    }
+}
+
+impl<W: Write> fmt::Debug for ObservadorJsonl<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservadorJsonl").field("erro", &self.erro).finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> ObservadorBusca for ObservadorJsonl<W> {
+    fn notificar(&mut self, _grafo: &GrafoMetro, evento: &EventoVisual) {
+        if self.erro.is_some() {
+            return;
+        }
+        let resultado = serde_json::to_writer(&mut self.saida, evento)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(self.saida));
+        if let Err(e) = resultado {
+            self.erro = Some(e);
+        }
+    }
+
+    fn finalizar(&mut self) -> io::Result<()> {
+        match self.erro.take() {
+            Some(e) => Err(e),
+            None => self.saida.flush(),
+        }
+    }
+}
+
+/// Repassa cada evento a vários observadores, na ordem da lista
+impl ObservadorBusca for Vec<Box<dyn ObservadorBusca>> {
+    fn notificar(&mut self, grafo: &GrafoMetro, evento: &EventoVisual) {
+        for observador in self.iter_mut() {
+            observador.notificar(grafo, evento);
+        }
+    }
+
+    fn finalizar(&mut self) -> io::Result<()> {
+        self.iter_mut().try_for_each(|observador| observador.finalizar())
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+
+use crate::arquivo_rede::FormatoArquivoRede;
+use crate::erro_carregamento::{AvisoCarregamento, ColetorAvisos, ErroCarregamento, LocalDados, ModoCarregamento};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+
+/// Minutos em um dia; os horários são contados em minutos desde a meia-noite do dia da consulta
+pub const MINUTOS_POR_DIA: f32 = 1440.0;
+
+/// Folga para considerar que um trem parte exatamente no instante pedido
+const TOLERANCIA: f32 = 1e-3;
+
+/// Intervalo entre trens numa faixa do dia, de `inicio` (inclusive) a `fim` (exclusive); os
+/// trens partem em `inicio`, `inicio + intervalo`, ...
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaixaIntervalo {
+    pub inicio: f32,
+    pub fim: f32,
+    pub intervalo_minutos: f32,
+}
+
+/// Operação de uma linha ao longo de um dia de serviço. Os horários são minutos desde a meia-noite
+/// e podem passar de 24h (depois da meia-noite, antes do fim do serviço). Partidas explícitas têm
+/// precedência sobre os intervalos: as da estação, se houver, senão as da linha.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServicoLinha {
+    pub primeiro_trem: f32,
+    pub ultimo_trem: f32,
+    /// Intervalo fora das faixas; sem ele, fora das faixas não há espera
+    pub intervalo_minutos: Option<f32>,
+    pub faixas: Vec<FaixaIntervalo>,
+    /// Partidas iguais em todas as estações da linha, em ordem crescente
+    pub partidas: Vec<f32>,
+    pub partidas_por_estacao: HashMap<IdEstacao, Vec<f32>>,
+}
+
+impl Default for ServicoLinha {
+    /// O dia todo, sem espera
+    fn default() -> Self {
+        ServicoLinha {
+            primeiro_trem: 0.0,
+            ultimo_trem: MINUTOS_POR_DIA,
+            intervalo_minutos: None,
+            faixas: Vec::new(),
+            partidas: Vec::new(),
+            partidas_por_estacao: HashMap::new(),
+        }
+    }
+}
+
+/// Primeiro horário da sequência `inicio + k * intervalo` que não é anterior a `instante`
+fn alinhar(inicio: f32, intervalo: f32, instante: f32) -> f32 {
+    if instante <= inicio {
+        return inicio;
+    }
+    inicio + ((instante - inicio) / intervalo - TOLERANCIA).ceil() * intervalo
+}
+
+/// Último horário da sequência `inicio + k * intervalo` que não é posterior a `instante` (>= `inicio`)
+fn alinhar_para_tras(inicio: f32, intervalo: f32, instante: f32) -> f32 {
+    inicio + ((instante - inicio) / intervalo + TOLERANCIA).floor() * intervalo
+}
+
+/// Último horário da sequência `inicio + k * intervalo` estritamente anterior a `limite`
+fn anterior_a(inicio: f32, intervalo: f32, limite: f32) -> f32 {
+    inicio + (((limite - inicio) / intervalo - TOLERANCIA).ceil() - 1.0) * intervalo
+}
+
+impl ServicoLinha {
+    /// Próxima partida na estação a partir de `instante`, dentro deste dia de serviço
+    fn proxima_no_dia(&self, id_estacao: IdEstacao, instante: f32) -> Option<f32> {
+        let partidas = self.partidas_por_estacao.get(&id_estacao)
+            .or(Some(&self.partidas).filter(|partidas| !partidas.is_empty()));
+        if let Some(partidas) = partidas {
+            return partidas.iter().copied().find(|&partida| partida >= instante - TOLERANCIA);
+        }
+
+        let instante = instante.max(self.primeiro_trem);
+        let faixa_em = |horario: f32| self.faixas.iter().find(|faixa| faixa.inicio <= horario && horario < faixa.fim);
+
+        let proxima_nas_faixas = self.faixas.iter()
+            .map(|faixa| alinhar(faixa.inicio, faixa.intervalo_minutos, instante))
+            .zip(&self.faixas)
+            .filter(|&(horario, faixa)| horario < faixa.fim && horario <= self.ultimo_trem)
+            .map(|(horario, _)| horario)
+            .min_by(f32::total_cmp);
+
+        // Fora das faixas vale o intervalo padrão (ou partida imediata); horários que caem numa
+        // faixa pertencem a ela, então a procura continua no fim da faixa
+        let mut horario = instante;
+        let proxima_fora_das_faixas = loop {
+            if let Some(intervalo) = self.intervalo_minutos {
+                horario = alinhar(self.primeiro_trem, intervalo, horario);
+            }
+            if horario > self.ultimo_trem + TOLERANCIA {
+                break None;
+            }
+            match faixa_em(horario) {
+                Some(faixa) => horario = faixa.fim,
+                None => break Some(horario),
+            }
+        };
+
+        match (proxima_nas_faixas, proxima_fora_das_faixas) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Última partida na estação até `instante`, dentro deste dia de serviço (o inverso de `proxima_no_dia`)
+    fn anterior_no_dia(&self, id_estacao: IdEstacao, instante: f32) -> Option<f32> {
+        let partidas = self.partidas_por_estacao.get(&id_estacao)
+            .or(Some(&self.partidas).filter(|partidas| !partidas.is_empty()));
+        if let Some(partidas) = partidas {
+            return partidas.iter().copied().rev().find(|&partida| partida <= instante + TOLERANCIA);
+        }
+
+        let instante = instante.min(self.ultimo_trem);
+        if instante < self.primeiro_trem - TOLERANCIA {
+            return None;
+        }
+        let faixa_em = |horario: f32| self.faixas.iter().find(|faixa| faixa.inicio <= horario && horario < faixa.fim);
+
+        let anterior_nas_faixas = self.faixas.iter()
+            .filter(|faixa| faixa.inicio <= instante + TOLERANCIA)
+            .map(|faixa| if instante < faixa.fim {
+                alinhar_para_tras(faixa.inicio, faixa.intervalo_minutos, instante)
+            } else {
+                anterior_a(faixa.inicio, faixa.intervalo_minutos, faixa.fim)
+            })
+            .max_by(f32::total_cmp);
+
+        // Como na ida, horários que caem numa faixa pertencem a ela: a procura continua antes do início da faixa
+        let mut horario = instante;
+        let anterior_fora_das_faixas = loop {
+            if let Some(intervalo) = self.intervalo_minutos {
+                horario = alinhar_para_tras(self.primeiro_trem, intervalo, horario);
+            }
+            if horario < self.primeiro_trem - TOLERANCIA {
+                break None;
+            }
+            match (faixa_em(horario), self.intervalo_minutos) {
+                (Some(faixa), Some(intervalo)) => horario = anterior_a(self.primeiro_trem, intervalo, faixa.inicio),
+                // Sem intervalo padrão, o serviço contínuo vai até o início da faixa, que também tem trem
+                (Some(faixa), None) => break Some(faixa.inicio),
+                (None, _) => break Some(horario),
+            }
+        };
+
+        match (anterior_nas_faixas, anterior_fora_das_faixas) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Camada opcional de horários sobre a rede: linhas sem serviço definido não têm espera
+#[derive(Debug, Clone, Default)]
+pub struct QuadroHorarios {
+    servicos: HashMap<IdLinha, ServicoLinha>,
+}
+
+impl QuadroHorarios {
+    pub fn novo() -> Self {
+        Self::default()
+    }
+
+    pub fn definir_servico(&mut self, id_linha: IdLinha, servico: ServicoLinha) {
+        self.servicos.insert(id_linha, servico);
+    }
+
+    pub fn servico(&self, id_linha: IdLinha) -> Option<&ServicoLinha> {
+        self.servicos.get(&id_linha)
+    }
+
+    pub fn esta_vazio(&self) -> bool {
+        self.servicos.is_empty()
+    }
+
+    /// Horário em que parte o próximo trem da linha na estação, para quem chega à plataforma
+    /// em `instante`. Depois do último trem, é o primeiro do dia seguinte.
+    pub fn proxima_partida(&self, id_linha: IdLinha, id_estacao: IdEstacao, instante: f32) -> f32 {
+        let Some(servico) = self.servicos.get(&id_linha) else {
+            return instante;
+        };
+        // O serviço de um dia pode passar da meia-noite, então o dia anterior também é consultado
+        let dia = (instante / MINUTOS_POR_DIA).floor();
+        (-1..=1)
+            .filter_map(|deslocamento| {
+                let inicio_do_dia = (dia + deslocamento as f32) * MINUTOS_POR_DIA;
+                servico.proxima_no_dia(id_estacao, instante - inicio_do_dia).map(|horario| horario + inicio_do_dia)
+            })
+            .min_by(f32::total_cmp)
+            .unwrap_or(instante)
+    }
+
+    /// Horário da última partida do trem da linha na estação até `instante`: embarcando nele, quem
+    /// precisa partir até `instante` parte o mais tarde possível. Antes do primeiro trem, é o último
+    /// do dia anterior.
+    pub fn partida_anterior(&self, id_linha: IdLinha, id_estacao: IdEstacao, instante: f32) -> f32 {
+        let Some(servico) = self.servicos.get(&id_linha) else {
+            return instante;
+        };
+        let dia = (instante / MINUTOS_POR_DIA).floor();
+        (-1..=1)
+            .filter_map(|deslocamento| {
+                let inicio_do_dia = (dia + deslocamento as f32) * MINUTOS_POR_DIA;
+                servico.anterior_no_dia(id_estacao, instante - inicio_do_dia).map(|horario| horario + inicio_do_dia)
+            })
+            .max_by(f32::total_cmp)
+            .unwrap_or(instante)
+    }
+
+    /// Carrega um quadro de horários de um arquivo .json ou .toml, para as linhas e estações do grafo
+    pub fn carregar(
+        grafo: &GrafoMetro,
+        caminho_arquivo: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<(Self, Vec<AvisoCarregamento>), ErroCarregamento> {
+        let formato = FormatoArquivoRede::do_caminho(caminho_arquivo)?;
+        let conteudo = std::fs::read_to_string(caminho_arquivo).map_err(ErroCarregamento::io(caminho_arquivo))?;
+        Self::de_texto(grafo, &conteudo, formato, caminho_arquivo, modo_carregamento)
+    }
+
+    /// Carrega o quadro de um documento já lido; `origem` só identifica o documento nos erros e avisos.
+    /// Linhas, estações e horários inválidos viram avisos e o item é ignorado.
+    pub fn de_texto(
+        grafo: &GrafoMetro,
+        conteudo: &str,
+        formato: FormatoArquivoRede,
+        origem: &str,
+        modo_carregamento: ModoCarregamento,
+    ) -> Result<(Self, Vec<AvisoCarregamento>), ErroCarregamento> {
+        let arquivo: ArquivoHorarios = match formato {
+            FormatoArquivoRede::Json => serde_json::from_str(conteudo).map_err(|e| ErroCarregamento::formato(origem, e))?,
+            FormatoArquivoRede::Toml => toml::from_str(conteudo).map_err(|e| ErroCarregamento::formato(origem, e))?,
+        };
+        let mut coletor = ColetorAvisos::novo(modo_carregamento);
+        let mut quadro = QuadroHorarios::novo();
+
+        for (indice, linha) in arquivo.linhas.iter().enumerate() {
+            let campo = |nome: &str| format!("linhas[{}].{}", indice, nome);
+            let Some(id_linha) = grafo.obter_id_linha(&linha.linha) else {
+                coletor.registrar(AvisoCarregamento::ValorInvalido {
+                    local: LocalDados::celula(origem, None, &campo("linha")),
+                    valor: linha.linha.clone(),
+                    esperado: "código ou nome de uma linha da rede",
+                })?;
+                continue;
+            };
+            let servico = linha.para_servico(grafo, &mut coletor, origem, &campo)?;
+            quadro.definir_servico(id_linha, servico);
+        }
+        Ok((quadro, coletor.concluir()))
+    }
+}
+
+/// Quadro de horários e horário de partida de uma consulta
+#[derive(Debug, Clone)]
+pub struct ConsultaHorarios {
+    pub quadro: Arc<QuadroHorarios>,
+    pub horario_partida: f32,
+}
+
+impl ConsultaHorarios {
+    /// Espera na plataforma pelo próximo trem da linha, para quem chega nela `decorrido` minutos após a partida
+    pub fn espera_minutos(&self, id_linha: IdLinha, id_estacao: IdEstacao, decorrido: f32) -> f32 {
+        let instante = self.horario_partida + decorrido;
+        (self.quadro.proxima_partida(id_linha, id_estacao, instante) - instante).max(0.0)
+    }
+}
+
+/// Horários de um trecho do itinerário, em minutos desde a meia-noite do dia da consulta
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HorarioTrecho {
+    /// Espera na plataforma antes do trecho (só ao embarcar numa linha)
+    pub espera_minutos: f32,
+    pub partida: f32,
+    pub chegada: f32,
+}
+
+/// Converte "HH:MM" ou "HH:MM:SS" em minutos desde a meia-noite; as horas podem passar de 24,
+/// como no GTFS, para horários depois da meia-noite
+pub fn interpretar_horario(texto: &str) -> Option<f32> {
+    let mut partes = texto.trim().split(':').map(|parte| parte.parse::<u32>().ok());
+    let (Some(Some(horas)), Some(Some(minutos)), segundos, None) = (partes.next(), partes.next(), partes.next(), partes.next()) else {
+        return None;
+    };
+    let segundos = match segundos {
+        None => 0,
+        Some(Some(segundos)) if segundos < 60 => segundos,
+        Some(_) => return None,
+    };
+    (horas < 48 && minutos < 60).then(|| (horas * 60 + minutos) as f32 + segundos as f32 / 60.0)
+}
+
+/// "HH:MM", arredondado ao minuto; horários do dia seguinte ganham "(+1)"
+pub fn formatar_horario(minutos: f32) -> String {
+    let total = minutos.round() as i64;
+    let (dia, minuto_do_dia) = (total.div_euclid(1440), total.rem_euclid(1440));
+    let relogio = format!("{:02}:{:02}", minuto_do_dia / 60, minuto_do_dia % 60);
+    if dia == 0 { relogio } else { format!("{} ({:+})", relogio, dia) }
+}
+
+/// Documento do quadro de horários: o serviço de cada linha, referenciada pelo código ou nome.
+/// Horários anteriores ao primeiro trem da linha são do dia seguinte (depois da meia-noite).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArquivoHorarios {
+    #[serde(default)]
+    pub linhas: Vec<HorarioLinhaArquivo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HorarioLinhaArquivo {
+    pub linha: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primeiro_trem: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ultimo_trem: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intervalo_minutos: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub faixas: Vec<FaixaIntervaloArquivo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partidas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partidas_por_estacao: Vec<PartidasEstacaoArquivo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FaixaIntervaloArquivo {
+    pub inicio: String,
+    pub fim: String,
+    pub intervalo_minutos: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartidasEstacaoArquivo {
+    pub estacao: String,
+    pub horarios: Vec<String>,
+}
+
+impl HorarioLinhaArquivo {
+    fn para_servico(
+        &self,
+        grafo: &GrafoMetro,
+        coletor: &mut ColetorAvisos,
+        origem: &str,
+        campo: &dyn Fn(&str) -> String,
+    ) -> Result<ServicoLinha, ErroCarregamento> {
+        let local = |nome: String| LocalDados::celula(origem, None, &campo(&nome));
+        let horario = |coletor: &mut ColetorAvisos, texto: &str, nome: String| match interpretar_horario(texto) {
+            Some(minutos) => Ok(Some(minutos)),
+            None => coletor
+                .registrar(AvisoCarregamento::ValorInvalido { local: local(nome), valor: texto.to_string(), esperado: "horário HH:MM" })
+                .map(|_| None),
+        };
+        let intervalo_valido = |intervalo: f64| intervalo > 0.0 && intervalo.is_finite();
+
+        let mut servico = ServicoLinha::default();
+        let primeiro_trem = match self.primeiro_trem.as_deref() {
+            Some(texto) => horario(coletor, texto, "primeiro_trem".to_string())?,
+            None => None,
+        };
+        let ultimo_trem = match self.ultimo_trem.as_deref() {
+            Some(texto) => horario(coletor, texto, "ultimo_trem".to_string())?,
+            None => None,
+        };
+        let mut partidas = Vec::new();
+        for (indice, texto) in self.partidas.iter().enumerate() {
+            partidas.extend(horario(coletor, texto, format!("partidas[{}]", indice))?);
+        }
+
+        // Sem primeiro trem declarado, o dia de serviço começa na primeira partida explícita
+        servico.primeiro_trem = primeiro_trem
+            .or_else(|| partidas.iter().copied().min_by(f32::total_cmp))
+            .unwrap_or(0.0);
+        let primeiro_trem = servico.primeiro_trem;
+        let no_dia_de_servico = move |minutos: f32| if minutos < primeiro_trem { minutos + MINUTOS_POR_DIA } else { minutos };
+        servico.ultimo_trem = ultimo_trem.map_or(servico.primeiro_trem + MINUTOS_POR_DIA, no_dia_de_servico);
+
+        match self.intervalo_minutos {
+            Some(intervalo) if intervalo_valido(intervalo) => servico.intervalo_minutos = Some(intervalo as f32),
+            Some(intervalo) => coletor.registrar(AvisoCarregamento::ValorInvalido {
+                local: local("intervalo_minutos".to_string()),
+                valor: intervalo.to_string(),
+                esperado: "intervalo positivo em minutos",
+            })?,
+            None => {}
+        }
+        for (indice, faixa) in self.faixas.iter().enumerate() {
+            let inicio = horario(coletor, &faixa.inicio, format!("faixas[{}].inicio", indice))?;
+            let fim = horario(coletor, &faixa.fim, format!("faixas[{}].fim", indice))?;
+            if !intervalo_valido(faixa.intervalo_minutos) {
+                coletor.registrar(AvisoCarregamento::ValorInvalido {
+                    local: local(format!("faixas[{}].intervalo_minutos", indice)),
+                    valor: faixa.intervalo_minutos.to_string(),
+                    esperado: "intervalo positivo em minutos",
+                })?;
+                continue;
+            }
+            if let (Some(inicio), Some(fim)) = (inicio, fim) {
+                let inicio = no_dia_de_servico(inicio);
+                let fim = no_dia_de_servico(fim);
+                servico.faixas.push(FaixaIntervalo {
+                    inicio,
+                    fim: if fim <= inicio { fim + MINUTOS_POR_DIA } else { fim },
+                    intervalo_minutos: faixa.intervalo_minutos as f32,
+                });
+            }
+        }
+
+        servico.partidas = partidas.into_iter().map(no_dia_de_servico).collect();
+        servico.partidas.sort_by(f32::total_cmp);
+        for (indice, partidas_estacao) in self.partidas_por_estacao.iter().enumerate() {
+            let Some(id_estacao) = grafo.obter_id_estacao(&partidas_estacao.estacao) else {
+                coletor.registrar(AvisoCarregamento::EstacaoDesconhecida {
+                    local: local(format!("partidas_por_estacao[{}].estacao", indice)),
+                    codigo: partidas_estacao.estacao.clone(),
+                })?;
+                continue;
+            };
+            let mut horarios = Vec::new();
+            for (indice_horario, texto) in partidas_estacao.horarios.iter().enumerate() {
+                let nome = format!("partidas_por_estacao[{}].horarios[{}]", indice, indice_horario);
+                horarios.extend(horario(coletor, texto, nome)?.map(no_dia_de_servico));
+            }
+            if !horarios.is_empty() {
+                horarios.sort_by(f32::total_cmp);
+                servico.partidas_por_estacao.insert(id_estacao, horarios);
+            }
+        }
+        Ok(servico)
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::algoritmo_a_estrela::{EstadoBusca, EstadoNoFronteira, InfoCaminho};
+use crate::fila_prioridade::{FilaPrioridadeIndexada, ResultadoInsercao};
+use crate::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+
+/// Quantidade de alternativas pedida quando nenhuma é informada
+pub const QUANTIDADE_ALTERNATIVAS_PADRAO: usize = 3;
+
+/// Uma das k melhores rotas, comparada com a melhor
+#[derive(Debug, Clone)]
+pub struct RotaAlternativa {
+    pub caminho: InfoCaminho,
+    /// Minutos a mais que a melhor rota (0 na própria)
+    pub diferenca_tempo_minutos: f32,
+    /// Baldeações a mais (negativo se a alternativa trocar menos de linha)
+    pub diferenca_baldeacoes: i32,
+}
+
+/// Caminho no espaço de estados (estação, linha de chegada) e custo acumulado até cada estado
+#[derive(Debug, Clone)]
+struct CaminhoEstados {
+    estados: Vec<EstadoBusca>,
+    custos: Vec<f32>,
+}
+
+impl CaminhoEstados {
+    fn custo_total(&self) -> f32 {
+        self.custos.last().copied().unwrap_or(0.0)
+    }
+}
+
+/// Custo de ir de um estado ao seguinte: a conexão mais rápida da linha de chegada, mais a
+/// baldeação quando se troca de linha (mesma regra do A*)
+fn custo_transicao(grafo: &GrafoMetro, (id_origem, linha_origem): EstadoBusca, (id_destino, linha_destino): EstadoBusca) -> f32 {
+    let tempo_conexao = grafo.lista_adjacencia[id_origem].iter()
+        .filter(|conexao| conexao.para_estacao == id_destino && Some(conexao.linha) == linha_destino)
+        .map(|conexao| conexao.tempo_minutos)
+        .fold(f32::INFINITY, f32::min);
+    let troca_de_linha = linha_origem.is_some() && linha_origem != linha_destino;
+    tempo_conexao + if troca_de_linha { grafo.tempo_baldeacao_em(id_origem) } else { 0.0 }
+}
+
+/// Dijkstra sobre os estados a partir de `estado_inicial`, sem passar pelas estações proibidas
+/// nem pelas transições proibidas; para no primeiro estado que chega ao objetivo. A estação de
+/// partida também não pode ser revisitada: voltar a ela por outra linha seria um jeito de trocar
+/// de linha sem pagar a baldeação.
+fn menor_caminho_restrito(
+    grafo: &GrafoMetro,
+    estado_inicial: EstadoBusca,
+    id_objetivo: IdEstacao,
+    estacoes_proibidas: &HashSet<IdEstacao>,
+    transicoes_proibidas: &HashSet<(EstadoBusca, EstadoBusca)>,
+) -> Option<CaminhoEstados> {
+    let mut fronteira = FilaPrioridadeIndexada::nova();
+    let mut explorados: HashSet<EstadoBusca> = HashSet::new();
+    let mut predecessores: HashMap<EstadoBusca, EstadoBusca> = HashMap::new();
+    let mut custos: HashMap<EstadoBusca, f32> = HashMap::new();
+
+    let no = |(id_estacao, linha_chegada): EstadoBusca, custo_g: f32| EstadoNoFronteira {
+        id_estacao,
+        linha_chegada,
+        custo_f: custo_g,
+        custo_g_viagem: custo_g,
+        custo_h: 0.0,
+        trechos: 0,
+    };
+    fronteira.inserir_ou_diminuir(no(estado_inicial, 0.0));
+    custos.insert(estado_inicial, 0.0);
+
+    while let Some(atual) = fronteira.remover_minimo() {
+        let estado_atual = atual.estado();
+        explorados.insert(estado_atual);
+
+        if atual.id_estacao == id_objetivo {
+            let mut estados = vec![estado_atual];
+            while let Some(&anterior) = predecessores.get(estados.last().unwrap()) {
+                estados.push(anterior);
+            }
+            estados.reverse();
+            let custos = estados.iter().map(|estado| custos[estado]).collect();
+            return Some(CaminhoEstados { estados, custos });
+        }
+
+        for conexao in &grafo.lista_adjacencia[atual.id_estacao] {
+            let vizinho: (IdEstacao, Option<IdLinha>) = (conexao.para_estacao, Some(conexao.linha));
+            if vizinho.0 == estado_inicial.0 || estacoes_proibidas.contains(&vizinho.0) || explorados.contains(&vizinho)
+                || transicoes_proibidas.contains(&(estado_atual, vizinho)) {
+                continue;
+            }
+            let custo_g = atual.custo_g_viagem + custo_transicao(grafo, estado_atual, vizinho);
+            if fronteira.inserir_ou_diminuir(no(vizinho, custo_g)) != ResultadoInsercao::Ignorado {
+                predecessores.insert(vizinho, estado_atual);
+                custos.insert(vizinho, custo_g);
+            }
+        }
+    }
+    None
+}
+
+/// A melhor rota e até `alternativas` rotas seguintes em ordem de tempo (algoritmo de Yen),
+/// sobre o mesmo espaço de estados (estação, linha de chegada) e o mesmo custo do A*. Duas rotas
+/// são diferentes quando passam por estações diferentes ou usam linhas diferentes no mesmo trecho;
+/// nenhuma passa duas vezes pela mesma estação.
+pub fn buscar_rotas_alternativas(
+    grafo: &GrafoMetro,
+    id_inicio: IdEstacao,
+    linha_inicial: Option<IdLinha>,
+    id_objetivo: IdEstacao,
+    alternativas: usize,
+) -> Vec<RotaAlternativa> {
+    let estado_inicial = (id_inicio, linha_inicial);
+    let Some(melhor) = menor_caminho_restrito(grafo, estado_inicial, id_objetivo, &HashSet::new(), &HashSet::new()) else {
+        return Vec::new();
+    };
+
+    let mut escolhidos = vec![melhor];
+    let mut candidatos: Vec<CaminhoEstados> = Vec::new();
+    while escolhidos.len() <= alternativas {
+        let anterior = escolhidos.last().unwrap().clone();
+
+        // Cada estado do caminho anterior (menos o último) é um ponto de desvio: a raiz até ele
+        // é mantida e o resto é refeito sem repetir os desvios já usados a partir dessa raiz
+        for indice_desvio in 0..anterior.estados.len() - 1 {
+            let raiz = &anterior.estados[..=indice_desvio];
+            let transicoes_proibidas: HashSet<(EstadoBusca, EstadoBusca)> = escolhidos.iter()
+                .filter(|caminho| caminho.estados.len() > indice_desvio + 1 && caminho.estados[..=indice_desvio] == *raiz)
+                .map(|caminho| (caminho.estados[indice_desvio], caminho.estados[indice_desvio + 1]))
+                .collect();
+            let estacoes_proibidas: HashSet<IdEstacao> = raiz[..indice_desvio].iter().map(|&(id_estacao, _)| id_estacao).collect();
+
+            let Some(desvio) = menor_caminho_restrito(grafo, raiz[indice_desvio], id_objetivo, &estacoes_proibidas, &transicoes_proibidas) else {
+                continue;
+            };
+            let custo_raiz = anterior.custos[indice_desvio];
+            let mut candidato = CaminhoEstados { estados: raiz.to_vec(), custos: anterior.custos[..=indice_desvio].to_vec() };
+            candidato.estados.extend_from_slice(&desvio.estados[1..]);
+            candidato.custos.extend(desvio.custos[1..].iter().map(|custo| custo_raiz + custo));
+
+            let estacoes_distintas: HashSet<IdEstacao> = candidato.estados.iter().map(|&(id_estacao, _)| id_estacao).collect();
+            let ja_conhecido = escolhidos.iter().chain(&candidatos).any(|caminho| caminho.estados == candidato.estados);
+            if estacoes_distintas.len() == candidato.estados.len() && !ja_conhecido {
+                candidatos.push(candidato);
+            }
+        }
+
+        let Some(indice_mais_barato) = (0..candidatos.len())
+            .min_by(|&a, &b| candidatos[a].custo_total().total_cmp(&candidatos[b].custo_total())) else {
+            break;
+        };
+        escolhidos.push(candidatos.swap_remove(indice_mais_barato));
+    }
+
+    let caminhos: Vec<InfoCaminho> = escolhidos.into_iter()
+        .map(|caminho| {
+            let custo_total = caminho.custo_total();
+            InfoCaminho::dos_estados(grafo, caminho.estados, custo_total)
+        })
+        .collect();
+    let (tempo_melhor, baldeacoes_melhor) = (caminhos[0].tempo_total_minutos, caminhos[0].baldeacoes as i32);
+    caminhos.into_iter()
+        .map(|caminho| RotaAlternativa {
+            diferenca_tempo_minutos: caminho.tempo_total_minutos - tempo_melhor,
+            diferenca_baldeacoes: caminho.baldeacoes as i32 - baldeacoes_melhor,
+            caminho,
+        })
+        .collect()
+}
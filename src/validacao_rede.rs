@@ -0,0 +1,307 @@
+use crate::grafo_metro::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+/// Folga para comparar tempos e distâncias lidos como f32
+const TOLERANCIA: f32 = 1e-3;
+
+/// Problema encontrado na rede carregada. Estações e linhas aparecem pelo código,
+/// como nos arquivos de dados, para que o problema possa ser corrigido à mão.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProblemaRede {
+    /// A ida e a volta na mesma linha têm distâncias diferentes
+    DistanciaAssimetrica {
+        estacao_a: String,
+        estacao_b: String,
+        linha: String,
+        ida_km: f32,
+        volta_km: f32,
+    },
+    /// A volta existe, mas em outra(s) linha(s)
+    LinhaAssimetrica {
+        estacao_a: String,
+        estacao_b: String,
+        linha_ida: String,
+        linhas_volta: Vec<String>,
+    },
+    /// Conexão sem nenhuma conexão no sentido contrário
+    ConexaoSemVolta {
+        origem: String,
+        destino: String,
+        linha: String,
+    },
+    /// Conexão numa linha que não existe no grafo (só em grafos montados à mão; os carregadores avisam antes)
+    LinhaInexistente {
+        origem: String,
+        destino: String,
+        id_linha: IdLinha,
+    },
+    /// Conexão de uma estação para ela mesma
+    LacoProprio {
+        estacao: String,
+        linha: String,
+    },
+    /// Estações que não podem ser alcançadas a partir da origem
+    EstacoesInalcancaveis {
+        origem: String,
+        destinos: Vec<String>,
+    },
+    /// A distância heurística de A até B difere da de B até A
+    HeuristicaAssimetrica {
+        estacao_a: String,
+        estacao_b: String,
+        ida_km: f32,
+        volta_km: f32,
+    },
+    /// A heurística superestima o menor tempo real, e o A* pode devolver uma rota que não é a melhor
+    HeuristicaInadmissivel {
+        origem: String,
+        destino: String,
+        heuristica_minutos: f32,
+        tempo_real_minutos: f32,
+    },
+}
+
+impl fmt::Display for ProblemaRede {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemaRede::DistanciaAssimetrica { estacao_a, estacao_b, linha, ida_km, volta_km } => write!(
+                f, "distância assimétrica na linha '{}': {} -> {} = {} km, {} -> {} = {} km",
+                linha, estacao_a, estacao_b, ida_km, estacao_b, estacao_a, volta_km
+            ),
+            ProblemaRede::LinhaAssimetrica { estacao_a, estacao_b, linha_ida, linhas_volta } => write!(
+                f, "linha assimétrica: {} -> {} na linha '{}', mas {} -> {} na(s) linha(s) '{}'",
+                estacao_a, estacao_b, linha_ida, estacao_b, estacao_a, linhas_volta.join("', '")
+            ),
+            ProblemaRede::ConexaoSemVolta { origem, destino, linha } => write!(
+                f, "conexão {} -> {} na linha '{}' sem conexão de volta", origem, destino, linha
+            ),
+            ProblemaRede::LinhaInexistente { origem, destino, id_linha } => write!(
+                f, "conexão {} -> {} numa linha que não existe (id {})", origem, destino, id_linha
+            ),
+            ProblemaRede::LacoProprio { estacao, linha } => write!(
+                f, "conexão de {} para ela mesma na linha '{}'", estacao, linha
+            ),
+            ProblemaRede::EstacoesInalcancaveis { origem, destinos } => write!(
+                f, "a partir de {} não é possível chegar a: {}", origem, destinos.join(", ")
+            ),
+            ProblemaRede::HeuristicaAssimetrica { estacao_a, estacao_b, ida_km, volta_km } => write!(
+                f, "heurística assimétrica: {} -> {} = {} km, {} -> {} = {} km",
+                estacao_a, estacao_b, ida_km, estacao_b, estacao_a, volta_km
+            ),
+            ProblemaRede::HeuristicaInadmissivel { origem, destino, heuristica_minutos, tempo_real_minutos } => write!(
+                f, "heurística inadmissível de {} para {}: h = {:.2} min, mas o menor tempo real é {:.2} min",
+                origem, destino, heuristica_minutos, tempo_real_minutos
+            ),
+        }
+    }
+}
+
+/// Resultado da validação: vazio quando a rede não tem problemas
+#[derive(Debug, Clone, Default)]
+pub struct RelatorioValidacao {
+    pub problemas: Vec<ProblemaRede>,
+}
+
+impl RelatorioValidacao {
+    pub fn esta_valida(&self) -> bool {
+        self.problemas.is_empty()
+    }
+}
+
+/// Entrada da fila de prioridade do Dijkstra (menor tempo primeiro)
+#[derive(PartialEq)]
+struct EntradaFila {
+    tempo_minutos: f32,
+    estado: usize,
+}
+
+impl Eq for EntradaFila {}
+
+impl Ord for EntradaFila {
+    fn cmp(&self, outra: &Self) -> Ordering {
+        outra.tempo_minutos.total_cmp(&self.tempo_minutos)
+    }
+}
+
+impl PartialOrd for EntradaFila {
+    fn partial_cmp(&self, outra: &Self) -> Option<Ordering> {
+        Some(self.cmp(outra))
+    }
+}
+
+/// Menor tempo de viagem da origem até cada estação (`None` se inalcançável).
+///
+/// Com `considerar_baldeacao` a busca percorre o mesmo espaço de estados do A*
+/// (estação, linha de chegada) e soma a penalidade a cada troca de linha; sem ela,
+/// é o Dijkstra simples sobre os tempos das conexões.
+pub fn tempos_minimos_a_partir(grafo: &GrafoMetro, origem: IdEstacao, considerar_baldeacao: bool) -> Vec<Option<f32>> {
+    tempos_minimos_sobre(grafo, &grafo.lista_adjacencia, origem, considerar_baldeacao)
+}
+
+/// Menor tempo de viagem de cada estação até o destino (`None` se ele é inalcançável a partir dela).
+/// Percorre a adjacência invertida; com `considerar_baldeacao` o estado guarda a linha de saída.
+pub fn tempos_minimos_ate(grafo: &GrafoMetro, destino: IdEstacao, considerar_baldeacao: bool) -> Vec<Option<f32>> {
+    tempos_minimos_sobre(grafo, &grafo.lista_adjacencia_reversa(), destino, considerar_baldeacao)
+}
+
+fn tempos_minimos_sobre(grafo: &GrafoMetro, adjacencia: &[Vec<Conexao>], origem: IdEstacao, considerar_baldeacao: bool) -> Vec<Option<f32>> {
+    // Estado = estação * (linhas + 1) + posição da linha de chegada (0: nenhuma)
+    let estados_por_estacao = if considerar_baldeacao { grafo.linhas.len() + 1 } else { 1 };
+    let indice_estado = |id_estacao: IdEstacao, linha: Option<IdLinha>| match linha {
+        Some(id_linha) if considerar_baldeacao => id_estacao * estados_por_estacao + id_linha + 1,
+        _ => id_estacao * estados_por_estacao,
+    };
+
+    let mut tempos_estados = vec![f32::INFINITY; grafo.numero_estacoes() * estados_por_estacao];
+    let mut fila = BinaryHeap::new();
+    if origem < grafo.numero_estacoes() {
+        tempos_estados[indice_estado(origem, None)] = 0.0;
+        fila.push(EntradaFila { tempo_minutos: 0.0, estado: indice_estado(origem, None) });
+    }
+
+    while let Some(EntradaFila { tempo_minutos, estado }) = fila.pop() {
+        if tempo_minutos > tempos_estados[estado] {
+            continue;
+        }
+        let id_estacao = estado / estados_por_estacao;
+        let linha_chegada = (estado % estados_por_estacao).checked_sub(1);
+
+        // Conexões em linhas inexistentes não têm estado próprio; o validador já as reporta
+        for conexao in adjacencia[id_estacao].iter().filter(|conexao| conexao.linha < grafo.linhas.len()) {
+            let custo_baldeacao = match linha_chegada {
+                Some(linha_atual) if linha_atual != conexao.linha => grafo.tempo_baldeacao_em(id_estacao),
+                _ => 0.0,
+            };
+            let tempo_novo = tempo_minutos + conexao.tempo_minutos + custo_baldeacao;
+            let estado_vizinho = indice_estado(conexao.para_estacao, Some(conexao.linha));
+            if tempo_novo < tempos_estados[estado_vizinho] {
+                tempos_estados[estado_vizinho] = tempo_novo;
+                fila.push(EntradaFila { tempo_minutos: tempo_novo, estado: estado_vizinho });
+            }
+        }
+    }
+
+    tempos_estados
+        .chunks(estados_por_estacao)
+        .map(|tempos| tempos.iter().copied().fold(f32::INFINITY, f32::min))
+        .map(|tempo| tempo.is_finite().then_some(tempo))
+        .collect()
+}
+
+/// Verifica a rede carregada e lista todos os problemas encontrados
+pub fn validar_rede(grafo: &GrafoMetro) -> RelatorioValidacao {
+    let mut problemas = Vec::new();
+    verificar_conexoes(grafo, &mut problemas);
+    verificar_heuristica_simetrica(grafo, &mut problemas);
+    verificar_alcance_e_admissibilidade(grafo, &mut problemas);
+    RelatorioValidacao { problemas }
+}
+
+fn verificar_conexoes(grafo: &GrafoMetro, problemas: &mut Vec<ProblemaRede>) {
+    let codigo = |id: IdEstacao| grafo.estacoes[id].codigo.clone();
+    let codigo_linha = |id: IdLinha| grafo.linhas.get(id).map_or_else(|| "?".to_string(), |linha| linha.codigo.clone());
+
+    for (id_origem, conexoes) in grafo.lista_adjacencia.iter().enumerate() {
+        for conexao in conexoes {
+            let id_destino = conexao.para_estacao;
+            if conexao.linha >= grafo.linhas.len() {
+                problemas.push(ProblemaRede::LinhaInexistente { origem: codigo(id_origem), destino: codigo(id_destino), id_linha: conexao.linha });
+                continue;
+            }
+            if id_destino == id_origem {
+                problemas.push(ProblemaRede::LacoProprio { estacao: codigo(id_origem), linha: codigo_linha(conexao.linha) });
+                continue;
+            }
+
+            let voltas: Vec<&Conexao> = grafo.lista_adjacencia[id_destino].iter()
+                .filter(|volta| volta.para_estacao == id_origem)
+                .collect();
+            if voltas.is_empty() {
+                problemas.push(ProblemaRede::ConexaoSemVolta {
+                    origem: codigo(id_origem),
+                    destino: codigo(id_destino),
+                    linha: codigo_linha(conexao.linha),
+                });
+                continue;
+            }
+
+            // Os problemas dos pares com volta são reportados uma vez só, a partir do menor id
+            if id_origem > id_destino {
+                continue;
+            }
+            match voltas.iter().find(|volta| volta.linha == conexao.linha) {
+                Some(volta) if (volta.distancia_km - conexao.distancia_km).abs() > TOLERANCIA => {
+                    problemas.push(ProblemaRede::DistanciaAssimetrica {
+                        estacao_a: codigo(id_origem),
+                        estacao_b: codigo(id_destino),
+                        linha: codigo_linha(conexao.linha),
+                        ida_km: conexao.distancia_km,
+                        volta_km: volta.distancia_km,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    problemas.push(ProblemaRede::LinhaAssimetrica {
+                        estacao_a: codigo(id_origem),
+                        estacao_b: codigo(id_destino),
+                        linha_ida: codigo_linha(conexao.linha),
+                        linhas_volta: voltas.iter().map(|volta| codigo_linha(volta.linha)).collect(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn verificar_heuristica_simetrica(grafo: &GrafoMetro, problemas: &mut Vec<ProblemaRede>) {
+    let Some(matriz) = &grafo.distancias_heuristicas_km else { return };
+    for (id_a, linha_a) in matriz.iter().enumerate() {
+        for (id_b, &ida) in linha_a.iter().enumerate().skip(id_a + 1) {
+            if let (Some(ida_km), Some(volta_km)) = (ida, matriz[id_b][id_a])
+                && (ida_km - volta_km).abs() > TOLERANCIA
+            {
+                problemas.push(ProblemaRede::HeuristicaAssimetrica {
+                    estacao_a: grafo.estacoes[id_a].codigo.clone(),
+                    estacao_b: grafo.estacoes[id_b].codigo.clone(),
+                    ida_km,
+                    volta_km,
+                });
+            }
+        }
+    }
+}
+
+fn verificar_alcance_e_admissibilidade(grafo: &GrafoMetro, problemas: &mut Vec<ProblemaRede>) {
+    for id_origem in 0..grafo.numero_estacoes() {
+        // O A* começa sem linha e paga as baldeações, então é esse o custo real a comparar
+        let tempos = tempos_minimos_a_partir(grafo, id_origem, true);
+
+        let inalcancaveis: Vec<String> = tempos.iter().enumerate()
+            .filter(|&(_, tempo)| tempo.is_none())
+            .map(|(id_destino, _)| grafo.estacoes[id_destino].codigo.clone())
+            .collect();
+        if !inalcancaveis.is_empty() {
+            problemas.push(ProblemaRede::EstacoesInalcancaveis {
+                origem: grafo.estacoes[id_origem].codigo.clone(),
+                destinos: inalcancaveis,
+            });
+        }
+
+        for (id_destino, tempo_real) in tempos.iter().enumerate() {
+            let (Some(tempo_real_minutos), Some(heuristica_minutos)) =
+                (*tempo_real, grafo.obter_tempo_heuristico_minutos(id_origem, id_destino)) else {
+                continue;
+            };
+            if heuristica_minutos > tempo_real_minutos + TOLERANCIA {
+                problemas.push(ProblemaRede::HeuristicaInadmissivel {
+                    origem: grafo.estacoes[id_origem].codigo.clone(),
+                    destino: grafo.estacoes[id_destino].codigo.clone(),
+                    heuristica_minutos,
+                    tempo_real_minutos,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use metro_paris_astar::analise_heuristica::{analisar_fonte_heuristica, analisar_heuristica, ViolacaoAdmissibilidade};
+use metro_paris_astar::grafo_metro::{Conexao, GrafoMetro};
+use metro_paris_astar::heuristica_alt::FonteHeuristica;
+use common::rede_de_exemplo;
+
+/// A, B e C em sequência numa linha, 2 min por trecho; a tabela superestima só a distância de A até C
+fn grafo_com_heuristica_superestimada() -> GrafoMetro {
+    let mut grafo = GrafoMetro::novo();
+    let [a, b, c] = ["A", "B", "C"].map(|codigo| grafo.adicionar_estacao(codigo));
+    let linha = grafo.adicionar_linha("L1", "Um", [200, 0, 0]);
+    for (de, para) in [(a, b), (b, a), (b, c), (c, b)] {
+        grafo.lista_adjacencia[de].push(Conexao { para_estacao: para, linha, distancia_km: 1.0, tempo_minutos: 2.0 });
+    }
+    grafo.definir_distancia_heuristica(a, c, 100.0);
+    grafo
+}
+
+#[test]
+fn par_superestimado_e_inadmissivel_e_inconsistente() {
+    let grafo = grafo_com_heuristica_superestimada();
+    let analise = analisar_fonte_heuristica(&grafo, &FonteHeuristica::LinhaReta);
+
+    assert_eq!(analise.pares_avaliados, 9);
+    let pares = |violacoes: &[ViolacaoAdmissibilidade]| violacoes.iter()
+        .map(|violacao| (violacao.origem.clone(), violacao.destino.clone(), violacao.tempo_real_minutos))
+        .collect::<Vec<_>>();
+    assert_eq!(pares(&analise.inadmissiveis_com_baldeacao), [("A".to_string(), "C".to_string(), 4.0)]);
+    assert_eq!(pares(&analise.inadmissiveis_sem_baldeacao), [("A".to_string(), "C".to_string(), 4.0)]);
+
+    // Os outros pares não têm valor na tabela (h = 0): só a conexão A -> B, rumo a C, quebra a desigualdade
+    assert_eq!(analise.inconsistencias.len(), 1);
+    let inconsistencia = &analise.inconsistencias[0];
+    assert_eq!((inconsistencia.origem.as_str(), inconsistencia.vizinho.as_str(), inconsistencia.objetivo.as_str()), ("A", "B", "C"));
+    assert_eq!(inconsistencia.heuristica_origem_minutos, analise.inadmissiveis_com_baldeacao[0].heuristica_minutos);
+    assert!(!analise.eh_admissivel() && !analise.eh_consistente());
+}
+
+#[test]
+fn linha_reta_da_rede_de_exemplo_e_admissivel() {
+    let grafo = rede_de_exemplo();
+    let analise = analisar_heuristica(&grafo);
+
+    assert_eq!(analise.pares_avaliados, grafo.numero_estacoes() * grafo.numero_estacoes());
+    assert!(analise.eh_admissivel(), "{}", analise);
+    assert!(analise.inadmissiveis_sem_baldeacao.is_empty());
+    assert!(analise.razao_media > 0.0 && analise.razao_media <= 1.0);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::arquivo_rede::FormatoArquivoRede;
+use metro_paris_astar::dados_metro::FonteRede;
+use metro_paris_astar::erro_carregamento::ModoCarregamento;
+use metro_paris_astar::grafo_metro::GrafoMetro;
+use common::rota_a_estrela;
+
+/// A rede das tabelas CSV, com uma penalidade própria de baldeação em La Défense (fora da rota E6 -> E13)
+fn rede_com_penalidade_local() -> GrafoMetro {
+    let diretorio = concat!(env!("CARGO_MANIFEST_DIR"), "/data").to_string();
+    let (mut grafo, _) = FonteRede::TabelasCsv(diretorio).carregar(ModoCarregamento::Tolerante).unwrap();
+    let la_defense = grafo.obter_id_estacao("E1").unwrap();
+    grafo.estacoes[la_defense].tempo_baldeacao_minutos = Some(6.0);
+    grafo
+}
+
+fn numero_conexoes(grafo: &GrafoMetro) -> usize {
+    grafo.lista_adjacencia.iter().map(Vec::len).sum()
+}
+
+fn penalidades_por_estacao(grafo: &GrafoMetro) -> Vec<(String, f32)> {
+    grafo.estacoes.iter()
+        .filter_map(|estacao| estacao.tempo_baldeacao_minutos.map(|tempo| (estacao.codigo.clone(), tempo)))
+        .collect()
+}
+
+fn tempo_e6_e13(grafo: GrafoMetro) -> f32 {
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+    rota_a_estrela(&Arc::new(grafo), inicio, None, objetivo).unwrap().tempo_total_minutos
+}
+
+fn verificar_ida_e_volta(formato: FormatoArquivoRede) {
+    let original = rede_com_penalidade_local();
+    let mut recarregado = GrafoMetro::novo();
+    let texto = original.arquivo_rede_para_texto(formato).unwrap();
+    let avisos = recarregado.carregar_arquivo_rede_de_texto(&texto, formato, "memória", ModoCarregamento::Estrito).unwrap();
+    assert!(avisos.is_empty(), "{:?}", avisos);
+
+    assert_eq!(recarregado.numero_estacoes(), original.numero_estacoes());
+    assert_eq!(recarregado.linhas.len(), original.linhas.len());
+    assert_eq!(numero_conexoes(&recarregado), numero_conexoes(&original));
+    assert_eq!(recarregado.tempo_baldeacao_minutos, original.tempo_baldeacao_minutos);
+    assert_eq!(penalidades_por_estacao(&recarregado), [("E1".to_string(), 6.0)]);
+    assert_eq!(recarregado.distancias_heuristicas_km, original.distancias_heuristicas_km);
+
+    assert!((tempo_e6_e13(original) - 61.6).abs() < 0.01);
+    assert!((tempo_e6_e13(recarregado) - 61.6).abs() < 0.01, "{:?}", formato);
+}
+
+#[test]
+fn rede_exportada_em_json_recarrega_igual() {
+    verificar_ida_e_volta(FormatoArquivoRede::Json);
+}
+
+#[test]
+fn rede_exportada_em_toml_recarrega_igual() {
+    verificar_ida_e_volta(FormatoArquivoRede::Toml);
+}
+
+#[test]
+fn tabela_heuristica_assimetrica_sobrevive_a_ida_e_volta() {
+    let mut original = GrafoMetro::novo();
+    let [a, b, c] = ["A", "B", "C"].map(|codigo| original.adicionar_estacao(codigo));
+    original.definir_distancia_heuristica(a, b, 1.0);
+    original.definir_distancia_heuristica(b, a, 2.0);
+    original.definir_distancia_heuristica(a, c, 3.0);
+    original.definir_distancia_heuristica(b, c, 4.0);
+    original.definir_distancia_heuristica(c, b, 4.0);
+
+    // A -> B e B -> A ficam separadas, A -> C não ganha volta e só B-C, igual nos dois sentidos, vira uma entrada
+    let entradas: Vec<_> = original.para_arquivo_rede().distancias_heuristicas.iter()
+        .map(|distancia| (distancia.de.clone() + distancia.para.as_str(), distancia.bidirecional))
+        .collect();
+    let esperadas = [("AB", false), ("BA", false), ("AC", false), ("BC", true)];
+    assert_eq!(entradas, esperadas.map(|(par, bidirecional)| (par.to_string(), bidirecional)));
+
+    for formato in [FormatoArquivoRede::Json, FormatoArquivoRede::Toml] {
+        let texto = original.arquivo_rede_para_texto(formato).unwrap();
+        let mut recarregado = GrafoMetro::novo();
+        let avisos = recarregado.carregar_arquivo_rede_de_texto(&texto, formato, "memória", ModoCarregamento::Estrito).unwrap();
+        assert!(avisos.is_empty());
+        assert_eq!(recarregado.distancias_heuristicas_km, original.distancias_heuristicas_km, "{:?}:\n{}", formato, texto);
+    }
+}
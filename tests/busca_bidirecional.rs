@@ -0,0 +1,53 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{InfoCaminho, ResultadoPassoAEstrela};
+use metro_paris_astar::busca_bidirecional::SolucionadorBidirecional;
+use metro_paris_astar::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+use common::{linhas_de_partida, rede_de_exemplo, rota_a_estrela, tempo_do_itinerario};
+
+fn rota_bidirecional(grafo: &Arc<GrafoMetro>, inicio: IdEstacao, linha: Option<IdLinha>, objetivo: IdEstacao) -> Option<InfoCaminho> {
+    let mut solucionador = SolucionadorBidirecional::novo(Arc::clone(grafo), inicio, linha, objetivo);
+    loop {
+        match solucionador.proximo_passo() {
+            ResultadoPassoAEstrela::EmProgresso => continue,
+            ResultadoPassoAEstrela::CaminhoEncontrado(caminho) => return Some(caminho),
+            _ => return None,
+        }
+    }
+}
+
+#[test]
+fn bidirecional_encontra_o_mesmo_tempo_do_a_estrela_em_todos_os_pares() {
+    let grafo = rede_de_exemplo();
+    let linhas = linhas_de_partida(&grafo);
+
+    for inicio in 0..grafo.numero_estacoes() {
+        for objetivo in 0..grafo.numero_estacoes() {
+            for &linha in &linhas {
+                let esperado = rota_a_estrela(&grafo, inicio, linha, objetivo).expect("a rede de exemplo é conexa");
+                let obtido = rota_bidirecional(&grafo, inicio, linha, objetivo).expect("a rede de exemplo é conexa");
+
+                assert!((obtido.tempo_total_minutos - esperado.tempo_total_minutos).abs() < 0.01,
+                    "E{} -> E{} (linha {:?}): {} != {}", inicio + 1, objetivo + 1, linha,
+                    obtido.tempo_total_minutos, esperado.tempo_total_minutos);
+                assert!((tempo_do_itinerario(&grafo, &obtido) - obtido.tempo_total_minutos).abs() < 0.01);
+                assert_eq!(obtido.estacoes_do_caminho.first().map(|&(id, _)| id), Some(inicio));
+                assert_eq!(obtido.estacoes_do_caminho.last().map(|&(id, _)| id), Some(objetivo));
+            }
+        }
+    }
+}
+
+#[test]
+fn encontro_na_estacao_de_baldeacao_cobra_a_troca() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+
+    let caminho = rota_bidirecional(&grafo, inicio, None, objetivo).unwrap();
+
+    let estacoes: Vec<_> = caminho.estacoes_do_caminho.iter().map(|&(id, _)| id).collect();
+    assert_eq!(estacoes, vec![5, 4, 3, 12]);
+    assert_eq!(caminho.baldeacoes, 1);
+    assert!((caminho.tempo_total_minutos - 61.6).abs() < 0.01);
+}
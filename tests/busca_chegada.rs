@@ -0,0 +1,112 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{InfoCaminho, SolucionadorAEstrela};
+use metro_paris_astar::busca_chegada::buscar_partida_mais_tarde;
+use metro_paris_astar::grafo_metro::{Conexao, GrafoMetro, IdEstacao, IdLinha};
+use metro_paris_astar::quadro_horarios::{formatar_horario, ConsultaHorarios, QuadroHorarios};
+use common::{executar_ate_o_fim, horario, linhas_de_partida, quadro_de_exemplo, rede_de_exemplo};
+
+fn chegada_partindo_as(grafo: &Arc<GrafoMetro>, consulta: ConsultaHorarios, inicio: IdEstacao, linha: Option<IdLinha>, objetivo: IdEstacao) -> f32 {
+    let horario_partida = consulta.horario_partida;
+    let mut solucionador = SolucionadorAEstrela::novo(Arc::clone(grafo), inicio, linha, objetivo).com_horarios(consulta);
+    horario_partida + executar_ate_o_fim(&mut solucionador).expect("a rede de exemplo é conexa").tempo_total_minutos
+}
+
+/// Partida mais tarde entre todas as rotas sem estação repetida, refazendo cada uma de trás para
+/// a frente a partir do horário de chegada (a rede de exemplo é pequena)
+fn partida_mais_tarde_por_forca_bruta(grafo: &GrafoMetro, quadro: &QuadroHorarios, inicio: IdEstacao, linha: Option<IdLinha>,
+                                      objetivo: IdEstacao, chegada: f32) -> f32 {
+    fn partida_da_rota(grafo: &GrafoMetro, quadro: &QuadroHorarios, linha_inicial: Option<IdLinha>, trechos: &[(IdEstacao, &Conexao)], chegada: f32) -> f32 {
+        let mut horario = chegada;
+        for (indice, &(id_estacao, conexao)) in trechos.iter().enumerate().rev() {
+            let partida = horario - conexao.tempo_minutos;
+            let linha_anterior = if indice == 0 { linha_inicial } else { Some(trechos[indice - 1].1.linha) };
+            horario = match linha_anterior {
+                Some(linha) if linha == conexao.linha => partida,
+                Some(_) => quadro.partida_anterior(conexao.linha, id_estacao, partida) - grafo.tempo_baldeacao_em(id_estacao),
+                None => quadro.partida_anterior(conexao.linha, id_estacao, partida),
+            };
+        }
+        horario
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visitar<'a>(grafo: &'a GrafoMetro, quadro: &QuadroHorarios, linha_inicial: Option<IdLinha>, id_estacao: IdEstacao, objetivo: IdEstacao,
+                   chegada: f32, trechos: &mut Vec<(IdEstacao, &'a Conexao)>, visitadas: &mut Vec<IdEstacao>, melhor: &mut f32) {
+        if id_estacao == objetivo {
+            *melhor = melhor.max(partida_da_rota(grafo, quadro, linha_inicial, trechos, chegada));
+            return;
+        }
+        for conexao in &grafo.lista_adjacencia[id_estacao] {
+            if visitadas.contains(&conexao.para_estacao) {
+                continue;
+            }
+            visitadas.push(conexao.para_estacao);
+            trechos.push((id_estacao, conexao));
+            visitar(grafo, quadro, linha_inicial, conexao.para_estacao, objetivo, chegada, trechos, visitadas, melhor);
+            trechos.pop();
+            visitadas.pop();
+        }
+    }
+
+    let mut melhor = f32::NEG_INFINITY;
+    visitar(grafo, quadro, linha, inicio, objetivo, chegada, &mut Vec::new(), &mut vec![inicio], &mut melhor);
+    melhor
+}
+
+fn verificar_itinerario(caminho: &InfoCaminho, inicio: IdEstacao, objetivo: IdEstacao, chegada: f32) {
+    assert_eq!(caminho.estacoes_do_caminho.first().map(|&(id, _)| id), Some(inicio));
+    assert_eq!(caminho.estacoes_do_caminho.last().map(|&(id, _)| id), Some(objetivo));
+    let partida = caminho.horario_partida.unwrap();
+    assert!(partida + caminho.tempo_total_minutos <= chegada + 0.01);
+    assert_eq!(caminho.horarios_trechos.len(), caminho.tempos_trechos_minutos.len());
+}
+
+#[test]
+fn partida_mais_tarde_bate_com_a_forca_bruta_e_com_a_ida() {
+    let grafo = rede_de_exemplo();
+    let quadro = quadro_de_exemplo(&grafo);
+    let linhas = linhas_de_partida(&grafo);
+
+    for chegada in ["06:20", "09:00", "00:50"].map(horario) {
+        for inicio in 0..grafo.numero_estacoes() {
+            for objetivo in (0..grafo.numero_estacoes()).filter(|&objetivo| objetivo != inicio) {
+                for &linha in &linhas {
+                    let contexto = format!("{} E{} -> E{} (linha {:?})", formatar_horario(chegada), inicio + 1, objetivo + 1, linha);
+                    let caminho = buscar_partida_mais_tarde(&grafo, &quadro, inicio, linha, objetivo, chegada).caminho.unwrap();
+                    verificar_itinerario(&caminho, inicio, objetivo, chegada);
+
+                    let partida = caminho.horario_partida.unwrap();
+                    let esperada = partida_mais_tarde_por_forca_bruta(&grafo, &quadro, inicio, linha, objetivo, chegada);
+                    assert!((partida - esperada).abs() < 0.01, "{}: {} != {}", contexto, partida, esperada);
+
+                    // Partindo nesse horário a ida chega a tempo; meio minuto depois, já não chega
+                    let consulta = |horario_partida| ConsultaHorarios { quadro: Arc::clone(&quadro), horario_partida };
+                    assert!(chegada_partindo_as(&grafo, consulta(partida), inicio, linha, objetivo) <= chegada + 0.01, "{}", contexto);
+                    assert!(chegada_partindo_as(&grafo, consulta(partida + 0.5), inicio, linha, objetivo) > chegada, "{}", contexto);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn chegar_a_place_d_italie_ate_as_nove() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+
+    // Sem quadro de horários não há espera: parte-se exatamente o tempo da rota mais rápida antes
+    let sem_horarios = buscar_partida_mais_tarde(&grafo, &Arc::new(QuadroHorarios::novo()), inicio, None, objetivo, horario("09:00"));
+    let caminho = sem_horarios.caminho.unwrap();
+    assert!((caminho.horario_partida.unwrap() - (horario("09:00") - 61.6)).abs() < 0.01);
+    assert_eq!(caminho.espera_total_minutos(), 0.0);
+    assert!(sem_horarios.estados_expandidos > 0);
+
+    // Com o quadro, a partida é o horário de um trem da linha Azul (a cada 2 min no pico, desde 07:00)
+    let caminho = buscar_partida_mais_tarde(&grafo, &quadro_de_exemplo(&grafo), inicio, None, objetivo, horario("09:00")).caminho.unwrap();
+    let partida = caminho.horario_partida.unwrap();
+    assert_eq!(((partida - horario("07:00")) % 2.0).abs(), 0.0);
+    assert_eq!(caminho.horarios_trechos[0].espera_minutos, 0.0);
+    assert!(partida < horario("09:00") - 61.6);
+}
@@ -0,0 +1,61 @@
+//! Funções compartilhadas pelos testes de integração; cada arquivo de teste usa só parte delas
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{InfoCaminho, ResultadoPassoAEstrela, SolucionadorAEstrela};
+use metro_paris_astar::dados_metro::FonteRede;
+use metro_paris_astar::erro_carregamento::ModoCarregamento;
+use metro_paris_astar::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+use metro_paris_astar::quadro_horarios::{interpretar_horario, QuadroHorarios};
+
+/// A rede de 14 estações das tabelas CSV em data/
+pub fn rede_de_exemplo() -> Arc<GrafoMetro> {
+    let diretorio = concat!(env!("CARGO_MANIFEST_DIR"), "/data").to_string();
+    let (grafo, _) = FonteRede::TabelasCsv(diretorio).carregar(ModoCarregamento::Tolerante).unwrap();
+    Arc::new(grafo)
+}
+
+/// O quadro de horários de data/horarios_exemplo.toml, que carrega sem avisos
+pub fn quadro_de_exemplo(grafo: &GrafoMetro) -> Arc<QuadroHorarios> {
+    let caminho = concat!(env!("CARGO_MANIFEST_DIR"), "/data/horarios_exemplo.toml");
+    let (quadro, avisos) = QuadroHorarios::carregar(grafo, caminho, ModoCarregamento::Estrito).unwrap();
+    assert!(avisos.is_empty());
+    Arc::new(quadro)
+}
+
+pub fn horario(texto: &str) -> f32 {
+    interpretar_horario(texto).unwrap()
+}
+
+/// Sem linha e cada uma das linhas do grafo, para testar todas as linhas de partida
+pub fn linhas_de_partida(grafo: &GrafoMetro) -> Vec<Option<IdLinha>> {
+    std::iter::once(None).chain((0..grafo.linhas.len()).map(Some)).collect()
+}
+
+/// Roda o solucionador até o fim; `None` se não há caminho
+pub fn executar_ate_o_fim(solucionador: &mut SolucionadorAEstrela) -> Option<InfoCaminho> {
+    loop {
+        match solucionador.proximo_passo() {
+            ResultadoPassoAEstrela::EmProgresso => continue,
+            ResultadoPassoAEstrela::CaminhoEncontrado(caminho) => return Some(caminho),
+            _ => return None,
+        }
+    }
+}
+
+pub fn rota_a_estrela(grafo: &Arc<GrafoMetro>, inicio: IdEstacao, linha: Option<IdLinha>, objetivo: IdEstacao) -> Option<InfoCaminho> {
+    executar_ate_o_fim(&mut SolucionadorAEstrela::novo(Arc::clone(grafo), inicio, linha, objetivo))
+}
+
+/// O tempo informado tem que bater com a soma dos trechos e das baldeações do itinerário
+pub fn tempo_do_itinerario(grafo: &GrafoMetro, caminho: &InfoCaminho) -> f32 {
+    let mut linha_anterior = caminho.linha_partida;
+    let mut tempo: f32 = caminho.tempos_trechos_minutos.iter().sum();
+    for (indice, &(_, linha)) in caminho.estacoes_do_caminho.iter().enumerate().skip(1) {
+        if linha_anterior.is_some() && linha_anterior != linha {
+            tempo += grafo.tempo_baldeacao_em(caminho.estacoes_do_caminho[indice - 1].0);
+        }
+        linha_anterior = linha;
+    }
+    tempo
+}
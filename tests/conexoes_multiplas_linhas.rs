@@ -0,0 +1,53 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::InfoCaminho;
+use metro_paris_astar::grafo_metro::{Conexao, GrafoMetro, IdEstacao, IdLinha};
+use common::rota_a_estrela;
+
+/// A -> B -> C, com A-B servido por duas linhas. A primeira conexão listada (Lenta) é a pior:
+/// a rota certa segue pela Rápida até C, sem baldeação.
+fn grafo_com_trecho_em_duas_linhas() -> (GrafoMetro, IdLinha, IdLinha) {
+    let mut grafo = GrafoMetro::novo();
+    let (a, b, c) = (grafo.adicionar_estacao("A"), grafo.adicionar_estacao("B"), grafo.adicionar_estacao("C"));
+    let lenta = grafo.adicionar_linha("L", "Lenta", [200, 0, 0]);
+    let rapida = grafo.adicionar_linha("R", "Rápida", [0, 0, 200]);
+
+    let mut conectar = |de: IdEstacao, para, linha, tempo_minutos| {
+        grafo.lista_adjacencia[de].push(Conexao { para_estacao: para, linha, distancia_km: 1.0, tempo_minutos });
+    };
+    conectar(a, b, lenta, 10.0);
+    conectar(a, b, rapida, 6.0);
+    conectar(b, c, rapida, 5.0);
+
+    (grafo, lenta, rapida)
+}
+
+fn buscar(grafo: GrafoMetro, linha_inicial: Option<IdLinha>) -> InfoCaminho {
+    let (inicio, objetivo) = (grafo.obter_id_estacao("A").unwrap(), grafo.obter_id_estacao("C").unwrap());
+    rota_a_estrela(&Arc::new(grafo), inicio, linha_inicial, objetivo).expect("há caminho de A a C")
+}
+
+#[test]
+fn itinerario_usa_a_segunda_linha_do_trecho_quando_ela_e_a_escolhida() {
+    let (grafo, _, rapida) = grafo_com_trecho_em_duas_linhas();
+    let caminho = buscar(grafo, None);
+
+    assert_eq!(caminho.estacoes_do_caminho, vec![(0, None), (1, Some(rapida)), (2, Some(rapida))]);
+    assert_eq!(caminho.linha_partida, None);
+    assert_eq!(caminho.tempos_trechos_minutos, vec![6.0, 5.0]);
+    assert_eq!(caminho.baldeacoes, 0);
+    assert!((caminho.tempo_total_minutos - 11.0).abs() < 0.01);
+}
+
+#[test]
+fn baldeacao_na_origem_conta_quando_a_linha_inicial_e_outra() {
+    let (grafo, lenta, rapida) = grafo_com_trecho_em_duas_linhas();
+    let caminho = buscar(grafo, Some(lenta));
+
+    // Trocar já na origem (4 + 6 + 5) ainda é melhor que seguir na Lenta e trocar em B (10 + 4 + 5)
+    assert_eq!(caminho.estacoes_do_caminho, vec![(0, None), (1, Some(rapida)), (2, Some(rapida))]);
+    assert_eq!(caminho.linha_partida, Some(lenta));
+    assert_eq!(caminho.baldeacoes, 1);
+    assert!((caminho.tempo_total_minutos - 15.0).abs() < 0.01);
+}
@@ -0,0 +1,34 @@
+use metro_paris_astar::grafo_metro::{distancia_entre_coordenadas_km, Coordenadas, GrafoMetro};
+
+const ETOILE: Coordenadas = Coordenadas::Geograficas { latitude: 48.8738, longitude: 2.2950 };
+const NATION: Coordenadas = Coordenadas::Geograficas { latitude: 48.8483, longitude: 2.3959 };
+
+#[test]
+fn haversine_entre_etoile_e_nation() {
+    // Cerca de 7,9 km em linha reta entre as duas pontas da linha 2 do metrô
+    let distancia = distancia_entre_coordenadas_km(ETOILE, NATION).unwrap();
+    assert!((distancia - 7.907).abs() < 0.01, "{}", distancia);
+    assert_eq!(distancia_entre_coordenadas_km(NATION, ETOILE), Some(distancia));
+    assert_eq!(distancia_entre_coordenadas_km(ETOILE, ETOILE), Some(0.0));
+}
+
+#[test]
+fn distancia_plana_e_euclidiana() {
+    let origem = Coordenadas::Planas { x_km: 1.0, y_km: 2.0 };
+    let destino = Coordenadas::Planas { x_km: 4.0, y_km: 6.0 };
+    assert_eq!(distancia_entre_coordenadas_km(origem, destino), Some(5.0));
+
+    // Coordenadas geográficas e planas não se comparam
+    assert_eq!(distancia_entre_coordenadas_km(origem, ETOILE), None);
+}
+
+#[test]
+fn grafo_sem_tabela_usa_as_coordenadas_das_estacoes() {
+    let mut grafo = GrafoMetro::novo();
+    let [etoile, nation, sem_posicao] = ["E", "N", "X"].map(|codigo| grafo.adicionar_estacao(codigo));
+    grafo.estacoes[etoile].coordenadas = Some(ETOILE);
+    grafo.estacoes[nation].coordenadas = Some(NATION);
+
+    assert_eq!(grafo.distancia_direta_km(etoile, nation), distancia_entre_coordenadas_km(ETOILE, NATION));
+    assert_eq!(grafo.distancia_direta_km(etoile, sem_posicao), None);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{EventoVisual, InfoCaminho, SolucionadorAEstrela};
+use metro_paris_astar::estrategia_busca::EstrategiaBusca;
+use metro_paris_astar::grafo_metro::{Conexao, GrafoMetro};
+use common::{executar_ate_o_fim, rede_de_exemplo};
+
+/// Rota de E1 a E14 e número de estados expandidos com a estratégia dada
+fn buscar(grafo: &Arc<GrafoMetro>, estrategia: EstrategiaBusca) -> (InfoCaminho, usize) {
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E1").unwrap(), grafo.obter_id_estacao("E14").unwrap());
+    let mut solucionador = SolucionadorAEstrela::novo(Arc::clone(grafo), inicio, None, objetivo).com_estrategia(estrategia);
+    let caminho = executar_ate_o_fim(&mut solucionador)
+        .unwrap_or_else(|| panic!("a rede de exemplo tem caminho de E1 a E14 ({})", estrategia));
+    (caminho, solucionador.explorados.len())
+}
+
+#[test]
+fn dijkstra_encontra_a_mesma_rota_do_a_estrela_expandindo_mais() {
+    let grafo = rede_de_exemplo();
+    let (rota_a_estrela, expandidos_a_estrela) = buscar(&grafo, EstrategiaBusca::AEstrela);
+    let (rota_dijkstra, expandidos_dijkstra) = buscar(&grafo, EstrategiaBusca::Dijkstra);
+
+    assert!((rota_a_estrela.tempo_total_minutos - 89.4).abs() < 0.01);
+    assert!((rota_dijkstra.tempo_total_minutos - rota_a_estrela.tempo_total_minutos).abs() < 0.01);
+    assert!(expandidos_dijkstra > expandidos_a_estrela);
+}
+
+#[test]
+fn a_estrela_ponderado_com_epsilon_1_e_o_proprio_a_estrela() {
+    let grafo = rede_de_exemplo();
+    let (rota_a_estrela, expandidos_a_estrela) = buscar(&grafo, EstrategiaBusca::AEstrela);
+    let (rota_ponderada, expandidos_ponderado) = buscar(&grafo, EstrategiaBusca::AEstrelaPonderado { epsilon: 1.0 });
+
+    assert_eq!(rota_ponderada.estacoes_do_caminho, rota_a_estrela.estacoes_do_caminho);
+    assert_eq!(expandidos_ponderado, expandidos_a_estrela);
+}
+
+#[test]
+fn estrategias_sem_garantia_encontram_rota_dentro_do_limite_esperado() {
+    let grafo = rede_de_exemplo();
+    let (otima, _) = buscar(&grafo, EstrategiaBusca::AEstrela);
+
+    let epsilon = 2.0;
+    let (ponderada, _) = buscar(&grafo, EstrategiaBusca::AEstrelaPonderado { epsilon });
+    assert!(ponderada.tempo_total_minutos <= epsilon * otima.tempo_total_minutos);
+
+    // A busca em largura minimiza trechos, não tempo
+    let (por_trechos, _) = buscar(&grafo, EstrategiaBusca::Largura);
+    assert!(por_trechos.estacoes_do_caminho.len() <= otima.estacoes_do_caminho.len());
+
+    let (gulosa, _) = buscar(&grafo, EstrategiaBusca::GulosaMelhorPrimeiro);
+    assert!(gulosa.tempo_total_minutos >= otima.tempo_total_minutos);
+}
+
+#[test]
+fn vizinho_ja_explorado_mostra_o_f_do_proprio_estado() {
+    // Triângulo A, B, C numa linha só, com o objetivo D inalcançável para a busca explorar tudo.
+    // B e C ficam a um trecho de A; são vistos já explorados de C e de A (chegando pela linha)
+    let mut grafo = GrafoMetro::novo();
+    let [a, b, c, d] = ["A", "B", "C", "D"].map(|codigo| grafo.adicionar_estacao(codigo));
+    let linha = grafo.adicionar_linha("L1", "Um", [200, 0, 0]);
+    for (de, para) in [(a, b), (b, a), (a, c), (c, a), (b, c), (c, b)] {
+        grafo.lista_adjacencia[de].push(Conexao { para_estacao: para, linha, distancia_km: 1.0, tempo_minutos: 2.0 });
+    }
+    let mut solucionador = SolucionadorAEstrela::novo(Arc::new(grafo), a, None, d).com_estrategia(EstrategiaBusca::Largura);
+
+    let mut vistos_ja_explorados = 0;
+    loop {
+        match solucionador.proximo_evento() {
+            EventoVisual::VizinhoJaExplorado { .. } => {
+                // Na busca em largura f é o número de trechos: 1 para B e C, não o do caminho pelo nó atual
+                let descricao = solucionador.ultima_analise.as_ref().unwrap().vizinhos_analisados.last().unwrap().clone();
+                assert!(descricao.ends_with("f=1.0 - JÁ EXPLORADO"), "{}", descricao);
+                vistos_ja_explorados += 1;
+            }
+            EventoVisual::CaminhoEncontrado { .. } | EventoVisual::NenhumCaminho => break,
+            _ => {}
+        }
+    }
+    assert_eq!(vistos_ja_explorados, 3);
+}
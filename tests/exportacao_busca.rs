@@ -0,0 +1,52 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{EventoVisual, SolucionadorAEstrela};
+use metro_paris_astar::exportacao_busca::{coletar_eventos, escrever_jsonl};
+use serde_json::{json, Value};
+use common::rede_de_exemplo;
+
+/// Escreve os eventos em JSON Lines e lê cada linha de volta
+fn eventos_relidos(eventos: &[EventoVisual]) -> Vec<Value> {
+    let mut saida = Vec::new();
+    escrever_jsonl(eventos, &mut saida).unwrap();
+    String::from_utf8(saida).unwrap().lines()
+        .map(|linha| serde_json::from_str(linha).unwrap())
+        .collect()
+}
+
+#[test]
+fn evento_segue_o_formato_documentado() {
+    let relidos = eventos_relidos(&[EventoVisual::VerificandoSeEhObjetivo { id_estacao: 3, eh_objetivo: false }, EventoVisual::NenhumCaminho]);
+    assert_eq!(relidos, [json!({"tipo": "VerificandoSeEhObjetivo", "id_estacao": 3, "eh_objetivo": false}), json!({"tipo": "NenhumCaminho"})]);
+}
+
+#[test]
+fn eventos_da_busca_relidos_trazem_o_tipo_e_o_caminho_encontrado() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+    let eventos = coletar_eventos(&mut SolucionadorAEstrela::novo(Arc::clone(&grafo), inicio, None, objetivo));
+    let relidos = eventos_relidos(&eventos);
+    assert_eq!(relidos.len(), eventos.len());
+
+    // Cada linha é o objeto da variante com o nome dela em "tipo"
+    for (evento, relido) in eventos.iter().zip(&relidos) {
+        let nome_variante = format!("{:?}", evento).split([' ', '{']).next().unwrap().to_string();
+        assert_eq!(relido["tipo"], nome_variante);
+    }
+
+    let EventoVisual::CaminhoEncontrado { info_caminho } = eventos.last().unwrap() else {
+        panic!("a busca termina encontrando o caminho");
+    };
+    let caminho_encontrado = relidos.last().unwrap();
+    assert_eq!(caminho_encontrado["tipo"], "CaminhoEncontrado");
+    let relido = &caminho_encontrado["info_caminho"];
+    assert_eq!(relido["estacoes_do_caminho"][0], json!([inicio, null]));
+    assert_eq!(relido["estacoes_do_caminho"].as_array().unwrap().len(), info_caminho.estacoes_do_caminho.len());
+    assert_eq!(relido["linha_partida"], Value::Null);
+    assert_eq!(relido["baldeacoes"], info_caminho.baldeacoes);
+    assert!((relido["tempo_total_minutos"].as_f64().unwrap() - 61.6).abs() < 0.01);
+    assert_eq!(relido["horario_partida"], Value::Null);
+    assert_eq!(relido["horarios_trechos"], json!([]));
+    assert_eq!(relido["tempos_trechos_minutos"].as_array().unwrap().len(), info_caminho.tempos_trechos_minutos.len());
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::analise_heuristica::{analisar_fonte_heuristica, comparar_heuristicas};
+use metro_paris_astar::grafo_metro::GrafoMetro;
+use metro_paris_astar::heuristica_alt::{FonteHeuristica, HeuristicaAlt, SelecaoMarcos};
+use common::rede_de_exemplo;
+
+fn fonte_alt(grafo: &GrafoMetro, selecao: &SelecaoMarcos) -> FonteHeuristica {
+    FonteHeuristica::Alt(Arc::new(HeuristicaAlt::calcular(grafo, selecao)))
+}
+
+#[test]
+fn alt_e_admissivel_e_consistente_com_qualquer_selecao_de_marcos() {
+    let grafo = rede_de_exemplo();
+    let selecoes = [
+        SelecaoMarcos::MaisDistantes { quantidade: 4 },
+        SelecaoMarcos::Aleatorios { quantidade: 3, semente: 42 },
+        SelecaoMarcos::Manuais(vec![grafo.obter_id_estacao("E7").unwrap()]),
+    ];
+
+    for selecao in &selecoes {
+        let analise = analisar_fonte_heuristica(&grafo, &fonte_alt(&grafo, selecao));
+        assert!(analise.eh_admissivel() && analise.eh_consistente(), "marcos {}:\n{}", selecao, analise);
+        assert!(analise.inadmissiveis_sem_baldeacao.is_empty());
+    }
+}
+
+#[test]
+fn marcos_mais_distantes_encontram_as_mesmas_rotas_expandindo_menos() {
+    let grafo = rede_de_exemplo();
+    let comparacao = comparar_heuristicas(&grafo, &FonteHeuristica::LinhaReta, &fonte_alt(&grafo, &SelecaoMarcos::default()));
+
+    assert_eq!(comparacao.pares_avaliados, grafo.numero_estacoes() * (grafo.numero_estacoes() - 1));
+    assert_eq!(comparacao.pares_com_tempo_diferente, 0);
+    assert!(comparacao.expandidos_alternativa < comparacao.expandidos_base, "{}", comparacao);
+}
+
+#[test]
+fn selecao_de_marcos() {
+    let grafo = rede_de_exemplo();
+
+    let sorteio = |semente| SelecaoMarcos::Aleatorios { quantidade: 5, semente }.escolher(&grafo);
+    assert_eq!(sorteio(7), sorteio(7));
+    let mut marcos = sorteio(7);
+    marcos.sort();
+    marcos.dedup();
+    assert_eq!(marcos.len(), 5);
+
+    let todas = SelecaoMarcos::MaisDistantes { quantidade: 100 }.escolher(&grafo);
+    assert_eq!(todas.len(), grafo.numero_estacoes());
+
+    let manuais = SelecaoMarcos::de_texto(&grafo, "E1, E14,E1").unwrap();
+    assert_eq!(manuais.escolher(&grafo), vec![0, 13]);
+    assert_eq!(SelecaoMarcos::de_texto(&grafo, "aleatorios:2:9").unwrap(), SelecaoMarcos::Aleatorios { quantidade: 2, semente: 9 });
+    assert_eq!(SelecaoMarcos::de_texto(&grafo, "mais-distantes").unwrap(), SelecaoMarcos::default());
+    assert!(SelecaoMarcos::de_texto(&grafo, "E1,E99").is_err());
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use metro_paris_astar::erro_carregamento::{AvisoCarregamento, ErroCarregamento, ModoCarregamento};
+use metro_paris_astar::grafo_metro::{GrafoMetro, ModoTransporte};
+use metro_paris_astar::importador_gtfs::CODIGO_LINHA_A_PE;
+use common::rota_a_estrela;
+
+const ARQUIVOS_FEED: [&str; 5] = ["stops.txt", "routes.txt", "trips.txt", "stop_times.txt", "transfers.txt"];
+
+fn diretorio_feed() -> PathBuf {
+    PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/dados/gtfs_exemplo"))
+}
+
+/// Compacta o feed de exemplo dentro de uma pasta do zip, como fazem alguns operadores
+fn feed_compactado() -> PathBuf {
+    let caminho = std::env::temp_dir().join(format!("gtfs_exemplo_{}.zip", std::process::id()));
+    let mut zip = zip::ZipWriter::new(File::create(&caminho).unwrap());
+    for nome in ARQUIVOS_FEED {
+        zip.start_file(format!("feed/{}", nome), zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(&std::fs::read(diretorio_feed().join(nome)).unwrap()).unwrap();
+    }
+    zip.finish().unwrap();
+    caminho
+}
+
+fn carregar(caminho: &Path) -> GrafoMetro {
+    let mut grafo = GrafoMetro::novo();
+    let avisos = grafo.carregar_gtfs(caminho.to_str().unwrap(), ModoCarregamento::Estrito).unwrap();
+    assert!(avisos.is_empty());
+    grafo
+}
+
+/// Tempo da conexão entre duas estações (pelo stop_id) na linha (pelo route_id)
+fn tempo_conexao(grafo: &GrafoMetro, de: &str, para: &str, linha: &str) -> Option<f32> {
+    let (de, para, linha) = (grafo.obter_id_estacao(de)?, grafo.obter_id_estacao(para)?, grafo.obter_id_linha(linha)?);
+    grafo.lista_adjacencia[de].iter()
+        .find(|conexao| conexao.para_estacao == para && conexao.linha == linha)
+        .map(|conexao| conexao.tempo_minutos)
+}
+
+fn verificar_feed_de_exemplo(grafo: &GrafoMetro) {
+    // As plataformas P1a e P1b ficam agrupadas na estação-mãe S1
+    assert_eq!(grafo.numero_estacoes(), 5);
+    assert_eq!(grafo.obter_id_estacao("P1a"), None);
+    let alfa = grafo.obter_id_estacao("S1").unwrap();
+    assert_eq!(grafo.estacoes[alfa].nome, "Alfa");
+
+    // Cada rota vira uma linha, com nome, cor e modo da própria rota
+    let metro = &grafo.linhas[grafo.obter_id_linha("R1").unwrap()];
+    assert_eq!((metro.nome.as_str(), metro.cor_rgb, metro.modo), ("1", [0x00, 0x55, 0xC8], ModoTransporte::Metro));
+    let onibus = &grafo.linhas[grafo.obter_id_linha("R2").unwrap()];
+    assert_eq!((onibus.nome.as_str(), onibus.modo), ("Alfa - Épsilon", ModoTransporte::Onibus));
+
+    // Média das duas viagens (4 e 5 min); a plataforma 2 de Alfa também é Alfa
+    assert!((tempo_conexao(grafo, "S1", "S2", "R1").unwrap() - 4.5).abs() < 0.01);
+    assert!((tempo_conexao(grafo, "S1", "S5", "R2").unwrap() - 10.0).abs() < 0.01);
+
+    // Gama não tem horário: os 10 min de Beta a Delta se dividem pela distância (1:2)
+    let (beta_gama, gama_delta) = (tempo_conexao(grafo, "S2", "S3", "R1").unwrap(), tempo_conexao(grafo, "S3", "S4", "R1").unwrap());
+    assert!((beta_gama + gama_delta - 10.0).abs() < 0.01);
+    assert!((beta_gama - 10.0 / 3.0).abs() < 0.05, "{}", beta_gama);
+
+    // transfers.txt: min_transfer_time em segundos, ou a penalidade do grafo; dentro da mesma estação, nada
+    assert!((tempo_conexao(grafo, "S4", "S5", CODIGO_LINHA_A_PE).unwrap() - 3.0).abs() < 0.01);
+    assert_eq!(tempo_conexao(grafo, "S5", "S4", CODIGO_LINHA_A_PE), Some(grafo.tempo_baldeacao_minutos));
+    assert!(grafo.lista_adjacencia[alfa].iter().all(|conexao| conexao.para_estacao != alfa));
+}
+
+#[test]
+fn feed_em_diretorio() {
+    let grafo = Arc::new(carregar(&diretorio_feed()));
+    verificar_feed_de_exemplo(&grafo);
+
+    // Passando por Gama, que só existe graças à interpolação
+    let (alfa, delta) = (grafo.obter_id_estacao("S1").unwrap(), grafo.obter_id_estacao("S4").unwrap());
+    let caminho = rota_a_estrela(&grafo, alfa, None, delta).unwrap();
+    assert_eq!(caminho.estacoes_do_caminho.len(), 4);
+    assert!((caminho.tempo_total_minutos - 14.5).abs() < 0.01);
+}
+
+#[test]
+fn feed_compactado_em_zip() {
+    let caminho = feed_compactado();
+    let grafo = carregar(&caminho);
+    std::fs::remove_file(&caminho).unwrap();
+    verificar_feed_de_exemplo(&grafo);
+}
+
+/// Cópia do feed de exemplo num diretório temporário, com linhas a mais em alguns arquivos
+fn feed_com_linhas_extras(nome: &str, extras: &[(&str, &str)]) -> PathBuf {
+    let diretorio = std::env::temp_dir().join(format!("{}_{}", nome, std::process::id()));
+    std::fs::create_dir_all(&diretorio).unwrap();
+    for arquivo in ARQUIVOS_FEED {
+        let mut conteudo = std::fs::read_to_string(diretorio_feed().join(arquivo)).unwrap();
+        for (_, linha) in extras.iter().filter(|(destino, _)| *destino == arquivo) {
+            conteudo.push_str(linha);
+            conteudo.push('\n');
+        }
+        std::fs::write(diretorio.join(arquivo), conteudo).unwrap();
+    }
+    diretorio
+}
+
+fn descrever(aviso: &AvisoCarregamento) -> String {
+    match aviso {
+        AvisoCarregamento::ValorInvalido { local, valor, .. } =>
+            format!("{}:{} {} = '{}'", local.arquivo, local.linha.unwrap(), local.coluna.as_deref().unwrap(), valor),
+        AvisoCarregamento::EstacaoDesconhecida { local, codigo } =>
+            format!("{}:{} {} ? '{}'", local.arquivo, local.linha.unwrap(), local.coluna.as_deref().unwrap(), codigo),
+        outro => panic!("aviso inesperado: {}", outro),
+    }
+}
+
+#[test]
+fn linhas_com_referencias_invalidas_viram_avisos() {
+    let diretorio = feed_com_linhas_extras("gtfs_invalido", &[
+        ("trips.txt", "R9,semana,T9"),
+        ("stop_times.txt", "T9,10:00:00,10:00:00,S1,1"),
+        ("stop_times.txt", "T7,10:00:00,10:00:00,S1,1"),
+        ("stop_times.txt", "T1,08:20:00,08:20:00,S99,5"),
+        ("stop_times.txt", "T2,09:10:00,09:10:00,S3,x"),
+        ("stop_times.txt", "T2,9h15,,S4,4"),
+        ("transfers.txt", "S4,S99,2,60"),
+        ("transfers.txt", "S2,S3,2,abc"),
+    ]);
+    let caminho = diretorio.to_str().unwrap();
+
+    let mut grafo = GrafoMetro::novo();
+    let avisos: Vec<String> = grafo.carregar_gtfs(caminho, ModoCarregamento::Tolerante).unwrap().iter().map(descrever).collect();
+    // Os horários da viagem T9 não repetem o aviso da rota desconhecida; o horário ilegível de T2
+    // deixa a última parada da viagem sem horário
+    assert_eq!(avisos, [
+        "trips.txt:5 route_id = 'R9'",
+        "stop_times.txt:11 trip_id = 'T7'",
+        "stop_times.txt:12 stop_id ? 'S99'",
+        "stop_times.txt:13 stop_sequence = 'x'",
+        "stop_times.txt:14 arrival_time = '9h15'",
+        "stop_times.txt:14 arrival_time = ''",
+        "transfers.txt:5 to_stop_id ? 'S99'",
+        "transfers.txt:6 min_transfer_time = 'abc'",
+    ]);
+    // O resto do feed continua carregado
+    assert!((tempo_conexao(&grafo, "S1", "S2", "R1").unwrap() - 4.5).abs() < 0.01);
+
+    let erro = GrafoMetro::novo().carregar_gtfs(caminho, ModoCarregamento::Estrito).unwrap_err();
+    assert!(matches!(&erro, ErroCarregamento::Inconsistencia(aviso) if descrever(aviso) == "trips.txt:5 route_id = 'R9'"));
+    std::fs::remove_dir_all(&diretorio).unwrap();
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{EventoVisual, SolucionadorAEstrela};
+use metro_paris_astar::grafo_metro::{Conexao, GrafoMetro};
+use metro_paris_astar::observador_busca::ObservadorBusca;
+use common::executar_ate_o_fim;
+
+/// Guarda cada evento como "Tipo Estação" numa lista compartilhada com o teste
+#[derive(Debug, Default)]
+struct ObservadorGravador {
+    eventos: Rc<RefCell<Vec<String>>>,
+}
+
+impl ObservadorBusca for ObservadorGravador {
+    fn notificar(&mut self, grafo: &GrafoMetro, evento: &EventoVisual) {
+        let id_estacao = match evento {
+            EventoVisual::AlgoritmoInicializado { id_inicio: id, .. }
+            | EventoVisual::NoEscolhidoDaFronteira { id_estacao: id, .. }
+            | EventoVisual::VerificandoSeEhObjetivo { id_estacao: id, .. }
+            | EventoVisual::InicioDaExpansaoDeNo { id_estacao: id, .. }
+            | EventoVisual::FimDaExpansaoDeNo { id_estacao: id, .. }
+            | EventoVisual::AvaliandoVizinho { id_vizinho: id, .. }
+            | EventoVisual::VizinhoJaExplorado { id_vizinho: id }
+            | EventoVisual::VizinhoJaTemCaminhoMelhor { id_vizinho: id, .. }
+            | EventoVisual::VizinhoAdicionadoNaFronteira { id_vizinho: id, .. } => Some(*id),
+            EventoVisual::CaminhoEncontrado { info_caminho } => info_caminho.estacoes_do_caminho.last().map(|&(id, _)| id),
+            EventoVisual::NenhumCaminho => None,
+        };
+        let tipo = serde_json::to_value(evento).unwrap()["tipo"].as_str().unwrap().to_string();
+        self.eventos.borrow_mut().push(match id_estacao {
+            Some(id) => format!("{} {}", tipo, grafo.estacoes[id].codigo),
+            None => tipo,
+        });
+    }
+}
+
+/// A -> B -> C numa linha só, sem volta, para a ordem dos eventos não depender de empates na fronteira
+fn solucionador_gravado() -> (SolucionadorAEstrela, Rc<RefCell<Vec<String>>>) {
+    let mut grafo = GrafoMetro::novo();
+    let [a, b, c] = ["A", "B", "C"].map(|codigo| grafo.adicionar_estacao(codigo));
+    let linha = grafo.adicionar_linha("L1", "Um", [200, 0, 0]);
+    for (de, para) in [(a, b), (b, c)] {
+        grafo.lista_adjacencia[de].push(Conexao { para_estacao: para, linha, distancia_km: 1.0, tempo_minutos: 2.0 });
+    }
+    let observador = ObservadorGravador::default();
+    let eventos = Rc::clone(&observador.eventos);
+    (SolucionadorAEstrela::novo(Arc::new(grafo), a, None, c).com_observador(Box::new(observador)), eventos)
+}
+
+const SEQUENCIA_ESPERADA: [&str; 16] = [
+    "AlgoritmoInicializado A",
+    "NoEscolhidoDaFronteira A",
+    "VerificandoSeEhObjetivo A",
+    "InicioDaExpansaoDeNo A",
+    "AvaliandoVizinho B",
+    "VizinhoAdicionadoNaFronteira B",
+    "FimDaExpansaoDeNo A",
+    "NoEscolhidoDaFronteira B",
+    "VerificandoSeEhObjetivo B",
+    "InicioDaExpansaoDeNo B",
+    "AvaliandoVizinho C",
+    "VizinhoAdicionadoNaFronteira C",
+    "FimDaExpansaoDeNo B",
+    "NoEscolhidoDaFronteira C",
+    "VerificandoSeEhObjetivo C",
+    "CaminhoEncontrado C",
+];
+
+#[test]
+fn observador_recebe_os_eventos_em_ordem() {
+    let (mut solucionador, eventos) = solucionador_gravado();
+    while !matches!(solucionador.proximo_evento(), EventoVisual::CaminhoEncontrado { .. } | EventoVisual::NenhumCaminho) {}
+
+    assert_eq!(*eventos.borrow(), SEQUENCIA_ESPERADA);
+}
+
+#[test]
+fn passos_completos_notificam_a_mesma_sequencia() {
+    let (mut solucionador, eventos) = solucionador_gravado();
+    assert!(executar_ate_o_fim(&mut solucionador).is_some());
+
+    assert_eq!(*eventos.borrow(), SEQUENCIA_ESPERADA);
+}
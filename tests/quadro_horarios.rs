@@ -0,0 +1,167 @@
+mod common;
+
+use std::sync::Arc;
+use metro_paris_astar::algoritmo_a_estrela::{InfoCaminho, SolucionadorAEstrela};
+use metro_paris_astar::arquivo_rede::FormatoArquivoRede;
+use metro_paris_astar::erro_carregamento::{AvisoCarregamento, ModoCarregamento};
+use metro_paris_astar::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+use metro_paris_astar::quadro_horarios::{formatar_horario, interpretar_horario, ConsultaHorarios, QuadroHorarios};
+use common::{executar_ate_o_fim, horario, linhas_de_partida, quadro_de_exemplo, rede_de_exemplo};
+
+fn rota_com_horario(grafo: &Arc<GrafoMetro>, consulta: ConsultaHorarios, inicio: IdEstacao, linha: Option<IdLinha>, objetivo: IdEstacao) -> InfoCaminho {
+    let mut solucionador = SolucionadorAEstrela::novo(Arc::clone(grafo), inicio, linha, objetivo).com_horarios(consulta);
+    executar_ate_o_fim(&mut solucionador).expect("a rede de exemplo é conexa")
+}
+
+/// Menor tempo até o objetivo entre todas as rotas sem estação repetida, simulando a espera
+/// a cada embarque (a rede de exemplo é pequena)
+fn menor_tempo_por_forca_bruta(grafo: &GrafoMetro, consulta: &ConsultaHorarios, inicio: IdEstacao, linha: Option<IdLinha>, objetivo: IdEstacao) -> f32 {
+    fn visitar(grafo: &GrafoMetro, consulta: &ConsultaHorarios, (id_estacao, linha): (IdEstacao, Option<IdLinha>), objetivo: IdEstacao,
+               decorrido: f32, visitadas: &mut Vec<IdEstacao>, melhor: &mut f32) {
+        if id_estacao == objetivo {
+            *melhor = melhor.min(decorrido);
+            return;
+        }
+        for conexao in &grafo.lista_adjacencia[id_estacao] {
+            if visitadas.contains(&conexao.para_estacao) {
+                continue;
+            }
+            let mut tempo = decorrido;
+            if linha != Some(conexao.linha) {
+                if linha.is_some() {
+                    tempo += grafo.tempo_baldeacao_em(id_estacao);
+                }
+                tempo += consulta.espera_minutos(conexao.linha, id_estacao, tempo);
+            }
+            visitadas.push(conexao.para_estacao);
+            visitar(grafo, consulta, (conexao.para_estacao, Some(conexao.linha)), objetivo, tempo + conexao.tempo_minutos, visitadas, melhor);
+            visitadas.pop();
+        }
+    }
+
+    let mut melhor = f32::INFINITY;
+    visitar(grafo, consulta, (inicio, linha), objetivo, 0.0, &mut vec![inicio], &mut melhor);
+    melhor
+}
+
+#[test]
+fn proxima_partida_por_intervalo_faixa_e_partidas_explicitas() {
+    let grafo = rede_de_exemplo();
+    let documento = r#"
+        [[linhas]]
+        linha = "Azul"
+        primeiro_trem = "05:30"
+        ultimo_trem = "00:40"
+        intervalo_minutos = 10
+        faixas = [{ inicio = "07:00", fim = "09:00", intervalo_minutos = 4 }]
+
+        [[linhas]]
+        linha = "4"
+        partidas = ["06:00", "06:45", "07:30"]
+        partidas_por_estacao = [{ estacao = "E14", horarios = ["06:10", "23:50"] }]
+    "#;
+    let (quadro, avisos) = QuadroHorarios::de_texto(&grafo, documento, FormatoArquivoRede::Toml, "horarios.toml", ModoCarregamento::Estrito).unwrap();
+    assert!(avisos.is_empty());
+    let (azul, verde, amarela) = (grafo.obter_id_linha("Azul").unwrap(), grafo.obter_id_linha("Verde").unwrap(), grafo.obter_id_linha("Amarela").unwrap());
+    let proxima = |linha, estacao: &str, instante: &str| formatar_horario(quadro.proxima_partida(linha, grafo.obter_id_estacao(estacao).unwrap(), horario(instante)));
+
+    // Antes do primeiro trem, intervalo padrão, faixa de pico e serviço depois da meia-noite
+    assert_eq!(proxima(azul, "E6", "04:00"), "05:30");
+    assert_eq!(proxima(azul, "E6", "05:31"), "05:40");
+    assert_eq!(proxima(azul, "E6", "06:55"), "07:00");
+    assert_eq!(proxima(azul, "E6", "07:01"), "07:04");
+    assert_eq!(proxima(azul, "E6", "08:58"), "09:00");
+    assert_eq!(proxima(azul, "E6", "00:25"), "00:30");
+    // Depois do último trem, o primeiro do dia seguinte
+    assert_eq!(proxima(azul, "E6", "00:41"), "05:30");
+    assert_eq!(proxima(azul, "E6", "24:41"), "05:30 (+1)");
+    // Partidas explícitas: as da estação têm precedência sobre as da linha
+    assert_eq!(proxima(verde, "E13", "06:00"), "06:00");
+    assert_eq!(proxima(verde, "E13", "06:01"), "06:45");
+    assert_eq!(proxima(verde, "E13", "08:00"), "06:00 (+1)");
+    assert_eq!(proxima(verde, "E14", "06:11"), "23:50");
+    // Linha sem serviço definido: sem espera
+    assert_eq!(proxima(amarela, "E2", "03:17"), "03:17");
+}
+
+#[test]
+fn quadro_vazio_so_acrescenta_os_horarios() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+    let consulta = ConsultaHorarios { quadro: Arc::new(QuadroHorarios::novo()), horario_partida: horario("08:00") };
+
+    let caminho = rota_com_horario(&grafo, consulta, inicio, None, objetivo);
+
+    assert!((caminho.tempo_total_minutos - 61.6).abs() < 0.01);
+    assert_eq!(caminho.espera_total_minutos(), 0.0);
+    assert_eq!(caminho.horario_partida, Some(480.0));
+    let horarios: Vec<(String, String)> = caminho.horarios_trechos.iter()
+        .map(|trecho| (formatar_horario(trecho.partida), formatar_horario(trecho.chegada)))
+        .collect();
+    // A baldeação em Gare de Lyon fica entre a chegada (08:32) e a partida seguinte (08:36)
+    assert_eq!(horarios, [("08:00", "08:06"), ("08:06", "08:32"), ("08:36", "09:02")].map(|(a, b)| (a.to_string(), b.to_string())));
+}
+
+#[test]
+fn busca_com_horario_inclui_as_esperas_e_encontra_a_chegada_mais_cedo() {
+    let grafo = rede_de_exemplo();
+    let quadro = quadro_de_exemplo(&grafo);
+    let linhas = linhas_de_partida(&grafo);
+
+    for partida in ["05:00", "08:01", "23:45"] {
+        let consulta = ConsultaHorarios { quadro: Arc::clone(&quadro), horario_partida: horario(partida) };
+        for inicio in 0..grafo.numero_estacoes() {
+            for objetivo in (0..grafo.numero_estacoes()).filter(|&objetivo| objetivo != inicio) {
+                for &linha in &linhas {
+                    let caminho = rota_com_horario(&grafo, consulta.clone(), inicio, linha, objetivo);
+                    let esperado = menor_tempo_por_forca_bruta(&grafo, &consulta, inicio, linha, objetivo);
+
+                    assert!((caminho.tempo_total_minutos - esperado).abs() < 0.01,
+                        "{} E{} -> E{} (linha {:?}): {} != {}", partida, inicio + 1, objetivo + 1, linha, caminho.tempo_total_minutos, esperado);
+                    let ultimo = caminho.horarios_trechos.last().unwrap();
+                    assert!((ultimo.chegada - (consulta.horario_partida + caminho.tempo_total_minutos)).abs() < 0.01);
+                    for par in caminho.horarios_trechos.windows(2) {
+                        assert!(par[1].partida >= par[0].chegada);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn espera_pelo_trem_na_origem_e_na_baldeacao() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+    let consulta = ConsultaHorarios { quadro: quadro_de_exemplo(&grafo), horario_partida: horario("06:01") };
+
+    let caminho = rota_com_horario(&grafo, consulta, inicio, None, objetivo);
+
+    // Azul a cada 5 min desde 05:30: o próximo trem sai 06:05; a Verde, a cada 10 min desde 06:00
+    let primeiro = caminho.horarios_trechos[0];
+    assert_eq!((formatar_horario(primeiro.partida), primeiro.espera_minutos.round()), ("06:05".to_string(), 4.0));
+    let esperas: f32 = caminho.horarios_trechos.iter().map(|trecho| trecho.espera_minutos).sum();
+    assert!(esperas > primeiro.espera_minutos);
+    assert!((caminho.espera_total_minutos() - esperas).abs() < 0.01);
+    assert!(caminho.tempo_total_minutos > 61.6 + esperas - 0.01);
+}
+
+#[test]
+fn horarios_invalidos_viram_avisos() {
+    let grafo = rede_de_exemplo();
+    let documento = r#"{"linhas": [
+        {"linha": "Laranja", "intervalo_minutos": 5},
+        {"linha": "Azul", "primeiro_trem": "25h", "intervalo_minutos": -2,
+         "partidas_por_estacao": [{"estacao": "E99", "horarios": ["07:00"]}]}
+    ]}"#;
+
+    let (quadro, avisos) = QuadroHorarios::de_texto(&grafo, documento, FormatoArquivoRede::Json, "horarios.json", ModoCarregamento::Tolerante).unwrap();
+    assert_eq!(avisos.len(), 4);
+    assert!(matches!(&avisos[0], AvisoCarregamento::ValorInvalido { valor, .. } if valor == "Laranja"));
+    assert!(matches!(&avisos[3], AvisoCarregamento::EstacaoDesconhecida { codigo, .. } if codigo == "E99"));
+    assert!(quadro.servico(grafo.obter_id_linha("Azul").unwrap()).is_some());
+
+    assert!(QuadroHorarios::de_texto(&grafo, documento, FormatoArquivoRede::Json, "horarios.json", ModoCarregamento::Estrito).is_err());
+    assert_eq!(interpretar_horario("07:30:30"), Some(450.5));
+    assert_eq!(interpretar_horario("07:60"), None);
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use common::{rede_de_exemplo, rota_a_estrela};
+
+#[test]
+fn rota_de_vincennes_a_place_d_italie_na_rede_de_exemplo() {
+    let grafo = rede_de_exemplo();
+    let inicio = grafo.obter_id_estacao("E6").unwrap();
+    let objetivo = grafo.obter_id_estacao("E13").unwrap();
+
+    let caminho = rota_a_estrela(&grafo, inicio, None, objetivo).expect("a rede de exemplo tem caminho de E6 a E13");
+
+    let estacoes: Vec<_> = caminho.estacoes_do_caminho.iter().map(|&(id, _)| id).collect();
+    assert_eq!(estacoes, vec![5, 4, 3, 12]);
+    assert_eq!(caminho.baldeacoes, 1);
+    assert!((caminho.tempo_total_minutos - 61.6).abs() < 0.01);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use metro_paris_astar::algoritmo_a_estrela::EstadoBusca;
+use metro_paris_astar::grafo_metro::{GrafoMetro, IdEstacao, IdLinha};
+use metro_paris_astar::rotas_alternativas::buscar_rotas_alternativas;
+use common::{linhas_de_partida, rede_de_exemplo};
+
+/// Custos de todas as rotas sem estação repetida, por busca em profundidade (a rede de exemplo é pequena)
+fn custos_de_todas_as_rotas(grafo: &GrafoMetro, inicio: IdEstacao, linha: Option<IdLinha>, objetivo: IdEstacao) -> Vec<f32> {
+    fn visitar(grafo: &GrafoMetro, (id_estacao, linha): EstadoBusca, objetivo: IdEstacao, custo: f32,
+               visitadas: &mut Vec<IdEstacao>, custos: &mut Vec<f32>) {
+        if id_estacao == objetivo {
+            custos.push(custo);
+            return;
+        }
+        for conexao in &grafo.lista_adjacencia[id_estacao] {
+            if visitadas.contains(&conexao.para_estacao) {
+                continue;
+            }
+            let baldeacao = if linha.is_some_and(|linha| linha != conexao.linha) { grafo.tempo_baldeacao_em(id_estacao) } else { 0.0 };
+            visitadas.push(conexao.para_estacao);
+            visitar(grafo, (conexao.para_estacao, Some(conexao.linha)), objetivo, custo + conexao.tempo_minutos + baldeacao, visitadas, custos);
+            visitadas.pop();
+        }
+    }
+
+    let mut custos = Vec::new();
+    visitar(grafo, (inicio, linha), objetivo, 0.0, &mut vec![inicio], &mut custos);
+    custos.sort_by(f32::total_cmp);
+    custos
+}
+
+#[test]
+fn yen_encontra_as_k_rotas_mais_curtas_em_ordem() {
+    let grafo = rede_de_exemplo();
+    let k = 4;
+    let linhas = linhas_de_partida(&grafo);
+
+    for inicio in 0..grafo.numero_estacoes() {
+        for objetivo in (0..grafo.numero_estacoes()).filter(|&objetivo| objetivo != inicio) {
+            for &linha in &linhas {
+                let esperados = custos_de_todas_as_rotas(&grafo, inicio, linha, objetivo);
+                let rotas = buscar_rotas_alternativas(&grafo, inicio, linha, objetivo, k);
+
+                assert_eq!(rotas.len(), esperados.len().min(k + 1), "E{} -> E{} (linha {:?})", inicio + 1, objetivo + 1, linha);
+                for (rota, esperado) in rotas.iter().zip(&esperados) {
+                    assert!((rota.caminho.tempo_total_minutos - esperado).abs() < 0.01,
+                        "E{} -> E{} (linha {:?}): {} != {}", inicio + 1, objetivo + 1, linha, rota.caminho.tempo_total_minutos, esperado);
+                    assert!((rota.diferenca_tempo_minutos - (esperado - esperados[0])).abs() < 0.01);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn alternativas_de_vincennes_a_place_d_italie() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E6").unwrap(), grafo.obter_id_estacao("E13").unwrap());
+
+    let rotas = buscar_rotas_alternativas(&grafo, inicio, None, objetivo, 3);
+
+    assert_eq!(rotas.len(), 4);
+    assert!((rotas[0].caminho.tempo_total_minutos - 61.6).abs() < 0.01);
+    assert_eq!((rotas[0].diferenca_tempo_minutos, rotas[0].diferenca_baldeacoes), (0.0, 0));
+    // Segue na linha Azul até Châtelet e troca para a Vermelha, em vez de trocar em Gare de Lyon
+    let estacoes: Vec<IdEstacao> = rotas[1].caminho.estacoes_do_caminho.iter().map(|&(id, _)| id).collect();
+    assert_eq!(estacoes, vec![5, 4, 3, 2, 12]);
+    assert!((rotas[1].diferenca_tempo_minutos - 24.4).abs() < 0.01);
+    assert_eq!(rotas[1].diferenca_baldeacoes, 0);
+    for par in rotas.windows(2) {
+        assert!(par[1].caminho.tempo_total_minutos >= par[0].caminho.tempo_total_minutos);
+    }
+}
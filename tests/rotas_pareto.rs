@@ -0,0 +1,45 @@
+mod common;
+
+use metro_paris_astar::busca_pareto::buscar_rotas_pareto;
+use common::{linhas_de_partida, rede_de_exemplo, rota_a_estrela, tempo_do_itinerario};
+
+#[test]
+fn conjunto_de_pareto_comeca_pela_rota_do_a_estrela_e_troca_tempo_por_baldeacoes() {
+    let grafo = rede_de_exemplo();
+    let linhas = linhas_de_partida(&grafo);
+
+    for inicio in 0..grafo.numero_estacoes() {
+        for objetivo in 0..grafo.numero_estacoes() {
+            for &linha in &linhas {
+                let rotas = buscar_rotas_pareto(&grafo, inicio, linha, objetivo).rotas;
+                let mais_rapida = rota_a_estrela(&grafo, inicio, linha, objetivo).expect("a rede de exemplo é conexa");
+
+                assert!(!rotas.is_empty());
+                assert!((rotas[0].tempo_total_minutos - mais_rapida.tempo_total_minutos).abs() < 0.01,
+                    "E{} -> E{} (linha {:?})", inicio + 1, objetivo + 1, linha);
+                assert!(rotas[0].baldeacoes <= mais_rapida.baldeacoes);
+                for par in rotas.windows(2) {
+                    assert!(par[1].tempo_total_minutos > par[0].tempo_total_minutos);
+                    assert!(par[1].baldeacoes < par[0].baldeacoes);
+                }
+                for rota in &rotas {
+                    assert!((tempo_do_itinerario(&grafo, rota) - rota.tempo_total_minutos).abs() < 0.01);
+                    assert_eq!(rota.estacoes_do_caminho.first().map(|&(id, _)| id), Some(inicio));
+                    assert_eq!(rota.estacoes_do_caminho.last().map(|&(id, _)| id), Some(objetivo));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn menos_baldeacoes_custa_mais_tempo() {
+    let grafo = rede_de_exemplo();
+    let (inicio, objetivo) = (grafo.obter_id_estacao("E11").unwrap(), grafo.obter_id_estacao("E7").unwrap());
+
+    let opcoes: Vec<(f32, u32)> = buscar_rotas_pareto(&grafo, inicio, None, objetivo).rotas.iter()
+        .map(|rota| ((rota.tempo_total_minutos * 10.0).round() / 10.0, rota.baldeacoes))
+        .collect();
+
+    assert_eq!(opcoes, vec![(94.6, 2), (112.4, 1)]);
+}
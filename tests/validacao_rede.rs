@@ -0,0 +1,66 @@
+mod common;
+
+use metro_paris_astar::grafo_metro::{Conexao, GrafoMetro, IdEstacao};
+use metro_paris_astar::validacao_rede::{validar_rede, ProblemaRede};
+use common::rede_de_exemplo;
+
+/// A, B e C ligadas com defeitos em cada par; D só tem um laço para ela mesma
+fn grafo_com_defeitos() -> GrafoMetro {
+    let mut grafo = GrafoMetro::novo();
+    let [a, b, c, d] = ["A", "B", "C", "D"].map(|codigo| grafo.adicionar_estacao(codigo));
+    let l1 = grafo.adicionar_linha("L1", "Um", [200, 0, 0]);
+    let l2 = grafo.adicionar_linha("L2", "Dois", [0, 0, 200]);
+    let linha_inexistente = 7;
+
+    let mut conectar = |de: IdEstacao, para, linha, distancia_km| {
+        grafo.lista_adjacencia[de].push(Conexao { para_estacao: para, linha, distancia_km, tempo_minutos: 2.0 });
+    };
+    conectar(a, b, l1, 1.0);
+    conectar(a, b, linha_inexistente, 1.0);
+    conectar(b, a, l1, 1.5);
+    conectar(b, c, l1, 1.0);
+    conectar(c, b, l2, 1.0);
+    conectar(c, a, l1, 1.0);
+    conectar(d, d, l1, 0.0);
+
+    // Tabela heurística assimétrica entre A e B e que superestima (muito) o tempo de A até C
+    grafo.definir_distancia_heuristica(a, b, 0.1);
+    grafo.definir_distancia_heuristica(b, a, 0.2);
+    grafo.definir_distancia_heuristica(a, c, 100.0);
+    grafo
+}
+
+#[test]
+fn validador_encontra_cada_problema_do_grafo() {
+    let grafo = grafo_com_defeitos();
+    let texto = |codigo: &str| codigo.to_string();
+
+    let problemas = validar_rede(&grafo).problemas;
+
+    assert_eq!(problemas[..6], [
+        ProblemaRede::DistanciaAssimetrica { estacao_a: texto("A"), estacao_b: texto("B"), linha: texto("L1"), ida_km: 1.0, volta_km: 1.5 },
+        ProblemaRede::LinhaInexistente { origem: texto("A"), destino: texto("B"), id_linha: 7 },
+        ProblemaRede::LinhaAssimetrica { estacao_a: texto("B"), estacao_b: texto("C"), linha_ida: texto("L1"), linhas_volta: vec![texto("L2")] },
+        ProblemaRede::ConexaoSemVolta { origem: texto("C"), destino: texto("A"), linha: texto("L1") },
+        ProblemaRede::LacoProprio { estacao: texto("D"), linha: texto("L1") },
+        ProblemaRede::HeuristicaAssimetrica { estacao_a: texto("A"), estacao_b: texto("B"), ida_km: 0.1, volta_km: 0.2 },
+    ]);
+    // De A a C o menor tempo real é A -> B -> C pela L1, sem baldeação
+    assert_eq!(problemas[6], ProblemaRede::EstacoesInalcancaveis { origem: texto("A"), destinos: vec![texto("D")] });
+    assert!(matches!(&problemas[7], ProblemaRede::HeuristicaInadmissivel { origem, destino, heuristica_minutos, tempo_real_minutos }
+        if origem == "A" && destino == "C" && *tempo_real_minutos == 4.0 && *heuristica_minutos > 100.0));
+    assert_eq!(problemas[8..], [
+        ProblemaRede::EstacoesInalcancaveis { origem: texto("B"), destinos: vec![texto("D")] },
+        ProblemaRede::EstacoesInalcancaveis { origem: texto("C"), destinos: vec![texto("D")] },
+        ProblemaRede::EstacoesInalcancaveis { origem: texto("D"), destinos: vec![texto("A"), texto("B"), texto("C")] },
+    ]);
+}
+
+#[test]
+fn rede_de_exemplo_so_tem_a_conexao_sem_volta_da_linha_verde() {
+    let relatorio = validar_rede(&rede_de_exemplo());
+
+    // A tabela de conexões traz E8 -> E4 na linha 4 sem distância real, então só a ida E4 -> E8 é carregada
+    assert_eq!(relatorio.problemas, [ProblemaRede::ConexaoSemVolta { origem: "E4".to_string(), destino: "E8".to_string(), linha: "4".to_string() }]);
+    assert!(!relatorio.esta_valida());
+}